@@ -0,0 +1,129 @@
+use std::ops::Range;
+
+use beamterm_unicode::{is_double_width, is_emoji};
+
+use crate::{GlyphLookup, GridModel, SelectionMode, gl::CellQuery, select};
+
+/// Text of a run of viewport cells, mapping each character back to the cells
+/// it covers.
+///
+/// The right halves of double-width glyphs are folded into the character of
+/// their left half, so patterns match across wide characters.
+pub(crate) struct CellText {
+    /// Concatenated symbols of the cells.
+    pub(crate) text: String,
+    /// Byte offset of each character in `text`, with the first and last
+    /// viewport cell index it covers.
+    chars: Vec<(usize, usize, usize)>,
+    /// Number of columns of the grid.
+    cols: usize,
+}
+
+impl CellText {
+    /// Collects the text of the viewport cells in `cells`.
+    pub(crate) fn new<A: GlyphLookup>(grid: &GridModel<A>, cells: Range<usize>) -> Self {
+        let cols = grid.terminal_size().cols as usize;
+        let mut text = String::new();
+        let mut chars: Vec<(usize, usize, usize)> = Vec::new();
+        let mut wide_glyph: Option<u16> = None;
+
+        for idx in cells {
+            let glyph_id = grid.viewport_glyph_id(idx);
+
+            // right half of the preceding double-width glyph
+            if let Some(left) = wide_glyph.take()
+                && glyph_id == left.wrapping_add(1)
+                && let Some(last) = chars.last_mut()
+            {
+                last.2 = idx;
+                continue;
+            }
+
+            let symbol = grid.viewport_symbol(idx).unwrap_or_default();
+            let symbol = if symbol.is_empty() { " " } else { symbol.as_str() };
+            if is_double_width(symbol) || is_emoji(symbol) {
+                wide_glyph = Some(glyph_id);
+            }
+
+            // multi-char graphemes map all their chars to the same cell
+            for (offset, _) in symbol.char_indices() {
+                chars.push((text.len() + offset, idx, idx));
+            }
+            text.push_str(symbol);
+        }
+
+        Self { text, chars, cols }
+    }
+
+    /// Returns the first and last viewport cell covered by a byte range of
+    /// the text, or `None` if the range is empty or not on character boundaries.
+    pub(crate) fn cell_range(&self, range: Range<usize>) -> Option<(usize, usize)> {
+        if range.is_empty()
+            || range.end > self.text.len()
+            || !self.text.is_char_boundary(range.start)
+            || !self.text.is_char_boundary(range.end)
+        {
+            return None;
+        }
+
+        let first = self.char_at(range.start)?;
+        let last = self.char_at(range.end - 1)?;
+        Some((first.1, last.2))
+    }
+
+    /// Returns a linear query spanning the cells covered by a byte range of the text.
+    pub(crate) fn query(&self, range: Range<usize>) -> Option<CellQuery> {
+        let (first, last) = self.cell_range(range)?;
+        let position = |idx: usize| ((idx % self.cols) as u16, (idx / self.cols) as u16);
+
+        Some(
+            select(SelectionMode::Linear)
+                .start(position(first))
+                .end(position(last)),
+        )
+    }
+
+    /// Returns the byte offset of the first character covering viewport cell `idx`.
+    pub(crate) fn offset_of_cell(&self, idx: usize) -> Option<usize> {
+        self.chars
+            .iter()
+            .find(|&&(_, first, last)| (first..=last).contains(&idx))
+            .map(|&(offset, _, _)| offset)
+    }
+
+    /// Returns the character containing the byte at `offset`.
+    fn char_at(&self, offset: usize) -> Option<(usize, usize, usize)> {
+        let idx = self
+            .chars
+            .partition_point(|&(start, _, _)| start <= offset);
+        idx.checked_sub(1).map(|idx| self.chars[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, TerminalSize};
+
+    use super::*;
+    use crate::{CellData, StaticGlyphLookup};
+
+    #[test]
+    fn maps_text_back_to_cells() {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        let mut model = GridModel::new(lookup, TerminalSize::new(4, 2));
+        let cells = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        model
+            .update_cells(
+                cells
+                    .iter()
+                    .map(|s| CellData::new_with_style_bits(s, 0, 0xFFFFFF, 0)),
+            )
+            .unwrap();
+
+        let text = CellText::new(&model, 2..7);
+        assert_eq!(text.text, "cdefg");
+        assert_eq!(text.cell_range(1..4), Some((3, 5)));
+        assert_eq!(text.cell_range(2..2), None);
+        assert_eq!(model.get_text(text.query(1..4).unwrap()), "d\nef");
+    }
+}
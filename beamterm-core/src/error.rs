@@ -65,4 +65,14 @@ impl Error {
     pub(crate) fn uniform_location_failed(name: &str) -> Self {
         Self::Resource(format!("Failed to get uniform location: {name}"))
     }
+
+    // Data errors
+    pub(crate) fn invalid_image_data(detail: impl std::fmt::Display) -> Self {
+        Self::Data(format!("Invalid image data: {detail}"))
+    }
+
+    #[cfg(feature = "regex")]
+    pub(crate) fn invalid_search_pattern(detail: impl std::fmt::Display) -> Self {
+        Self::Data(format!("Invalid search pattern: {detail}"))
+    }
 }
@@ -0,0 +1,788 @@
+use std::fmt::Write;
+
+use beamterm_data::{CellSize, Glyph, LineDecoration};
+use beamterm_unicode::{is_double_width, is_emoji};
+use compact_str::CompactString;
+
+use crate::{
+    CellAttributes, CellColor, CellDynamic, GlyphLookup, GridModel, LineAttribute, Palette,
+    SelectionMode, UnderlineStyle, gl::CellQuery, select,
+};
+
+/// Text style of a cell, rebuilt from its [`CellDynamic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CellStyle {
+    fg: CellColor,
+    bg: CellColor,
+    bold: bool,
+    italic: bool,
+    underline: Option<UnderlineStyle>,
+    underline_color: Option<u32>,
+    strikethrough: bool,
+    attributes: CellAttributes,
+}
+
+/// A glyph with its style, covering one or two cells.
+#[derive(Debug, Clone)]
+struct StyledCell {
+    symbol: CompactString,
+    style: CellStyle,
+    /// Number of cells covered by the glyph.
+    width: usize,
+}
+
+impl CellStyle {
+    const DEFAULT: Self = Self {
+        fg: CellColor::DefaultFg,
+        bg: CellColor::DefaultBg,
+        bold: false,
+        italic: false,
+        underline: None,
+        underline_color: None,
+        strikethrough: false,
+        attributes: CellAttributes::empty(),
+    };
+
+    fn of(cell: &CellDynamic) -> Self {
+        let style = cell.get_style();
+        // the bold and italic bits are part of the id of emoji glyphs
+        let is_emoji = cell.glyph_id() & Glyph::EMOJI_FLAG != 0;
+        let underline = style & Glyph::UNDERLINE_FLAG != 0;
+
+        Self {
+            fg: cell.get_fg(),
+            bg: cell.get_bg(),
+            bold: !is_emoji && style & Glyph::BOLD_FLAG != 0,
+            italic: !is_emoji && style & Glyph::ITALIC_FLAG != 0,
+            underline: underline.then(|| cell.get_underline_style()),
+            underline_color: cell.get_underline_color().filter(|_| underline),
+            strikethrough: style & Glyph::STRIKETHROUGH_FLAG != 0,
+            attributes: cell.get_attributes(),
+        }
+    }
+
+    /// Returns the foreground and background as drawn, after applying the
+    /// reverse, dim and hidden attributes.
+    fn resolved_colors(&self, palette: &Palette) -> (u32, u32) {
+        let (mut fg, mut bg) = (palette.resolve(self.fg), palette.resolve(self.bg));
+        if self.attributes.contains(CellAttributes::REVERSE) {
+            (fg, bg) = (bg, fg);
+        }
+        if self.attributes.contains(CellAttributes::DIM) {
+            fg = mix_halfway(fg, bg);
+        }
+        if self.attributes.contains(CellAttributes::HIDDEN) {
+            fg = bg;
+        }
+
+        (fg, bg)
+    }
+}
+
+/// Exports the cells of `selection` as text with ANSI SGR escape sequences,
+/// for pasting into another terminal.
+///
+/// Each styled run starts with a full SGR reset followed by its attributes,
+/// and every line ends unstyled. Palette colors are written as indexed colors,
+/// so the receiving terminal applies its own theme; `palette` is only used for
+/// default colors in swapped roles, which have no SGR equivalent.
+#[must_use]
+pub fn export_ansi<A: GlyphLookup>(
+    grid: &GridModel<A>,
+    selection: CellQuery,
+    palette: &Palette,
+) -> String {
+    let mut ansi = String::new();
+
+    for (row, line) in styled_lines(grid, selection).iter().enumerate() {
+        if row > 0 {
+            ansi.push('\n');
+        }
+
+        let mut current = CellStyle::DEFAULT;
+        for cell in line {
+            if cell.style != current {
+                write_sgr(&mut ansi, &cell.style, palette);
+                current = cell.style;
+            }
+            ansi.push_str(&cell.symbol);
+        }
+
+        if current != CellStyle::DEFAULT {
+            ansi.push_str("\x1b[0m");
+        }
+    }
+
+    ansi
+}
+
+/// Exports the cells of `selection` as an HTML `<pre>` fragment with inline
+/// styles, for pasting into rich text editors.
+///
+/// Colors are resolved against `palette`, with reverse, dim and hidden cells
+/// drawn as on screen. Blinking is not exported.
+#[must_use]
+pub fn export_html<A: GlyphLookup>(
+    grid: &GridModel<A>,
+    selection: CellQuery,
+    palette: &Palette,
+) -> String {
+    let (default_fg, default_bg) = (palette.default_fg(), palette.default_bg());
+    let mut html = format!(
+        "<pre style=\"font-family:monospace;color:{};background-color:{}\">",
+        css_color(default_fg),
+        css_color(default_bg)
+    );
+
+    for (row, line) in styled_lines(grid, selection).iter().enumerate() {
+        if row > 0 {
+            html.push('\n');
+        }
+
+        for run in line.chunk_by(|a, b| a.style == b.style) {
+            let css = css_style(&run[0].style, palette, default_fg, default_bg);
+            if !css.is_empty() {
+                let _ = write!(html, "<span style=\"{css}\">");
+            }
+            for cell in run {
+                escape_html(&mut html, &cell.symbol);
+            }
+            if !css.is_empty() {
+                html.push_str("</span>");
+            }
+        }
+    }
+
+    html.push_str("</pre>");
+    html
+}
+
+/// Layout and font of an SVG image exported with [`export_svg`].
+///
+/// # Example
+/// ```
+/// use beamterm_core::{CellSize, SvgOptions};
+///
+/// let options = SvgOptions::new(CellSize::new(9, 18))
+///     .with_font_family("'JetBrains Mono', monospace")
+///     .with_font_size(14.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Size of a cell in pixels.
+    pub cell_size: CellSize,
+    /// Font size of the text in pixels.
+    pub font_size: f32,
+    /// CSS `font-family` list of the text.
+    pub font_family: CompactString,
+    /// Position and thickness of underlines, relative to the cell height.
+    pub underline: LineDecoration,
+    /// Position and thickness of strikethrough lines, relative to the cell height.
+    pub strikethrough: LineDecoration,
+}
+
+impl SvgOptions {
+    /// Creates options for cells of `cell_size`, with a generic monospace
+    /// font sized to the cell height and typical decoration metrics.
+    ///
+    /// [`TerminalGrid::svg_options`](crate::TerminalGrid::svg_options) takes
+    /// the metrics from the font atlas instead.
+    #[must_use]
+    pub fn new(cell_size: CellSize) -> Self {
+        Self {
+            cell_size,
+            font_size: cell_size.height as f32 * 0.8,
+            font_family: CompactString::const_new("monospace"),
+            underline: LineDecoration::new(0.85, 0.05),
+            strikethrough: LineDecoration::new(0.5, 0.05),
+        }
+    }
+
+    /// Sets the CSS `font-family` list of the text.
+    #[must_use]
+    pub fn with_font_family(mut self, font_family: &str) -> Self {
+        self.font_family = CompactString::new(font_family);
+        self
+    }
+
+    /// Sets the font size of the text in pixels.
+    #[must_use]
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+}
+
+/// Exports the viewport as a scalable SVG image, e.g. for documentation and
+/// bug reports.
+///
+/// The image contains a rect per run of cells sharing a background color,
+/// a text element per run of cells sharing a text style, and the underlines
+/// and strikethrough lines of the runs. Each text run is stretched to the
+/// width of its cells, so the grid stays aligned regardless of the font.
+/// Double-width and double-height rows are scaled like the rendered rows.
+/// Colors are resolved against `palette`; blinking, the cursor and the
+/// selection are not exported.
+#[must_use]
+pub fn export_svg<A: GlyphLookup>(
+    grid: &GridModel<A>,
+    palette: &Palette,
+    options: &SvgOptions,
+) -> String {
+    let size = grid.terminal_size();
+    let (cell_w, cell_h) = (
+        options.cell_size.width as f32,
+        options.cell_size.height as f32,
+    );
+    let (width, height) = (size.cols as f32 * cell_w, size.rows as f32 * cell_h);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        css_color(palette.default_bg())
+    );
+
+    let viewport = select(SelectionMode::Block)
+        .start((0, 0))
+        .end((size.cols - 1, size.rows - 1));
+    let lines = styled_lines(grid, viewport);
+    let line_attributes = grid.viewport_line_attributes();
+
+    // double-height rows only show one half of their scaled content
+    let double_height_rows: Vec<_> = line_attributes
+        .iter()
+        .enumerate()
+        .filter(|(_, line_attribute)| line_attribute.is_double_height())
+        .collect();
+    if !double_height_rows.is_empty() {
+        svg.push_str("<defs>\n");
+        for (row, _) in double_height_rows {
+            let _ = writeln!(
+                svg,
+                "<clipPath id=\"row-{row}\"><rect y=\"{}\" width=\"{width}\" \
+                 height=\"{cell_h}\"/></clipPath>",
+                row as f32 * cell_h
+            );
+        }
+        svg.push_str("</defs>\n");
+    }
+
+    // backgrounds first, so that no text is covered by the next cell's background
+    for (row, line) in lines.iter().enumerate() {
+        let y = row as f32 * cell_h;
+        let group_end = open_line_group(&mut svg, line_attributes[row], row, y, cell_h);
+        let mut col = 0;
+        for run in line.chunk_by(|a, b| {
+            a.style.resolved_colors(palette).1 == b.style.resolved_colors(palette).1
+        }) {
+            let cells: usize = run.iter().map(|cell| cell.width).sum();
+            let (_, bg) = run[0].style.resolved_colors(palette);
+            if bg != palette.default_bg() {
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{y}\" width=\"{}\" height=\"{cell_h}\" fill=\"{}\"/>",
+                    col as f32 * cell_w,
+                    cells as f32 * cell_w,
+                    css_color(bg)
+                );
+            }
+            col += cells;
+        }
+        svg.push_str(group_end);
+    }
+
+    let mut font_family = String::new();
+    escape_html(&mut font_family, &options.font_family);
+    let _ = writeln!(
+        svg,
+        "<g font-family=\"{font_family}\" font-size=\"{}\" dominant-baseline=\"central\" \
+         xml:space=\"preserve\">",
+        options.font_size
+    );
+
+    for (row, line) in lines.iter().enumerate() {
+        let y = row as f32 * cell_h;
+        let group_end = open_line_group(&mut svg, line_attributes[row], row, y, cell_h);
+        let mut col = 0;
+        for run in line.chunk_by(|a, b| a.style == b.style) {
+            let cells: usize = run.iter().map(|cell| cell.width).sum();
+            let x = col as f32 * cell_w;
+            write_svg_run(
+                &mut svg,
+                run,
+                (x, y),
+                cells as f32 * cell_w,
+                palette,
+                options,
+            );
+            col += cells;
+        }
+        svg.push_str(group_end);
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+/// Opens a group scaling the content of the row at `y` by its DEC line
+/// attribute, and returns the tags closing it. Double-height rows show the
+/// top or bottom half of their content, clipped to the row.
+fn open_line_group(
+    svg: &mut String,
+    line_attribute: LineAttribute,
+    row: usize,
+    y: f32,
+    cell_h: f32,
+) -> &'static str {
+    let half_offset = match line_attribute {
+        LineAttribute::Single => return "",
+        LineAttribute::DoubleWidth => {
+            svg.push_str("<g transform=\"scale(2 1)\">\n");
+            return "</g>\n";
+        },
+        LineAttribute::DoubleHeightTop => 0.0,
+        LineAttribute::DoubleHeightBottom => cell_h / 2.0,
+    };
+
+    let _ = writeln!(
+        svg,
+        "<g clip-path=\"url(#row-{row})\"><g transform=\"translate(0 {y}) scale(2) \
+         translate(0 {})\">",
+        -(y + half_offset)
+    );
+    "</g></g>\n"
+}
+
+/// Writes the text and decoration lines of a run of equally styled cells
+/// at `origin`, spanning `width` pixels.
+fn write_svg_run(
+    svg: &mut String,
+    run: &[StyledCell],
+    (x, y): (f32, f32),
+    width: f32,
+    palette: &Palette,
+    options: &SvgOptions,
+) {
+    let style = &run[0].style;
+    let (fg, _) = style.resolved_colors(palette);
+    let cell_h = options.cell_size.height as f32;
+    let fill = css_color(fg);
+
+    let has_text = run
+        .iter()
+        .any(|cell| !cell.symbol.trim().is_empty());
+    if has_text && !style.attributes.contains(CellAttributes::HIDDEN) {
+        let _ = write!(
+            svg,
+            "<text x=\"{x}\" y=\"{}\" fill=\"{fill}\" textLength=\"{width}\" \
+             lengthAdjust=\"spacingAndGlyphs\"",
+            y + cell_h / 2.0
+        );
+        if style.bold {
+            svg.push_str(" font-weight=\"bold\"");
+        }
+        if style.italic {
+            svg.push_str(" font-style=\"italic\"");
+        }
+        svg.push('>');
+        for cell in run {
+            escape_html(svg, &cell.symbol);
+        }
+        svg.push_str("</text>\n");
+    }
+
+    let line =
+        |svg: &mut String, decoration: LineDecoration, offset: f32, color: &str, dash: &str| {
+            let line_y = y + (decoration.position() + offset) * cell_h;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x}\" y1=\"{line_y}\" x2=\"{}\" y2=\"{line_y}\" stroke=\"{color}\" \
+             stroke-width=\"{}\"{dash}/>",
+                x + width,
+                decoration.thickness() * cell_h
+            );
+        };
+
+    if let Some(underline) = style.underline {
+        let color = css_color(style.underline_color.unwrap_or(fg));
+        let decoration = options.underline;
+        let thickness = decoration.thickness();
+        let cell_w = options.cell_size.width as f32;
+
+        match underline {
+            UnderlineStyle::Double => {
+                line(svg, decoration, -1.5 * thickness, &color, "");
+                line(svg, decoration, 1.5 * thickness, &color, "");
+            },
+            UnderlineStyle::Curly => {
+                // one wave per cell, as drawn by the cell shader
+                let amplitude = thickness * cell_h;
+                let line_y = y + decoration.position() * cell_h;
+                let _ = write!(
+                    svg,
+                    "<path d=\"M{x},{line_y} q{},{} {},0",
+                    cell_w / 4.0,
+                    -2.0 * amplitude,
+                    cell_w / 2.0
+                );
+                for _ in 1..(2.0 * width / cell_w).round() as usize {
+                    let _ = write!(svg, " t{},0", cell_w / 2.0);
+                }
+                let _ = writeln!(
+                    svg,
+                    "\" fill=\"none\" stroke=\"{color}\" stroke-width=\"{amplitude}\"/>"
+                );
+            },
+            UnderlineStyle::Dotted => {
+                let dash = format!(" stroke-dasharray=\"{0} {0}\"", cell_w / 8.0);
+                line(svg, decoration, 0.0, &color, &dash);
+            },
+            UnderlineStyle::Dashed => {
+                let dash = format!(
+                    " stroke-dasharray=\"{} {}\"",
+                    cell_w * 3.0 / 8.0,
+                    cell_w / 8.0
+                );
+                line(svg, decoration, 0.0, &color, &dash);
+            },
+            UnderlineStyle::Single => line(svg, decoration, 0.0, &color, ""),
+        }
+    }
+
+    if style.strikethrough {
+        line(svg, options.strikethrough, 0.0, &fill, "");
+    }
+}
+
+/// Collects the symbols and styles of the selected cells, one line per row.
+///
+/// The right halves of double-width glyphs are folded into their left half,
+/// and trailing whitespace is trimmed if the query asks for it.
+fn styled_lines<A: GlyphLookup>(grid: &GridModel<A>, selection: CellQuery) -> Vec<Vec<StyledCell>> {
+    if selection.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines: Vec<Vec<StyledCell>> = vec![Vec::new()];
+    let mut wide_glyph: Option<u16> = None;
+
+    for (idx, newline_after) in grid.cell_iter(selection) {
+        let cell = grid.viewport_cell(idx);
+        let glyph_id = cell.glyph_id();
+
+        let Some(line) = lines.last_mut() else { break };
+
+        // right half of the preceding double-width glyph
+        let is_right_half = wide_glyph
+            .take()
+            .is_some_and(|left| glyph_id == left.wrapping_add(1));
+        if is_right_half && let Some(left) = line.last_mut() {
+            left.width += 1;
+        } else {
+            let symbol = grid
+                .viewport_symbol(idx)
+                .filter(|symbol| !symbol.is_empty())
+                .unwrap_or_else(|| CompactString::const_new(" "));
+            if is_double_width(&symbol) || is_emoji(&symbol) {
+                wide_glyph = Some(glyph_id);
+            }
+            line.push(StyledCell { symbol, style: CellStyle::of(&cell), width: 1 });
+        }
+
+        if newline_after {
+            wide_glyph = None;
+            lines.push(Vec::new());
+        }
+    }
+
+    if selection.trim_trailing_whitespace {
+        for line in &mut lines {
+            while line
+                .last()
+                .is_some_and(|cell| cell.symbol.trim().is_empty())
+            {
+                line.pop();
+            }
+        }
+    }
+
+    lines
+}
+
+/// Writes a full SGR reset followed by the attributes of `style`.
+fn write_sgr(out: &mut String, style: &CellStyle, palette: &Palette) {
+    let mut params = vec![CompactString::const_new("0")];
+    let mut push = |param: &str| params.push(CompactString::new(param));
+
+    if style.bold {
+        push("1");
+    }
+    if style.attributes.contains(CellAttributes::DIM) {
+        push("2");
+    }
+    if style.italic {
+        push("3");
+    }
+    if let Some(underline) = style.underline {
+        push(match underline {
+            UnderlineStyle::Single => "4",
+            UnderlineStyle::Double => "4:2",
+            UnderlineStyle::Curly => "4:3",
+            UnderlineStyle::Dotted => "4:4",
+            UnderlineStyle::Dashed => "4:5",
+        });
+    }
+    if style.attributes.contains(CellAttributes::BLINK) {
+        push("5");
+    }
+    if style.attributes.contains(CellAttributes::REVERSE) {
+        push("7");
+    }
+    if style.attributes.contains(CellAttributes::HIDDEN) {
+        push("8");
+    }
+    if style.strikethrough {
+        push("9");
+    }
+
+    params.extend(sgr_color(style.fg, 30, 90, CellColor::DefaultFg, palette));
+    params.extend(sgr_color(style.bg, 40, 100, CellColor::DefaultBg, palette));
+    if let Some(color) = style.underline_color {
+        params.push(sgr_rgb(58, color));
+    }
+
+    let _ = write!(out, "\x1b[{}m", params.join(";"));
+}
+
+/// Returns the SGR parameter selecting `color`, where `base` and
+/// `bright_base` are the codes of the first normal and bright ANSI colors.
+/// Returns `None` for the default color of the role.
+fn sgr_color(
+    color: CellColor,
+    base: u8,
+    bright_base: u8,
+    default: CellColor,
+    palette: &Palette,
+) -> Option<CompactString> {
+    let extended = base + 8;
+    match color {
+        c if c == default => None,
+        CellColor::Indexed(index @ 0..=7) => Some(CompactString::new((base + index).to_string())),
+        CellColor::Indexed(index @ 8..=15) => {
+            Some(CompactString::new((bright_base + index - 8).to_string()))
+        },
+        CellColor::Indexed(index) => Some(CompactString::new(format!("{extended};5;{index}"))),
+        color => Some(sgr_rgb(extended, palette.resolve(color))),
+    }
+}
+
+fn sgr_rgb(code: u8, rgb: u32) -> CompactString {
+    let [_, r, g, b] = rgb.to_be_bytes();
+    CompactString::new(format!("{code};2;{r};{g};{b}"))
+}
+
+/// Returns the inline CSS of `style`, leaving out the default colors.
+fn css_style(style: &CellStyle, palette: &Palette, default_fg: u32, default_bg: u32) -> String {
+    let (fg, bg) = style.resolved_colors(palette);
+    let mut css = String::new();
+
+    if fg != default_fg {
+        let _ = write!(css, "color:{};", css_color(fg));
+    }
+    if bg != default_bg {
+        let _ = write!(css, "background-color:{};", css_color(bg));
+    }
+    if style.bold {
+        css.push_str("font-weight:bold;");
+    }
+    if style.italic {
+        css.push_str("font-style:italic;");
+    }
+
+    let lines = match (style.underline.is_some(), style.strikethrough) {
+        (true, true) => "underline line-through",
+        (true, false) => "underline",
+        (false, true) => "line-through",
+        (false, false) => return css,
+    };
+    let _ = write!(css, "text-decoration:{lines}");
+    match style.underline {
+        Some(UnderlineStyle::Double) => css.push_str(" double"),
+        Some(UnderlineStyle::Curly) => css.push_str(" wavy"),
+        Some(UnderlineStyle::Dotted) => css.push_str(" dotted"),
+        Some(UnderlineStyle::Dashed) => css.push_str(" dashed"),
+        _ => {},
+    }
+    if let Some(color) = style.underline_color {
+        let _ = write!(css, " {}", css_color(color));
+    }
+    css.push(';');
+
+    css
+}
+
+fn css_color(rgb: u32) -> CompactString {
+    CompactString::new(format!("#{:06x}", rgb & 0xFFFFFF))
+}
+
+fn escape_html(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            ch => out.push(ch),
+        }
+    }
+}
+
+/// Blends two colors halfway, like dim text in the cell shader.
+fn mix_halfway(a: u32, b: u32) -> u32 {
+    let channel = |shift: u32| (((a >> shift) & 0xFF) + ((b >> shift) & 0xFF)) / 2;
+    (channel(16) << 16) | (channel(8) << 8) | channel(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, TerminalSize};
+
+    use super::*;
+    use crate::{CellData, SelectionMode, StaticGlyphLookup, select};
+
+    fn model_with_cells(cols: u16, rows: u16, cells: &[CellData]) -> GridModel<StaticGlyphLookup> {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        let mut model = GridModel::new(lookup, TerminalSize::new(cols, rows));
+        model.update_cells(cells.iter().copied()).unwrap();
+        model
+    }
+
+    fn cell(symbol: &str) -> CellData<'_> {
+        CellData::new_with_style_bits(symbol, 0, 0, 0)
+            .with_fg(CellColor::DefaultFg)
+            .with_bg(CellColor::DefaultBg)
+    }
+
+    fn all(cols: u16, rows: u16) -> CellQuery {
+        select(SelectionMode::Linear)
+            .start((0, 0))
+            .end((cols - 1, rows - 1))
+    }
+
+    #[test]
+    fn ansi_rebuilds_styles() {
+        let cells = [
+            cell("a"),
+            CellData::new_with_style_bits("b", Glyph::BOLD_FLAG, 0xFF8000, 0)
+                .with_bg(CellColor::Indexed(1)),
+            cell("c")
+                .with_fg(CellColor::Indexed(12))
+                .with_attributes(CellAttributes::REVERSE),
+            CellData::new_with_style_bits("d", Glyph::UNDERLINE_FLAG, 0, 0)
+                .with_fg(CellColor::Indexed(200))
+                .with_bg(CellColor::DefaultBg)
+                .with_underline(UnderlineStyle::Curly),
+            cell("e"),
+            cell("f"),
+        ];
+        let model = model_with_cells(3, 2, &cells);
+
+        assert_eq!(
+            export_ansi(&model, all(3, 2), &Palette::default()),
+            "a\x1b[0;1;38;2;255;128;0;41mb\x1b[0;7;94mc\x1b[0m\n\
+             \x1b[0;4:3;38;5;200md\x1b[0mef"
+        );
+    }
+
+    #[test]
+    fn html_resolves_colors_and_escapes_text() {
+        let cells = [
+            cell("<"),
+            cell("&")
+                .with_fg(CellColor::Indexed(1))
+                .with_attributes(CellAttributes::REVERSE),
+            CellData::new_with_style_bits(
+                ">",
+                Glyph::ITALIC_FLAG | Glyph::STRIKETHROUGH_FLAG,
+                0,
+                0,
+            )
+            .with_fg(CellColor::DefaultFg)
+            .with_bg(CellColor::DefaultBg),
+            cell(" "),
+        ];
+        let model = model_with_cells(4, 1, &cells);
+        let palette = Palette::default();
+        let (fg, bg) = (palette.default_fg(), palette.default_bg());
+        let query = all(4, 1).trim_trailing_whitespace(true);
+
+        assert_eq!(
+            export_html(&model, query, &palette),
+            format!(
+                "<pre style=\"font-family:monospace;color:{};background-color:{}\">&lt;\
+                 <span style=\"color:{};background-color:#cd0000;\">&amp;</span>\
+                 <span style=\"font-style:italic;text-decoration:line-through;\">&gt;</span></pre>",
+                css_color(fg),
+                css_color(bg),
+                css_color(bg),
+            )
+        );
+    }
+
+    #[test]
+    fn double_width_glyphs_are_exported_once() {
+        let cells = [cell("🚀"), cell(" "), cell("x")];
+        let model = model_with_cells(3, 1, &cells);
+
+        assert_eq!(export_ansi(&model, all(3, 1), &Palette::default()), "🚀x");
+    }
+
+    #[test]
+    fn svg_draws_backgrounds_runs_and_decorations() {
+        let cells = [
+            cell("a").with_bg(CellColor::Indexed(1)),
+            cell("b").with_bg(CellColor::Indexed(1)),
+            CellData::new_with_style_bits("<", Glyph::UNDERLINE_FLAG, 0, 0)
+                .with_fg(CellColor::DefaultFg)
+                .with_bg(CellColor::DefaultBg),
+            cell(" "),
+        ];
+        let model = model_with_cells(2, 2, &cells);
+        let options = SvgOptions::new(CellSize::new(10, 20)).with_font_family("\"Fira Code\"");
+
+        let svg = export_svg(&model, &Palette::default(), &options);
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" height=\"40\"")
+        );
+        assert!(
+            svg.contains("<rect x=\"0\" y=\"0\" width=\"20\" height=\"20\" fill=\"#cd0000\"/>")
+        );
+        assert!(svg.contains("font-family=\"&quot;Fira Code&quot;\""));
+        assert!(svg.contains("textLength=\"20\" lengthAdjust=\"spacingAndGlyphs\">ab</text>"));
+        assert!(svg.contains("textLength=\"10\" lengthAdjust=\"spacingAndGlyphs\">&lt;</text>"));
+        assert!(svg.contains("<line x1=\"0\" y1=\"37\" x2=\"10\" y2=\"37\""));
+        assert_eq!(svg.matches("<text").count(), 2);
+    }
+
+    #[test]
+    fn svg_scales_rows_by_their_line_attribute() {
+        let mut model = model_with_cells(2, 2, &[cell("a"), cell("b"), cell("c"), cell("d")]);
+        model.set_line_attribute(0, LineAttribute::DoubleWidth);
+        model.set_line_attribute(1, LineAttribute::DoubleHeightBottom);
+
+        let svg = export_svg(
+            &model,
+            &Palette::default(),
+            &SvgOptions::new(CellSize::new(10, 20)),
+        );
+        assert!(svg.contains("<g transform=\"scale(2 1)\">\n<text x=\"0\""));
+        assert!(svg.contains(
+            "<clipPath id=\"row-1\"><rect y=\"20\" width=\"20\" height=\"20\"/></clipPath>"
+        ));
+        assert!(svg.contains(
+            "<g clip-path=\"url(#row-1)\"><g transform=\"translate(0 20) scale(2) translate(0 -30)\">"
+        ));
+        assert_eq!(svg.matches("<g").count(), svg.matches("</g>").count());
+    }
+}
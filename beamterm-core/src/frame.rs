@@ -0,0 +1,116 @@
+use beamterm_data::{CellSnapshot, FrameDiff, FrameOp};
+
+use crate::{GlyphLookup, GridModel, snapshot::cell_data};
+
+/// Applies the operations of a [`FrameDiff`], e.g. received from a server,
+/// to the live screen of `grid`.
+///
+/// [`TerminalGrid::apply_frame_diff`](crate::TerminalGrid::apply_frame_diff)
+/// additionally scrolls within the GPU buffers instead of re-uploading the
+/// scrolled rows.
+pub fn apply_frame_diff<A: GlyphLookup>(grid: &mut GridModel<A>, diff: &FrameDiff) {
+    for op in &diff.ops {
+        match op {
+            FrameOp::Resize(size) => {
+                grid.resize(*size);
+            },
+            FrameOp::Scroll { top, bottom, lines, fill } => {
+                grid.scroll_region(*top, *bottom, *lines, cell_data(fill));
+            },
+            op => apply_cell_op(grid, op),
+        }
+    }
+}
+
+/// Writes the cells of a [`FrameOp::Cells`] or [`FrameOp::Fill`] operation;
+/// other operations are ignored.
+pub(crate) fn apply_cell_op<A: GlyphLookup>(grid: &mut GridModel<A>, op: &FrameOp) {
+    match op {
+        FrameOp::Cells { index, cells } => update_cells(grid, *index, cells.iter()),
+        FrameOp::Fill { index, count, cell } => {
+            update_cells(grid, *index, std::iter::repeat_n(cell, *count as usize));
+        },
+        FrameOp::Resize(_) | FrameOp::Scroll { .. } => {},
+    }
+}
+
+fn update_cells<'a, A: GlyphLookup>(
+    grid: &mut GridModel<A>,
+    index: u32,
+    cells: impl Iterator<Item = &'a CellSnapshot>,
+) {
+    let index = index as usize;
+    let cells = cells
+        .take(grid.cell_count().saturating_sub(index))
+        .enumerate()
+        .map(|(offset, cell)| (index + offset, cell_data(cell)));
+
+    // infallible; see `update_cells_by_index`
+    let _ = grid.update_cells_by_index(cells);
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, Frame, TerminalSize};
+    use compact_str::CompactString;
+
+    use super::*;
+    use crate::{SelectionMode, StaticGlyphLookup, select, snapshot_grid};
+
+    fn cell(symbol: &str) -> CellSnapshot {
+        CellSnapshot {
+            symbol: CompactString::from(symbol),
+            style_bits: 0,
+            fg: 0xFFFFFF,
+            bg: 0x000000,
+            underline_style: 0,
+            underline_color: None,
+            attributes: 0,
+        }
+    }
+
+    fn frame(cols: u16, rows: u16, text: &str) -> Frame {
+        let mut frame = Frame::new(TerminalSize::new(cols, rows), cell(" "));
+        for (idx, ch) in text.chars().enumerate() {
+            frame.cells[idx] = cell(&ch.to_string());
+        }
+        frame
+    }
+
+    fn text(grid: &GridModel<StaticGlyphLookup>) -> CompactString {
+        let size = grid.terminal_size();
+        grid.get_text(
+            select(SelectionMode::Linear)
+                .start((0, 0))
+                .end((size.cols - 1, size.rows - 1)),
+        )
+    }
+
+    #[test]
+    fn diffs_reproduce_the_server_frames() {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        let mut grid = GridModel::new(lookup, TerminalSize::new(1, 1));
+
+        let frames = [
+            frame(4, 3, "$ ls"),
+            frame(4, 3, "$ lsa.rsb.rs"),
+            frame(4, 3, "a.rsb.rs$   "),
+            frame(4, 3, "a.rsb.rs$ 🚀 "),
+            frame(4, 3, "a.rsb.rs$ x "),
+            frame(5, 2, "=====hello"),
+        ];
+
+        let mut previous = frame(1, 1, "");
+        for next in frames {
+            let diff = FrameDiff::between(&previous, &next);
+            let diff = FrameDiff::from_binary(&diff.to_binary().unwrap()).unwrap();
+            apply_frame_diff(&mut grid, &diff);
+
+            assert_eq!(grid.terminal_size(), next.size);
+            assert_eq!(snapshot_grid(&grid).cells, next.cells);
+            previous = next;
+        }
+
+        assert_eq!(text(&grid), "=====\nhello");
+    }
+}
@@ -158,6 +158,24 @@ pub trait Atlas: sealed::Sealed {
     fn texture_cell_size(&self) -> beamterm_data::CellSize;
 }
 
+/// GL-free glyph resolution, as used by a [`GridModel`](crate::GridModel).
+///
+/// Implemented by [`FontAtlas`] for GPU-backed grids, and by
+/// [`StaticGlyphLookup`](crate::StaticGlyphLookup) for grid models without a
+/// GL context.
+pub trait GlyphLookup {
+    /// Resolves a symbol with the given style bits to its glyph slot.
+    ///
+    /// May mutate internal state (e.g., LRU promotion, missing glyph tracking).
+    fn resolve_glyph_slot(&mut self, key: &str, style_bits: u16) -> Option<GlyphSlot>;
+
+    /// Returns the symbol for the given glyph ID, if it exists
+    fn get_symbol(&self, glyph_id: u16) -> Option<CompactString>;
+
+    /// Returns the ASCII character for the given glyph ID, if it represents an ASCII char.
+    fn get_ascii_char(&self, glyph_id: u16) -> Option<char>;
+}
+
 /// Type-erased wrapper around any [`Atlas`] implementation.
 pub struct FontAtlas {
     inner: Box<dyn Atlas>,
@@ -269,11 +287,6 @@ impl FontAtlas {
         self.inner.emoji_bit()
     }
 
-    pub(crate) fn space_glyph_id(&mut self) -> u16 {
-        self.get_glyph_id(" ", 0x0)
-            .expect("space glyph exists in every font atlas")
-    }
-
     /// Deletes the GPU texture resources associated with this atlas.
     pub fn delete(&self, gl: &glow::Context) {
         self.inner.delete(gl);
@@ -306,6 +319,20 @@ impl FontAtlas {
     }
 }
 
+impl GlyphLookup for FontAtlas {
+    fn resolve_glyph_slot(&mut self, key: &str, style_bits: u16) -> Option<GlyphSlot> {
+        self.inner.resolve_glyph_slot(key, style_bits)
+    }
+
+    fn get_symbol(&self, glyph_id: u16) -> Option<CompactString> {
+        self.inner.get_symbol(glyph_id)
+    }
+
+    fn get_ascii_char(&self, glyph_id: u16) -> Option<char> {
+        self.inner.get_ascii_char(glyph_id)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
 /// Classifies a glyph's texture slot by width category.
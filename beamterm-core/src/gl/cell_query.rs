@@ -1,24 +1,40 @@
+use std::ops::Range;
+
 use compact_str::{CompactString, CompactStringExt};
 
-use crate::gl::TerminalGrid;
+use crate::{
+    Matcher,
+    cell_text::CellText,
+    gl::{GlyphLookup, GridModel},
+};
+
+/// Characters ending a word in [`SelectionMode::Word`] selections, unless
+/// configured otherwise with [`CellQuery::word_separators`]. Whitespace always
+/// separates words.
+pub const DEFAULT_WORD_SEPARATORS: &str = ",│`|:\"'()[]{}<>";
+
+/// Characters delimiting quoted strings in [`SelectionMode::Word`] selections.
+const QUOTES: &str = "\"'`";
 
 /// Configuration for querying and extracting text from terminal cells.
 ///
 /// Defines the selection mode, coordinate range, and text processing options
 /// for extracting content from the terminal grid.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct CellQuery {
     pub(crate) mode: SelectionMode,
     pub(crate) start: Option<(u16, u16)>,
     pub(crate) end: Option<(u16, u16)>,
     pub(crate) trim_trailing_whitespace: bool,
+    /// Characters separating words, or `None` for [`DEFAULT_WORD_SEPARATORS`].
+    pub(crate) word_separators: Option<&'static str>,
     /// Hash of the selected content when selection was completed.
     /// Used to detect if underlying content has changed.
     pub(crate) content_hash: Option<u64>,
 }
 
 /// Defines how cells are selected in the terminal grid.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum SelectionMode {
     /// Rectangular selection of cells.
@@ -31,6 +47,24 @@ pub enum SelectionMode {
     /// Selects cells from start to end following line wrapping, similar to
     /// standard text selection in terminals.
     Linear,
+    /// Linear selection of whole words, e.g. on double-click.
+    ///
+    /// The start and end points are expanded to the words containing them.
+    /// Clicking a URL selects all of it, and clicking a quote selects the
+    /// quoted string, including its quotes.
+    Word,
+    /// Linear selection of whole rows, e.g. on triple-click.
+    Line,
+}
+
+/// Cells covered by a query once words and rows are expanded, clamped to the
+/// viewport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CellSpan {
+    /// Rectangle of cells between two corners, as `(col, row)`.
+    Block { start: (u16, u16), end: (u16, u16) },
+    /// Run of cells from `start` to `end` in reading order, as `(col, row)`.
+    Linear { start: (u16, u16), end: (u16, u16) },
 }
 
 /// Zero-allocation iterator over terminal cell indices.
@@ -105,17 +139,25 @@ impl CellQuery {
 
     /// Returns the normalized selection range if both start and end are defined.
     ///
-    /// The returned range has coordinates ordered so that the first tuple
-    /// contains the minimum coordinates and the second contains the maximum.
+    /// In block mode, the first tuple contains the minimum coordinates and the
+    /// second contains the maximum. In linear mode, the positions are ordered
+    /// by row, then column.
     #[must_use]
     pub fn range(&self) -> Option<((u16, u16), (u16, u16))> {
-        if let (Some(start), Some(end)) = (self.start, self.end) {
-            Some((
+        let (start, end) = (self.start?, self.end?);
+
+        match self.mode {
+            SelectionMode::Block => Some((
                 (start.0.min(end.0), start.1.min(end.1)),
                 (start.0.max(end.0), start.1.max(end.1)),
-            ))
-        } else {
-            None
+            )),
+            SelectionMode::Linear | SelectionMode::Word | SelectionMode::Line => {
+                let reading_order = |&(col, row): &(u16, u16)| (row, col);
+                Some((
+                    std::cmp::min_by_key(start, end, reading_order),
+                    std::cmp::max_by_key(start, end, reading_order),
+                ))
+            },
         }
     }
 
@@ -129,6 +171,16 @@ impl CellQuery {
         self
     }
 
+    /// Sets the characters separating words in [`SelectionMode::Word`]
+    /// selections, replacing [`DEFAULT_WORD_SEPARATORS`].
+    ///
+    /// Whitespace always separates words.
+    #[must_use]
+    pub fn word_separators(mut self, separators: &'static str) -> Self {
+        self.word_separators = Some(separators);
+        self
+    }
+
     /// Sets the content hash for this query.
     ///
     /// The hash is computed from the selected cells when the selection
@@ -224,7 +276,8 @@ impl BlockCellIterator {
             end.1
                 .min((max_cells / cols as usize).saturating_sub(1) as u16),
         );
-        let (start, end) = if start > end { (end, start) } else { (start, end) };
+        let (start, end) =
+            if (start.1, start.0) > (end.1, end.0) { (end, start) } else { (start, end) };
 
         Self {
             cols,
@@ -255,7 +308,8 @@ impl LinearCellIterator {
             end.1
                 .min((max_cells / cols_usize).saturating_sub(1) as u16),
         );
-        let (start, end) = if start > end { (end, start) } else { (start, end) };
+        let (start, end) =
+            if (start.1, start.0) > (end.1, end.0) { (end, start) } else { (start, end) };
 
         let start_idx = start.1 as usize * cols_usize + start.0 as usize;
         let end_idx = end.1 as usize * cols_usize + end.0 as usize;
@@ -271,7 +325,7 @@ impl LinearCellIterator {
     }
 }
 
-impl TerminalGrid {
+impl<A: GlyphLookup> GridModel<A> {
     /// Zero-allocation iterator over cell indices for a given selection range and mode.
     ///
     /// Creates an efficient iterator that yields cell indices and newline indicators
@@ -284,18 +338,83 @@ impl TerminalGrid {
         let cols = self.terminal_size().cols;
         let max_cells = self.cell_count();
 
-        let (start, end) = selection.range().unwrap_or_default();
-
-        match selection.mode {
-            SelectionMode::Block => {
+        match self.cell_span(selection) {
+            CellSpan::Block { start, end } => {
                 CellIterator::Block(BlockCellIterator::new(cols, start, end, max_cells))
             },
-            SelectionMode::Linear => {
+            CellSpan::Linear { start, end } => {
                 CellIterator::Linear(LinearCellIterator::new(cols, start, end, max_cells))
             },
         }
     }
 
+    /// Returns the cells covered by the selection, with word and line
+    /// selections expanded.
+    pub(crate) fn cell_span(&self, selection: CellQuery) -> CellSpan {
+        let size = self.terminal_size();
+        let clamp = |(col, row): (u16, u16)| {
+            (
+                col.min(size.cols.saturating_sub(1)),
+                row.min(size.rows.saturating_sub(1)),
+            )
+        };
+
+        let (start, end) = selection.range().unwrap_or_default();
+        let (start, end) = (clamp(start), clamp(end));
+
+        match selection.mode {
+            SelectionMode::Block => CellSpan::Block { start, end },
+            SelectionMode::Linear => CellSpan::Linear { start, end },
+            SelectionMode::Word | SelectionMode::Line => {
+                let (start, end) = self.expand_selection(&selection, start, end);
+                CellSpan::Linear { start, end }
+            },
+        }
+    }
+
+    /// Expands the ordered anchors of a word or line selection to whole
+    /// words or rows.
+    fn expand_selection(
+        &self,
+        selection: &CellQuery,
+        start: (u16, u16),
+        end: (u16, u16),
+    ) -> ((u16, u16), (u16, u16)) {
+        if selection.mode == SelectionMode::Line {
+            let last_col = self.terminal_size().cols.saturating_sub(1);
+            return ((0, start.1), (last_col, end.1));
+        }
+
+        let separators = selection
+            .word_separators
+            .unwrap_or(DEFAULT_WORD_SEPARATORS);
+        let (first, _) = self.word_extent(start, separators);
+        let (_, last) = self.word_extent(end, separators);
+        (first, last)
+    }
+
+    /// Returns the first and last position of the word, quoted string or URL
+    /// at `pos`, which is limited to its row.
+    fn word_extent(&self, pos: (u16, u16), separators: &str) -> ((u16, u16), (u16, u16)) {
+        let size = self.terminal_size();
+        if size.cols == 0 || pos.1 >= size.rows {
+            return (pos, pos);
+        }
+
+        let cols = size.cols as usize;
+        let pos = (pos.0.min(size.cols - 1), pos.1);
+        let row_start = pos.1 as usize * cols;
+        let line = CellText::new(self, row_start..row_start + cols);
+        let position = |idx: usize| ((idx % cols) as u16, (idx / cols) as u16);
+
+        line.offset_of_cell(row_start + pos.0 as usize)
+            .map(|offset| word_range(&line.text, offset, separators))
+            .and_then(|range| line.cell_range(range))
+            .map_or((pos, pos), |(first, last)| {
+                (position(first), position(last))
+            })
+    }
+
     /// Extracts text content from the terminal based on the selection query.
     ///
     /// Retrieves the text within the selection range, optionally trimming
@@ -320,3 +439,158 @@ impl TerminalGrid {
         }
     }
 }
+
+/// Returns the byte range of the URL, quoted string or word containing the
+/// character at `offset`. Runs of whitespace are selected as a whole, other
+/// separators on their own.
+fn word_range(text: &str, offset: usize, separators: &str) -> Range<usize> {
+    if let Some(url) = Matcher::urls()
+        .find(text)
+        .into_iter()
+        .find(|url| url.contains(&offset))
+    {
+        return url;
+    }
+
+    let ch = text[offset..].chars().next().unwrap_or(' ');
+    if let Some(quoted) = quoted_range(text, offset, ch) {
+        return quoted;
+    }
+
+    let is_word_char = |c: char| !c.is_whitespace() && !separators.contains(c);
+    if !ch.is_whitespace() && !is_word_char(ch) {
+        return offset..offset + ch.len_utf8();
+    }
+
+    let same_class = |c: char| {
+        if ch.is_whitespace() { c.is_whitespace() } else { is_word_char(c) }
+    };
+    let start = text[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| same_class(c))
+        .last()
+        .map_or(offset, |(idx, _)| idx);
+    let end = text[offset..]
+        .char_indices()
+        .find(|&(_, c)| !same_class(c))
+        .map_or(text.len(), |(idx, _)| offset + idx);
+
+    start..end
+}
+
+/// Returns the byte range of the quoted string opened or closed by the
+/// `quote` at `offset`, if `quote` is a quote character with a counterpart.
+fn quoted_range(text: &str, offset: usize, quote: char) -> Option<Range<usize>> {
+    if !QUOTES.contains(quote) {
+        return None;
+    }
+
+    // quotes preceded by an even number of the same quote open a string
+    let quote_len = quote.len_utf8();
+    if text[..offset]
+        .matches(quote)
+        .count()
+        .is_multiple_of(2)
+    {
+        let after = offset + quote_len;
+        text[after..]
+            .find(quote)
+            .map(|close| offset..after + close + quote_len)
+    } else {
+        text[..offset]
+            .rfind(quote)
+            .map(|open| open..offset + quote_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, TerminalSize};
+
+    use super::*;
+    use crate::{CellData, StaticGlyphLookup};
+
+    fn model(cols: u16, rows: u16, text: &str) -> GridModel<StaticGlyphLookup> {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        let mut model = GridModel::new(lookup, TerminalSize::new(cols, rows));
+        let symbols: Vec<String> = text.chars().map(String::from).collect();
+        model
+            .update_cells(
+                symbols
+                    .iter()
+                    .map(|s| CellData::new_with_style_bits(s, 0, 0xFFFFFF, 0)),
+            )
+            .unwrap();
+        model
+    }
+
+    fn word_at(model: &GridModel<StaticGlyphLookup>, pos: (u16, u16)) -> CompactString {
+        model.get_text(select(SelectionMode::Word).start(pos).end(pos))
+    }
+
+    #[test]
+    fn word_selection_expands_to_words() {
+        let model = model(20, 2, "ls src/gl/mod.rs,  x  say \"hi there\"  ");
+
+        assert_eq!(word_at(&model, (0, 0)), "ls");
+        assert_eq!(word_at(&model, (8, 0)), "src/gl/mod.rs");
+        assert_eq!(word_at(&model, (16, 0)), ",");
+        assert_eq!(word_at(&model, (17, 0)), "  ");
+        // quoted strings, from either quote
+        assert_eq!(word_at(&model, (6, 1)), "\"hi there\"");
+        assert_eq!(word_at(&model, (15, 1)), "\"hi there\"");
+        assert_eq!(word_at(&model, (10, 1)), "there");
+
+        // dragging extends the selection word by word, in either direction
+        let query = select(SelectionMode::Word)
+            .start((1, 1))
+            .end((4, 0));
+        assert_eq!(model.get_text(query), "src/gl/mod.rs,  x\n  ");
+
+        let query = select(SelectionMode::Word)
+            .start((7, 0))
+            .end((7, 0))
+            .word_separators("/");
+        assert_eq!(model.get_text(query), "gl");
+    }
+
+    #[test]
+    fn word_selection_selects_whole_urls() {
+        let model = model(30, 1, "see (https://x.org/a?b=c:1), ok");
+
+        assert_eq!(word_at(&model, (20, 0)), "https://x.org/a?b=c:1");
+        assert_eq!(word_at(&model, (2, 0)), "see");
+    }
+
+    #[test]
+    fn cell_spans_are_clamped_and_expanded() {
+        let model = model(4, 3, "ab cdefghijk");
+
+        let block = select(SelectionMode::Block)
+            .start((9, 1))
+            .end((1, 0));
+        assert_eq!(model.cell_span(block), CellSpan::Block {
+            start: (1, 0),
+            end: (3, 1)
+        });
+
+        let word = select(SelectionMode::Word)
+            .start((3, 0))
+            .end((9, 9));
+        assert_eq!(model.cell_span(word), CellSpan::Linear {
+            start: (3, 0),
+            end: (3, 2)
+        });
+    }
+
+    #[test]
+    fn line_selection_spans_whole_rows() {
+        let model = model(4, 3, "abcdefghijkl");
+
+        let query = select(SelectionMode::Line)
+            .start((2, 2))
+            .end((1, 1));
+        assert_eq!(model.get_text(query), "efgh\nijkl");
+    }
+}
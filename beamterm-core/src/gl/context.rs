@@ -9,8 +9,9 @@ pub struct GlState {
     // Clear color
     clear_color: [f32; 4],
 
-    // Blend function state
-    blend_func: (u32, u32), // (src_factor, dst_factor)
+    // Blending state
+    blend: bool,
+    blend_func: (u32, u32, u32, u32), // (src_rgb, dst_rgb, src_alpha, dst_alpha)
 
     // Active texture unit
     active_texture_unit: u32,
@@ -28,7 +29,8 @@ impl GlState {
         Self {
             viewport: [0, 0, 0, 0],
             clear_color: [0.0, 0.0, 0.0, 0.0],
-            blend_func: (glow::ONE, glow::ZERO), // Default blend function
+            blend: false,
+            blend_func: (glow::ONE, glow::ZERO, glow::ONE, glow::ZERO), // Default blend function
             active_texture_unit: glow::TEXTURE0,
             enabled_vertex_attribs: vec![false; max_vertex_attribs],
         }
@@ -61,6 +63,36 @@ impl GlState {
         self
     }
 
+    /// Enable or disable blending
+    pub fn blend(&mut self, gl: &glow::Context, enable: bool) -> &mut Self {
+        if self.blend != enable {
+            if enable {
+                unsafe { gl.enable(glow::BLEND) };
+            } else {
+                unsafe { gl.disable(glow::BLEND) };
+            }
+            self.blend = enable;
+        }
+        self
+    }
+
+    /// Set separate blend factors for the color and alpha channels
+    pub fn blend_func_separate(
+        &mut self,
+        gl: &glow::Context,
+        src_rgb: u32,
+        dst_rgb: u32,
+        src_alpha: u32,
+        dst_alpha: u32,
+    ) -> &mut Self {
+        let new_func = (src_rgb, dst_rgb, src_alpha, dst_alpha);
+        if self.blend_func != new_func {
+            unsafe { gl.blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha) };
+            self.blend_func = new_func;
+        }
+        self
+    }
+
     /// Set active texture unit
     pub fn active_texture(&mut self, gl: &glow::Context, texture_unit: u32) -> &mut Self {
         if self.active_texture_unit != texture_unit {
@@ -91,11 +123,9 @@ impl GlState {
 
     /// Reset all tracked state to GL defaults
     pub fn reset(&mut self, gl: &glow::Context) {
-        // Reset blend function
-        if self.blend_func != (glow::ONE, glow::ZERO) {
-            unsafe { gl.blend_func(glow::ONE, glow::ZERO) };
-            self.blend_func = (glow::ONE, glow::ZERO);
-        }
+        // Reset blending
+        self.blend(gl, false);
+        self.blend_func_separate(gl, glow::ONE, glow::ZERO, glow::ONE, glow::ZERO);
 
         // Reset texture unit
         if self.active_texture_unit != glow::TEXTURE0 {
@@ -1,104 +1,183 @@
-/// Tracks which chunks of the cell buffer need uploading to the GPU.
+use std::ops::Range;
+
+/// Tracks which cells of the cell buffer need uploading to the GPU.
 ///
-/// Uses a `u64` bitmask where each bit represents a chunk of 1024 cells.
-/// Adjacent dirty chunks are merged into contiguous uploads via [`drain()`](Self::drain).
+/// Uses a growable bitset with one bit per cell, so terminals of any size are
+/// tracked without aliasing. [`drain()`](Self::drain) coalesces the dirty cells
+/// into contiguous uploads: runs separated by small gaps are merged, and the
+/// merge distance grows adaptively when updates are scattered, bounding the
+/// number of uploads per frame.
 #[derive(Debug)]
 pub(super) struct DirtyRegions {
-    dirty: u64,
+    /// One bit per cell; bits past `total_cells` are never set.
+    words: Vec<u64>,
     total_cells: usize,
+    /// Number of set bits in `words`.
+    dirty_cells: usize,
+    /// Coalesced ranges of the last drain, reused across frames.
+    ranges: Vec<(usize, usize)>,
 }
 
 impl DirtyRegions {
-    const CHUNK_SHIFT: u32 = 10; // 1024 cells per chunk
-    const CHUNK_SIZE: usize = 1 << Self::CHUNK_SHIFT;
+    /// Clean cells between two dirty runs that are re-uploaded rather than
+    /// issuing a separate upload.
+    const MERGE_GAP: usize = 32;
+    /// Upper bound on the number of uploads per drain.
+    const MAX_RANGES: usize = 64;
 
     pub(super) fn new(total_cells: usize) -> Self {
         debug_assert!(total_cells > 0, "requires a non-zero sized terminal");
-        Self { dirty: 0, total_cells }
+        Self {
+            words: vec![0; total_cells.div_ceil(64)],
+            total_cells,
+            dirty_cells: 0,
+            ranges: Vec::new(),
+        }
+    }
+
+    /// Resizes the tracker to `total_cells`, keeping its allocations.
+    ///
+    /// All cells are clean afterwards.
+    pub(super) fn resize(&mut self, total_cells: usize) {
+        debug_assert!(total_cells > 0, "requires a non-zero sized terminal");
+        self.words.clear();
+        self.words.resize(total_cells.div_ceil(64), 0);
+        self.total_cells = total_cells;
+        self.dirty_cells = 0;
     }
 
     pub(super) fn is_clean(&self) -> bool {
-        self.dirty == 0
+        self.dirty_cells == 0
     }
 
-    /// Marks the chunk containing `cell_index` as dirty.
+    /// Marks the cell at `cell_index` as dirty.
     pub(super) fn mark(&mut self, cell_index: usize) {
-        self.dirty |= 1u64 << ((cell_index >> Self::CHUNK_SHIFT) & 0b0011_1111);
+        if cell_index >= self.total_cells {
+            return;
+        }
+
+        let word = &mut self.words[cell_index / 64];
+        let bit = 1u64 << (cell_index % 64);
+        if *word & bit == 0 {
+            *word |= bit;
+            self.dirty_cells += 1;
+        }
     }
 
-    /// Marks all chunks as dirty (used for bulk updates / context loss).
-    pub(super) fn mark_all(&mut self) {
-        self.dirty = u64::MAX;
+    /// Marks all cells in the range `start..end` as dirty.
+    pub(super) fn mark_range(&mut self, range: Range<usize>) {
+        let end = range.end.min(self.total_cells);
+        let mut idx = range.start;
+
+        while idx < end {
+            let offset = idx % 64;
+            let len = (64 - offset).min(end - idx);
+            let mask = (u64::MAX >> (64 - len)) << offset;
+
+            let word = &mut self.words[idx / 64];
+            self.dirty_cells += (mask & !*word).count_ones() as usize;
+            *word |= mask;
+
+            idx += len;
+        }
     }
 
-    /// Returns a bitmask covering only the chunks that contain actual cells.
-    fn active_mask(&self) -> u64 {
-        let active_chunks = self.total_cells.div_ceil(Self::CHUNK_SIZE);
-        if active_chunks >= 64 { u64::MAX } else { (1u64 << active_chunks) - 1 }
+    /// Marks all cells as dirty (used for bulk updates / context loss).
+    pub(super) fn mark_all(&mut self) {
+        self.mark_range(0..self.total_cells);
     }
 
-    /// Returns true if every active chunk is dirty.
+    /// Returns true if every cell is dirty.
     pub(super) fn is_all_active_dirty(&self) -> bool {
-        let mask = self.active_mask();
-        self.dirty & mask == mask
+        self.dirty_cells == self.total_cells
     }
 
     pub(super) fn clear(&mut self) {
-        self.dirty = 0;
+        self.words.fill(0);
+        self.dirty_cells = 0;
     }
 
-    /// Takes the dirty bits and clears them, returning an iterator
-    /// over contiguous dirty `(start_cell, end_cell)` ranges.
-    ///
-    /// When the terminal exceeds 64 chunks (65 536 cells), multiple chunks
-    /// alias the same bit. The iterator walks all actual chunks and checks
-    /// the aliased bit, so every dirty region is uploaded — aliased chunks
-    /// may cause redundant uploads but never missed ones.
-    pub(super) fn drain(&mut self) -> DirtyChunkIter {
-        let dirty = self.dirty;
-        self.dirty = 0;
-        let total_chunks = self.total_cells.div_ceil(Self::CHUNK_SIZE);
-        DirtyChunkIter {
-            dirty,
-            total_cells: self.total_cells,
-            current_chunk: 0,
-            total_chunks,
+    /// Takes the dirty cells and clears them, returning an iterator over
+    /// coalesced `(start_cell, end_cell)` ranges in ascending order.
+    pub(super) fn drain(&mut self) -> std::vec::Drain<'_, (usize, usize)> {
+        self.ranges.clear();
+        if self.dirty_cells > 0 {
+            self.collect_runs();
+            self.coalesce();
         }
+        self.clear();
+
+        self.ranges.drain(..)
     }
-}
 
-/// Iterator over contiguous runs of dirty chunks, yielding `(start, end)` cell ranges.
-///
-/// Walks all actual chunks (not just bit positions 0–63), checking each
-/// chunk's aliased bit in the `u64` mask. This correctly handles terminals
-/// larger than 64 × 1024 = 65 536 cells.
-pub(super) struct DirtyChunkIter {
-    dirty: u64,
-    total_cells: usize,
-    current_chunk: usize,
-    total_chunks: usize,
-}
+    /// Collects the runs of consecutive dirty cells, merging runs separated
+    /// by at most [`MERGE_GAP`](Self::MERGE_GAP) clean cells.
+    fn collect_runs(&mut self) {
+        let mut run_start: Option<usize> = None;
+
+        for (word_idx, &word) in self.words.iter().enumerate() {
+            let base = word_idx * 64;
+            let mut bit = 0;
+
+            while bit < 64 {
+                let rest = word >> bit;
+                match run_start {
+                    Some(start) => {
+                        // bits shifted in from the top are zero, so a run
+                        // reaching bit 63 continues into the next word
+                        let ones = rest.trailing_ones() as usize;
+                        if bit + ones >= 64 {
+                            break;
+                        }
+                        push_merged(
+                            &mut self.ranges,
+                            (start, base + bit + ones),
+                            Self::MERGE_GAP,
+                        );
+                        run_start = None;
+                        bit += ones;
+                    },
+                    None if rest == 0 => break,
+                    None => {
+                        bit += rest.trailing_zeros() as usize;
+                        run_start = Some(base + bit);
+                    },
+                }
+            }
+        }
 
-impl Iterator for DirtyChunkIter {
-    type Item = (usize, usize);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        while self.current_chunk < self.total_chunks {
-            if self.dirty & (1u64 << (self.current_chunk & 63)) != 0 {
-                let start_chunk = self.current_chunk;
-                self.current_chunk += 1;
-                while self.current_chunk < self.total_chunks
-                    && self.dirty & (1u64 << (self.current_chunk & 63)) != 0
-                {
-                    self.current_chunk += 1;
+        if let Some(start) = run_start {
+            push_merged(&mut self.ranges, (start, self.total_cells), Self::MERGE_GAP);
+        }
+    }
+
+    /// Widens the merge distance until at most [`MAX_RANGES`](Self::MAX_RANGES)
+    /// uploads remain, trading redundant bytes for fewer upload calls.
+    fn coalesce(&mut self) {
+        let mut gap = Self::MERGE_GAP;
+        while self.ranges.len() > Self::MAX_RANGES {
+            gap *= 4;
+
+            let mut merged = 0;
+            for i in 0..self.ranges.len() {
+                let range = self.ranges[i];
+                if merged > 0 && range.0 - self.ranges[merged - 1].1 <= gap {
+                    self.ranges[merged - 1].1 = range.1;
+                } else {
+                    self.ranges[merged] = range;
+                    merged += 1;
                 }
-                let start = start_chunk * DirtyRegions::CHUNK_SIZE;
-                let end = (self.current_chunk * DirtyRegions::CHUNK_SIZE).min(self.total_cells);
-                return Some((start, end));
             }
-            self.current_chunk += 1;
+            self.ranges.truncate(merged);
         }
-        None
+    }
+}
+
+/// Appends `range`, extending the last range instead if at most `gap` cells apart.
+fn push_merged(ranges: &mut Vec<(usize, usize)>, range: (usize, usize), gap: usize) {
+    match ranges.last_mut() {
+        Some(last) if range.0 - last.1 <= gap => last.1 = range.1,
+        _ => ranges.push(range),
     }
 }
 
@@ -118,41 +197,57 @@ mod tests {
         dr.mark(42);
         assert!(!dr.is_clean());
         let ranges: Vec<_> = dr.drain().collect();
-        assert_eq!(ranges, vec![(0, 1024)]);
+        assert_eq!(ranges, vec![(42, 43)]);
     }
 
     #[test]
-    fn mark_adjacent_chunks_merge() {
+    fn nearby_cells_merge() {
         let mut dr = DirtyRegions::new(10_000);
-        dr.mark(500); // chunk 0
-        dr.mark(1500); // chunk 1
-        dr.mark(2500); // chunk 2
+        dr.mark(500);
+        dr.mark(510);
+        dr.mark(511);
         let ranges: Vec<_> = dr.drain().collect();
-        assert_eq!(ranges, vec![(0, 3072)]);
+        assert_eq!(ranges, vec![(500, 512)]);
     }
 
     #[test]
-    fn mark_separated_chunks() {
+    fn distant_cells_upload_separately() {
         let mut dr = DirtyRegions::new(10_000);
-        dr.mark(0); // chunk 0
-        dr.mark(5000); // chunk 4
+        dr.mark(0);
+        dr.mark(5000);
         let ranges: Vec<_> = dr.drain().collect();
-        assert_eq!(ranges, vec![(0, 1024), (4096, 5120)]);
+        assert_eq!(ranges, vec![(0, 1), (5000, 5001)]);
+    }
+
+    #[test]
+    fn runs_span_word_boundaries() {
+        let mut dr = DirtyRegions::new(10_000);
+        dr.mark_range(60..200);
+        dr.mark(1000);
+        let ranges: Vec<_> = dr.drain().collect();
+        assert_eq!(ranges, vec![(60, 200), (1000, 1001)]);
     }
 
     #[test]
     fn all_dirty_detection() {
-        let mut dr = DirtyRegions::new(10_000); // 10 chunks
+        let mut dr = DirtyRegions::new(10_000);
         dr.mark_all();
         assert!(dr.is_all_active_dirty());
+
+        dr.clear();
+        dr.mark_range(0..9_999);
+        assert!(!dr.is_all_active_dirty());
+        dr.mark(9_999);
+        assert!(dr.is_all_active_dirty());
     }
 
     #[test]
     fn drain_clamps_to_total_cells() {
-        let mut dr = DirtyRegions::new(1500); // 2 chunks, last chunk partially filled
-        dr.mark(1400); // chunk 1
+        let mut dr = DirtyRegions::new(1500);
+        dr.mark_range(1400..2000);
+        dr.mark(1600);
         let ranges: Vec<_> = dr.drain().collect();
-        assert_eq!(ranges, vec![(1024, 1500)]); // clamped to total_cells
+        assert_eq!(ranges, vec![(1400, 1500)]);
     }
 
     #[test]
@@ -161,66 +256,63 @@ mod tests {
         dr.mark(42);
         let _ = dr.drain().count();
         assert!(dr.is_clean());
+        assert_eq!(dr.drain().count(), 0);
     }
 
     #[test]
     fn all_dirty_single_range() {
-        let mut dr = DirtyRegions::new(2048); // exactly 2 chunks
+        let mut dr = DirtyRegions::new(2048);
         dr.mark_all();
         let ranges: Vec<_> = dr.drain().collect();
-        // all-dirty produces contiguous run from drain
         assert_eq!(ranges, vec![(0, 2048)]);
     }
 
     #[test]
-    fn aliased_chunk_beyond_64k_is_uploaded() {
-        // 70 chunks = 71680 cells; chunk 65 aliases to bit 1
-        let mut dr = DirtyRegions::new(71_680);
-        dr.mark(66_000); // chunk 64 (aliases bit 0)
+    fn cells_beyond_64k_do_not_alias() {
+        // 400x200 cells, well past the 65 536 cells of the former u64 chunk mask
+        let mut dr = DirtyRegions::new(80_000);
+        dr.mark(66_000);
         let ranges: Vec<_> = dr.drain().collect();
-        assert!(
-            ranges
-                .iter()
-                .any(|(s, e)| *s <= 66_000 && *e > 66_000),
-            "expected a range covering cell 66000, got {ranges:?}"
-        );
+        assert_eq!(ranges, vec![(66_000, 66_001)]);
     }
 
     #[test]
-    fn aliased_chunk_also_uploads_lower_alias() {
-        // marking chunk 64 (bit 0) should also upload chunk 0
-        let mut dr = DirtyRegions::new(71_680);
-        dr.mark(66_000); // chunk 64, aliases to bit 0
+    fn scattered_updates_are_coalesced_adaptively() {
+        let mut dr = DirtyRegions::new(100_000);
+        (0..1000).for_each(|i| dr.mark(i * 100));
+
         let ranges: Vec<_> = dr.drain().collect();
-        // bit 0 is set, so both chunk 0 and chunk 64 should be uploaded
-        assert!(
-            ranges.iter().any(|(s, _)| *s == 0),
-            "expected chunk 0 (aliased) to be uploaded, got {ranges:?}"
-        );
-        assert!(
-            ranges
-                .iter()
-                .any(|(s, e)| *s <= 65_536 && *e > 65_536),
-            "expected chunk 64 to be uploaded, got {ranges:?}"
-        );
+        assert!(ranges.len() <= DirtyRegions::MAX_RANGES, "{}", ranges.len());
+        assert!(ranges.windows(2).all(|w| w[0].1 < w[1].0));
+        for i in 0..1000 {
+            let cell = i * 100;
+            assert!(
+                ranges.iter().any(|&(s, e)| s <= cell && cell < e),
+                "cell {cell}"
+            );
+        }
     }
 
     #[test]
-    fn adjacent_aliased_chunks_merge() {
-        let mut dr = DirtyRegions::new(71_680);
-        dr.mark(65_536); // chunk 64 (bit 0)
-        dr.mark(66_560); // chunk 65 (bit 1)
+    fn resize_grows_and_clears() {
+        let mut dr = DirtyRegions::new(100);
+        dr.mark(10);
+        dr.resize(100_000);
+        assert!(dr.is_clean());
+
+        dr.mark(99_999);
         let ranges: Vec<_> = dr.drain().collect();
-        // chunks 0-1 and 64-65 should each be merged into contiguous ranges
-        assert!(
-            ranges.iter().any(|(s, e)| *s == 0 && *e >= 2048),
-            "expected chunks 0-1 merged, got {ranges:?}"
-        );
-        assert!(
-            ranges
-                .iter()
-                .any(|(s, e)| *s == 65_536 && *e >= 67_584),
-            "expected chunks 64-65 merged, got {ranges:?}"
-        );
+        assert_eq!(ranges, vec![(99_999, 100_000)]);
+    }
+
+    #[test]
+    fn mark_range_covers_exact_cells() {
+        let mut dr = DirtyRegions::new(10_000);
+        dr.mark_range(1000..2050);
+        let ranges: Vec<_> = dr.drain().collect();
+        assert_eq!(ranges, vec![(1000, 2050)]);
+
+        dr.mark_range(500..500);
+        assert!(dr.is_clean());
     }
 }
@@ -0,0 +1,1260 @@
+use std::cmp::min;
+
+use beamterm_data::{FontStyle, TerminalSize};
+use compact_str::CompactString;
+
+use crate::{
+    CursorPosition, SelectionMode, UrlMatch,
+    error::Error,
+    gl::{
+        CellIterator, CellQuery,
+        atlas::{self, FontAtlas, GlyphLookup, GlyphSlot},
+        dirty_regions::DirtyRegions,
+        hyperlink::{HyperlinkId, HyperlinkRegistry},
+        scrollback::ScrollbackRing,
+        select,
+        selection::SelectionTracker,
+        terminal_grid::{CellData, CellDynamic, LineAttribute},
+    },
+};
+
+/// The CPU-side cell model of a terminal grid.
+///
+/// `GridModel` owns the cell data, the scrollback history, the selection, the
+/// hyperlinks and the glyph lookup, and implements everything that works
+/// without a GL context: cell updates, scrolling, text extraction and URL
+/// detection. A [`TerminalGrid`] is a GPU mirror of its model, which it brings
+/// in sync on [`flush_cells`](crate::TerminalGrid::flush_cells).
+///
+/// Paired with a [`StaticGlyphLookup`](crate::StaticGlyphLookup), a model can
+/// be used in unit tests and application logic without any graphics context:
+///
+/// ```
+/// use beamterm_core::{CellData, FontAtlasData, GridModel, StaticGlyphLookup, TerminalSize};
+/// use beamterm_core::{SelectionMode, select};
+///
+/// let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+/// let mut model = GridModel::new(lookup, TerminalSize::new(5, 1));
+///
+/// let cells = "hello".chars().map(|ch| ch.to_string()).collect::<Vec<_>>();
+/// let cells = cells.iter().map(|s| CellData::new_with_style_bits(s, 0, 0xffffff, 0x000000));
+/// model.update_cells(cells).unwrap();
+///
+/// let query = select(SelectionMode::Linear).start((0, 0)).end((4, 0));
+/// assert_eq!(model.get_text(query), "hello");
+/// ```
+///
+/// [`TerminalGrid`]: crate::TerminalGrid
+#[derive(Debug)]
+pub struct GridModel<A: GlyphLookup = FontAtlas> {
+    /// Glyph lookup for resolving symbols to glyph ids.
+    atlas: A,
+    /// Terminal cell instance data, laid out as a ring of lines; see [`ScrollbackRing`]
+    cells: Vec<CellDynamic>,
+    /// Hyperlink of each cell, parallel to `cells`; `0` for unlinked cells.
+    links: Vec<u32>,
+    /// Targets of the hyperlinks referenced by `links`.
+    hyperlinks: HyperlinkRegistry,
+    /// Whether each line of the cell ring continues on the next row.
+    wrapped: Vec<bool>,
+    /// DEC line attribute of each line of the cell ring.
+    line_attributes: Vec<LineAttribute>,
+    /// Whether `line_attributes` changed since the GPU mirror was last synchronized.
+    line_attributes_dirty: bool,
+    /// Maps live screen and viewport rows to lines in the cell ring.
+    scrollback: ScrollbackRing,
+    /// Terminal size in cells
+    terminal_size: TerminalSize,
+    /// Fallback glyph for missing symbols.
+    fallback_glyph: u16,
+    /// Selection tracker for managing cell selections.
+    selection: SelectionTracker,
+    /// Cells modified since the GPU mirror was last synchronized.
+    dirty_regions: DirtyRegions,
+}
+
+impl<A: GlyphLookup> GridModel<A> {
+    /// Creates a model of `size` cells, filled with spaces.
+    ///
+    /// Both dimensions are clamped to at least one cell.
+    pub fn new(mut atlas: A, size: TerminalSize) -> Self {
+        let size = TerminalSize::new(size.cols.max(1), size.rows.max(1));
+        let space_glyph = space_glyph_id(&mut atlas);
+        let cell_count = size.cols as usize * size.rows as usize;
+
+        Self {
+            atlas,
+            cells: vec![CellDynamic::new(space_glyph, 0x00ff_ffff, 0x0000_0000); cell_count],
+            links: vec![0; cell_count],
+            hyperlinks: HyperlinkRegistry::new(),
+            wrapped: vec![false; size.rows as usize],
+            line_attributes: vec![LineAttribute::Single; size.rows as usize],
+            line_attributes_dirty: false,
+            scrollback: ScrollbackRing::new(size.rows as usize, 0),
+            terminal_size: size,
+            fallback_glyph: space_glyph,
+            selection: SelectionTracker::new(),
+            dirty_regions: DirtyRegions::new(cell_count),
+        }
+    }
+
+    /// Returns the glyph lookup of this model.
+    #[must_use]
+    pub fn atlas(&self) -> &A {
+        &self.atlas
+    }
+
+    /// Returns a mutable reference to the glyph lookup.
+    pub fn atlas_mut(&mut self) -> &mut A {
+        &mut self.atlas
+    }
+
+    /// Sets the fallback glyph for missing characters.
+    pub fn set_fallback_glyph(&mut self, fallback: &str) {
+        self.fallback_glyph = self
+            .atlas
+            .resolve_glyph_slot(fallback, FontStyle::Normal as u16)
+            .map_or(' ' as u16, |slot| slot.slot_id());
+    }
+
+    /// Replaces the glyph lookup, translating all existing glyph IDs to the
+    /// new one. Returns the previous lookup.
+    ///
+    /// This method handles the transition between atlases by:
+    /// 1. Looking up the symbol for each existing glyph ID in the old atlas
+    /// 2. Resolving the corresponding glyph slot in the new atlas
+    /// 3. Updating double-width glyphs (emoji, wide chars) across both cells
+    pub fn replace_atlas(&mut self, mut atlas: A) -> A {
+        let glyph_mask = atlas::GLYPH_SLOT_MASK as u16;
+        let style_mask = !glyph_mask;
+
+        // compute space glyph before mutable borrows
+        let space_glyph = space_glyph_id(&mut atlas);
+
+        // update fallback glyph to new atlas, before translating existing cells
+        self.fallback_glyph = self
+            .atlas
+            .get_symbol(self.fallback_glyph & glyph_mask)
+            .and_then(|symbol| {
+                let style_bits = self.fallback_glyph & style_mask;
+                atlas.resolve_glyph_slot(symbol.as_str(), style_bits)
+            })
+            .map_or(space_glyph, |slot| slot.slot_id());
+
+        // translate existing glyph ids to new atlas
+        let mut skip_next = false;
+        for idx in 0..self.cells.len() {
+            if skip_next {
+                skip_next = false;
+                continue;
+            }
+
+            let old_glyph_id = self.cells[idx].glyph_id();
+            let style_bits = old_glyph_id & style_mask;
+
+            let slot = self
+                .atlas
+                .get_symbol(old_glyph_id & glyph_mask)
+                .and_then(|symbol| atlas.resolve_glyph_slot(symbol.as_str(), style_bits));
+
+            match slot {
+                Some(GlyphSlot::Normal(id)) => {
+                    self.cells[idx].set_glyph_id(id);
+                },
+                Some(GlyphSlot::Wide(id)) | Some(GlyphSlot::Emoji(id)) => {
+                    self.cells[idx].set_glyph_id(id);
+                    // update right-half in next cell if within bounds
+                    if let Some(next_cell) = self.cells.get_mut(idx + 1) {
+                        next_cell.set_glyph_id(id + 1);
+                        skip_next = true;
+                    }
+                },
+                None => {
+                    self.cells[idx].set_glyph_id(self.fallback_glyph);
+                },
+            }
+        }
+
+        // clear any active selection, just to keep it simple
+        self.selection.clear();
+        self.dirty_regions.mark_all();
+
+        std::mem::replace(&mut self.atlas, atlas)
+    }
+
+    /// Returns the size of the terminal grid in cells.
+    #[must_use]
+    pub fn terminal_size(&self) -> TerminalSize {
+        self.terminal_size
+    }
+
+    /// Returns the total number of cells in the terminal grid.
+    ///
+    /// Scrollback history lines are not included.
+    #[must_use]
+    pub fn cell_count(&self) -> usize {
+        self.terminal_size.cols as usize * self.terminal_size.rows as usize
+    }
+
+    /// Resizes the grid to `size` cells, keeping the scrollback history.
+    ///
+    /// Cells outside the new size are discarded, and the viewport is reset to
+    /// the live screen. Both dimensions are clamped to at least one cell.
+    ///
+    /// Returns `true` if the size changed.
+    pub fn resize(&mut self, size: TerminalSize) -> bool {
+        let size = TerminalSize::new(size.cols.max(1), size.rows.max(1));
+        if size == self.terminal_size {
+            return false;
+        }
+
+        let capacity = self.scrollback.capacity();
+        self.resize_cell_grid(size, capacity);
+
+        true
+    }
+
+    /// Returns a mutable reference to the cell data at the specified cell coordinates.
+    ///
+    /// Coordinates refer to the live screen, regardless of the scrollback viewport.
+    pub fn cell_data_mut(&mut self, x: u16, y: u16) -> Option<&mut CellDynamic> {
+        let cols = self.terminal_size.cols as usize;
+        let idx = y as usize * cols + x as usize;
+        if idx >= self.cell_count() {
+            return None;
+        }
+
+        let idx = self.scrollback.live_cell(idx, cols);
+        self.dirty_regions.mark(idx);
+        self.cells.get_mut(idx)
+    }
+
+    /// Returns the active selection state of the terminal grid.
+    #[must_use]
+    pub fn selection_tracker(&self) -> SelectionTracker {
+        self.selection.clone()
+    }
+
+    /// Returns the symbols in the specified block range as a `CompactString`.
+    pub(super) fn get_symbols(&self, selection: CellIterator) -> CompactString {
+        let mut text = CompactString::new("");
+        let cols = self.terminal_size.cols as usize;
+
+        for (idx, require_newline_after) in selection {
+            let cell_symbol = self.get_cell_symbol(self.scrollback.viewport_cell(idx, cols));
+            if cell_symbol.is_some() {
+                text.push_str(&cell_symbol.unwrap_or_default());
+            }
+
+            if require_newline_after {
+                text.push('\n'); // add newline after each row
+            }
+        }
+
+        text
+    }
+
+    /// Returns the ASCII character at the given position, if it's an ASCII char.
+    ///
+    /// Returns `None` for non-ASCII characters or out-of-bounds positions.
+    /// This is an optimized path for URL detection that avoids string allocation.
+    pub(crate) fn get_ascii_char_at(&self, cursor: CursorPosition) -> Option<char> {
+        let cols = self.terminal_size.cols as usize;
+        let idx = cursor.row as usize * cols + cursor.col as usize;
+        if idx < self.cell_count() {
+            let glyph_id = self.cells[self.scrollback.viewport_cell(idx, cols)].glyph_id();
+            self.atlas.get_ascii_char(glyph_id)
+        } else {
+            None
+        }
+    }
+
+    /// Internal method — not covered by semver guarantees.
+    #[doc(hidden)]
+    #[must_use]
+    pub fn hash_cells(&self, selection: CellQuery) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        use rustc_hash::FxHasher;
+
+        let cols = self.terminal_size.cols as usize;
+        let mut hasher = FxHasher::default();
+        for (idx, _) in self.cell_iter(selection) {
+            self.cells[self.scrollback.viewport_cell(idx, cols)].hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    /// Returns a copy of the cells currently shown in the viewport, in row-major
+    /// order.
+    ///
+    /// Together with a [`SoftwareRenderer`](crate::SoftwareRenderer), this
+    /// allows rendering snapshots of the grid without a GL context.
+    #[must_use]
+    pub fn viewport_cells(&self) -> Vec<CellDynamic> {
+        let cols = self.terminal_size.cols as usize;
+        (0..self.cell_count())
+            .map(|idx| self.cells[self.scrollback.viewport_cell(idx, cols)])
+            .collect()
+    }
+
+    /// Returns the glyph id of a cell in the viewport, by row-major index.
+    pub(crate) fn viewport_glyph_id(&self, idx: usize) -> u16 {
+        let cols = self.terminal_size.cols as usize;
+        self.cells[self.scrollback.viewport_cell(idx, cols)].glyph_id()
+    }
+
+    /// Returns a cell in the viewport, by row-major index.
+    pub(crate) fn viewport_cell(&self, idx: usize) -> CellDynamic {
+        let cols = self.terminal_size.cols as usize;
+        self.cells[self.scrollback.viewport_cell(idx, cols)]
+    }
+
+    /// Returns the symbol of a cell in the viewport, by row-major index.
+    pub(crate) fn viewport_symbol(&self, idx: usize) -> Option<CompactString> {
+        let cols = self.terminal_size.cols as usize;
+        self.get_cell_symbol(self.scrollback.viewport_cell(idx, cols))
+    }
+
+    /// Returns a cell on the live screen, by row-major index.
+    pub(crate) fn live_cell(&self, idx: usize) -> CellDynamic {
+        let cols = self.terminal_size.cols as usize;
+        self.cells[self.scrollback.live_cell(idx, cols)]
+    }
+
+    /// Returns the symbol of a cell on the live screen, by row-major index.
+    pub(crate) fn live_symbol(&self, idx: usize) -> Option<CompactString> {
+        let cols = self.terminal_size.cols as usize;
+        self.get_cell_symbol(self.scrollback.live_cell(idx, cols))
+    }
+
+    fn get_cell_symbol(&self, idx: usize) -> Option<CompactString> {
+        if idx < self.cells.len() {
+            let glyph_id = self.cells[idx].glyph_id();
+            let cell_symbol = self.atlas.get_symbol(glyph_id);
+            if cell_symbol.is_some() {
+                return cell_symbol;
+            }
+        }
+
+        self.fallback_symbol()
+    }
+
+    fn fallback_symbol(&self) -> Option<CompactString> {
+        self.atlas.get_symbol(self.fallback_glyph)
+    }
+
+    /// Updates the content of terminal cells with new data.
+    ///
+    /// Cells are written to the live screen in row-major order, regardless of
+    /// the scrollback viewport.
+    ///
+    /// # Errors
+    /// This method is infallible in the current implementation but returns
+    /// `Result` for API consistency with other update methods.
+    pub fn update_cells<'a>(
+        &mut self,
+        cells: impl Iterator<Item = CellData<'a>>,
+    ) -> Result<(), Error> {
+        let fallback_glyph = GlyphSlot::Normal(self.fallback_glyph);
+        let cell_count = self.cell_count();
+        let cols = self.terminal_size.cols as usize;
+        let scrollback = self.scrollback;
+
+        // split borrows: atlas needs &mut, cells needs &mut, dirty_regions needs &mut
+        let atlas = &mut self.atlas;
+        let cell_buf = &mut self.cells;
+        let links = &mut self.links;
+
+        // handle double-width emoji that span two cells
+        let mut pending_cell: Option<(CellDynamic, u32)> = None;
+        (0..cell_count)
+            .map(|idx| scrollback.live_cell(idx, cols))
+            .zip(cells)
+            .for_each(|(idx, data)| {
+                let glyph = atlas
+                    .resolve_glyph_slot(data.symbol, data.style_bits)
+                    .unwrap_or(fallback_glyph);
+                let link = HyperlinkId::cell_value(data.hyperlink);
+
+                let (cell, link) = if let Some(second_cell) = pending_cell.take() {
+                    second_cell
+                } else {
+                    match glyph {
+                        GlyphSlot::Normal(id) => (data.to_cell_dynamic(id), link),
+
+                        GlyphSlot::Wide(id) | GlyphSlot::Emoji(id) => {
+                            // storing a double-width glyph, reserve next cell with right-half id
+                            pending_cell = Some((data.to_cell_dynamic(id + 1), link));
+                            (data.to_cell_dynamic(id), link)
+                        },
+                    }
+                };
+                cell_buf[idx] = cell;
+                links[idx] = link;
+            });
+
+        scrollback
+            .live_cell_ranges(cols)
+            .for_each(|range| self.dirty_regions.mark_range(range));
+
+        Ok(())
+    }
+
+    /// Updates cells at specific grid coordinates on the live screen.
+    ///
+    /// # Errors
+    /// This method is infallible in the current implementation but returns
+    /// `Result` for API consistency with other update methods.
+    pub fn update_cells_by_position<'a>(
+        &mut self,
+        cells: impl Iterator<Item = (u16, u16, CellData<'a>)>,
+    ) -> Result<(), Error> {
+        let cols = self.terminal_size.cols as usize;
+        let cells_by_index = cells.map(|(x, y, data)| (y as usize * cols + x as usize, data));
+
+        self.update_cells_by_index(cells_by_index)
+    }
+
+    /// Updates cells at specific flat indices on the live screen.
+    ///
+    /// # Errors
+    /// This method is infallible in the current implementation but returns
+    /// `Result` for API consistency with other update methods.
+    pub fn update_cells_by_index<'a>(
+        &mut self,
+        cells: impl Iterator<Item = (usize, CellData<'a>)>,
+    ) -> Result<(), Error> {
+        let fallback_glyph = GlyphSlot::Normal(self.fallback_glyph);
+        let cell_count = self.cell_count();
+        let cols = self.terminal_size.cols as usize;
+        let scrollback = self.scrollback;
+
+        let atlas = &mut self.atlas;
+        let cell_buf = &mut self.cells;
+        let links = &mut self.links;
+        let dirty_regions = &mut self.dirty_regions;
+
+        // ratatui and beamterm can disagree on which emoji
+        // are double-width (beamterm assumes double-width for all emoji),
+        // so for ratatui and similar clients we need to skip the next cell
+        // if we just wrote a double-width emoji in the current cell.
+        let mut skip_idx = None;
+
+        cells
+            .filter(|(idx, _)| *idx < cell_count)
+            .for_each(|(idx, cell)| {
+                if skip_idx.take() == Some(idx) {
+                    // skip this cell, already handled as part of previous double-width emoji
+                    return;
+                }
+
+                let glyph = atlas
+                    .resolve_glyph_slot(cell.symbol, cell.style_bits)
+                    .unwrap_or(fallback_glyph);
+                let link = HyperlinkId::cell_value(cell.hyperlink);
+
+                let ring_idx = scrollback.live_cell(idx, cols);
+                match glyph {
+                    GlyphSlot::Normal(id) => {
+                        cell_buf[ring_idx] = cell.to_cell_dynamic(id);
+                        links[ring_idx] = link;
+                        dirty_regions.mark(ring_idx);
+                    },
+
+                    GlyphSlot::Wide(id) | GlyphSlot::Emoji(id) => {
+                        // render left half in current cell
+                        cell_buf[ring_idx] = cell.to_cell_dynamic(id);
+                        links[ring_idx] = link;
+                        dirty_regions.mark(ring_idx);
+
+                        // render right half in next cell, if within bounds
+                        if idx + 1 < cell_count {
+                            let next_idx = scrollback.live_cell(idx + 1, cols);
+                            cell_buf[next_idx] = cell.to_cell_dynamic(id + 1);
+                            links[next_idx] = link;
+                            dirty_regions.mark(next_idx);
+                            skip_idx = Some(idx + 1);
+                        }
+                    },
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Updates a single cell at the given grid coordinates.
+    ///
+    /// # Errors
+    /// This method is infallible in the current implementation but returns
+    /// `Result` for API consistency with batch update methods.
+    pub fn update_cell(&mut self, x: u16, y: u16, cell_data: CellData) -> Result<(), Error> {
+        let cols = self.terminal_size.cols;
+        let idx = y as usize * cols as usize + x as usize;
+        self.update_cell_by_index(idx, cell_data)
+    }
+
+    /// Updates a single cell at the given flat index.
+    ///
+    /// # Errors
+    /// This method is infallible in the current implementation but returns
+    /// `Result` for API consistency with batch update methods.
+    pub fn update_cell_by_index(&mut self, idx: usize, cell_data: CellData) -> Result<(), Error> {
+        self.update_cells_by_index(std::iter::once((idx, cell_data)))
+    }
+
+    /// Sets the DEC line attribute of a row on the live screen, making it
+    /// double-width or one half of a double-height row.
+    ///
+    /// The attribute belongs to the row rather than its cells: it is kept when
+    /// cells are updated, moves along when the row is scrolled, and is reset
+    /// for rows vacated by scrolling. Rows outside the screen are ignored.
+    ///
+    /// Cells keep their logical columns: on a double-width row, column `n`
+    /// is drawn across the screen columns `2n` and `2n + 1`. Cells past the
+    /// middle of the row are not drawn.
+    pub fn set_line_attribute(&mut self, row: u16, line_attribute: LineAttribute) {
+        if row < self.terminal_size.rows {
+            let line = self.scrollback.live_line(row as usize);
+            self.set_ring_line_attribute(line, line_attribute);
+        }
+    }
+
+    /// Returns the DEC line attribute of a row on the live screen, or
+    /// [`LineAttribute::Single`] for rows outside the screen.
+    #[must_use]
+    pub fn line_attribute(&self, row: u16) -> LineAttribute {
+        if row >= self.terminal_size.rows {
+            return LineAttribute::Single;
+        }
+
+        self.line_attributes[self.scrollback.live_line(row as usize)]
+    }
+
+    /// Returns the DEC line attribute of a viewport row, or
+    /// [`LineAttribute::Single`] for rows outside the viewport.
+    ///
+    /// Use this to map screen columns to cells, e.g. for hit-testing.
+    #[must_use]
+    pub fn viewport_line_attribute(&self, row: u16) -> LineAttribute {
+        if row >= self.terminal_size.rows {
+            return LineAttribute::Single;
+        }
+
+        self.line_attributes[self.scrollback.viewport_line(row as usize)]
+    }
+
+    /// Returns the DEC line attributes of the rows currently shown in the
+    /// viewport, to be rendered along with [`viewport_cells`](Self::viewport_cells).
+    #[must_use]
+    pub fn viewport_line_attributes(&self) -> Vec<LineAttribute> {
+        (0..self.terminal_size.rows)
+            .map(|row| self.viewport_line_attribute(row))
+            .collect()
+    }
+
+    /// Sets the line attribute of a line of the cell ring.
+    fn set_ring_line_attribute(&mut self, line: usize, line_attribute: LineAttribute) {
+        if self.line_attributes[line] != line_attribute {
+            self.line_attributes[line] = line_attribute;
+            self.line_attributes_dirty = true;
+        }
+    }
+
+    /// Marks whether a row on the live screen was soft-wrapped, i.e. its
+    /// text continues on the next row rather than ending with a line break.
+    ///
+    /// Like line attributes, the flag belongs to the row: it moves along when
+    /// the row is scrolled and is reset for rows vacated by scrolling. Text
+    /// search joins wrapped rows into one line. Rows outside the screen are
+    /// ignored.
+    pub fn set_line_wrapped(&mut self, row: u16, wrapped: bool) {
+        if row < self.terminal_size.rows {
+            let line = self.scrollback.live_line(row as usize);
+            self.wrapped[line] = wrapped;
+        }
+    }
+
+    /// Returns `true` if a row on the live screen continues on the next row.
+    #[must_use]
+    pub fn is_line_wrapped(&self, row: u16) -> bool {
+        row < self.terminal_size.rows && self.wrapped[self.scrollback.live_line(row as usize)]
+    }
+
+    /// Returns `true` if a viewport row continues on the next row.
+    pub(crate) fn is_viewport_line_wrapped(&self, row: u16) -> bool {
+        row < self.terminal_size.rows && self.wrapped[self.scrollback.viewport_line(row as usize)]
+    }
+
+    /// Registers a hyperlink target, e.g. from an OSC 8 escape sequence, for
+    /// linking cells with [`CellData::with_hyperlink`].
+    ///
+    /// Links registered with the same `id` parameter and `uri` share a single
+    /// target, so that cells printed separately form one link. Anonymous links
+    /// always register a new target. Targets no longer referenced by any cell
+    /// are eventually released.
+    pub fn register_hyperlink(&mut self, uri: &str, id: Option<&str>) -> HyperlinkId {
+        if self.hyperlinks.needs_pruning() {
+            self.hyperlinks.prune(&self.links);
+        }
+
+        self.hyperlinks.register(uri, id)
+    }
+
+    /// Returns the hyperlink at a viewport position, with the URI of its
+    /// target and a query spanning all contiguous cells of the link, which
+    /// may wrap across rows.
+    ///
+    /// Returns `None` if the cell at the position is not linked.
+    #[must_use]
+    pub fn hyperlink_at(&self, cursor: CursorPosition) -> Option<UrlMatch> {
+        let cols = self.terminal_size.cols as usize;
+        let idx = cursor.row as usize * cols + cursor.col as usize;
+        if cursor.col as usize >= cols || idx >= self.cell_count() {
+            return None;
+        }
+
+        let link_at = |idx: usize| self.links[self.scrollback.viewport_cell(idx, cols)];
+        let link = link_at(idx);
+        let uri = self.hyperlinks.uri(link)?;
+
+        let start = (0..idx)
+            .rev()
+            .find(|&i| link_at(i) != link)
+            .map_or(0, |i| i + 1);
+        let end = (idx + 1..self.cell_count())
+            .find(|&i| link_at(i) != link)
+            .unwrap_or(self.cell_count())
+            - 1;
+
+        let position = |idx: usize| ((idx % cols) as u16, (idx / cols) as u16);
+        let query = select(SelectionMode::Linear)
+            .start(position(start))
+            .end(position(end));
+
+        Some(UrlMatch { query, url: CompactString::new(uri) })
+    }
+
+    /// Sets the maximum number of lines kept in the scrollback history.
+    ///
+    /// The most recent history lines are kept when the capacity shrinks, and
+    /// the viewport is reset to the live screen. A capacity of `0` disables
+    /// the scrollback history.
+    pub fn set_scrollback_capacity(&mut self, lines: usize) {
+        if lines == self.scrollback.capacity() {
+            return;
+        }
+
+        self.clear_selection();
+        self.resize_cell_grid(self.terminal_size, lines);
+    }
+
+    /// Returns the maximum number of lines kept in the scrollback history.
+    #[must_use]
+    pub fn scrollback_capacity(&self) -> usize {
+        self.scrollback.capacity()
+    }
+
+    /// Returns the number of lines currently stored in the scrollback history.
+    #[must_use]
+    pub fn scrollback_len(&self) -> usize {
+        self.scrollback.history_len()
+    }
+
+    /// Returns how many lines the viewport is scrolled back into the history.
+    #[must_use]
+    pub fn scrollback_offset(&self) -> usize {
+        self.scrollback.viewport_offset()
+    }
+
+    /// Scrolls the viewport `lines` back into the scrollback history, where
+    /// `0` shows the live screen. The offset is clamped to the stored history.
+    /// Any active selection is cleared.
+    ///
+    /// Returns the effective offset.
+    pub fn set_scrollback_offset(&mut self, lines: usize) -> usize {
+        let offset = lines.min(self.scrollback.history_len());
+        if offset != self.scrollback.viewport_offset() {
+            self.clear_selection();
+            self.scrollback.set_viewport_offset(offset);
+        }
+
+        offset
+    }
+
+    /// Scrolls the rows `top..bottom` of the live screen by `lines`, like a
+    /// terminal scroll region (DECSTBM). Positive values move the content up,
+    /// negative values move it down. Rows scrolled out of the region are
+    /// discarded, and the vacated rows are filled with `fill`.
+    ///
+    /// Any active selection is cleared.
+    pub fn scroll_region(&mut self, top: u16, bottom: u16, lines: i32, fill: CellData) {
+        if self.is_noop_region_scroll(top, bottom, lines) {
+            return;
+        }
+
+        self.clear_selection();
+        for (_, dst, count) in self.move_region(top, bottom, lines, fill) {
+            self.dirty_regions.mark_range(dst..dst + count);
+        }
+    }
+
+    /// Returns `true` if scrolling `top..bottom` by `lines` changes nothing.
+    pub(super) fn is_noop_region_scroll(&self, top: u16, bottom: u16, lines: i32) -> bool {
+        top >= bottom.min(self.terminal_size.rows) || lines == 0
+    }
+
+    /// Moves the cells of a region scroll and fills the vacated rows, marking
+    /// only the vacated rows as dirty.
+    ///
+    /// Returns the performed moves as `(src, dst, count)` cell ranges within
+    /// the ring, in copy order, so that a GPU mirror can replay them.
+    pub(super) fn move_region(
+        &mut self,
+        top: u16,
+        bottom: u16,
+        lines: i32,
+        fill: CellData,
+    ) -> Vec<(usize, usize, usize)> {
+        if self.is_noop_region_scroll(top, bottom, lines) {
+            return Vec::new();
+        }
+
+        let (top, bottom) = (top as usize, bottom.min(self.terminal_size.rows) as usize);
+        let cols = self.terminal_size.cols as usize;
+
+        let line_moves = self
+            .scrollback
+            .region_moves(top, bottom, lines as isize);
+        for &(src, dst, count) in &line_moves {
+            self.wrapped.copy_within(src..src + count, dst);
+            if self.line_attributes[src..src + count] != self.line_attributes[dst..dst + count] {
+                self.line_attributes
+                    .copy_within(src..src + count, dst);
+                self.line_attributes_dirty = true;
+            }
+        }
+
+        let moves: Vec<_> = line_moves
+            .into_iter()
+            .map(|(src, dst, count)| (src * cols, dst * cols, count * cols))
+            .collect();
+
+        for &(src, dst, count) in &moves {
+            self.cells.copy_within(src..src + count, dst);
+            self.links.copy_within(src..src + count, dst);
+        }
+
+        let distance = (lines.unsigned_abs() as usize).min(bottom - top);
+        let vacated = if lines > 0 { bottom - distance..bottom } else { top..top + distance };
+
+        let fill_cell = fill.to_cell_dynamic(self.resolve_fill_glyph(fill));
+        for row in vacated {
+            let line = self.scrollback.live_line(row);
+            let range = line * cols..(line + 1) * cols;
+            self.cells[range.clone()].fill(fill_cell);
+            self.links[range.clone()].fill(0);
+            self.wrapped[line] = false;
+            self.set_ring_line_attribute(line, LineAttribute::Single);
+            self.dirty_regions.mark_range(range);
+        }
+
+        moves
+    }
+
+    /// Scrolls the live screen up by `lines`, moving its top rows into the
+    /// scrollback history. The vacated rows at the bottom are filled with `fill`.
+    ///
+    /// Existing cell data is never moved; only the vacated rows are marked as
+    /// modified. A scrolled-back viewport stays anchored to the lines it shows.
+    pub fn scroll_into_history(&mut self, lines: u16, fill: CellData) {
+        if lines == 0 {
+            return;
+        }
+
+        let fill_cell = fill.to_cell_dynamic(self.resolve_fill_glyph(fill));
+        let cols = self.terminal_size.cols as usize;
+        let offset_before = self.scrollback.viewport_offset();
+
+        for line in self.scrollback.push(lines as usize) {
+            let range = line * cols..(line + 1) * cols;
+            self.cells[range.clone()].fill(fill_cell);
+            self.links[range.clone()].fill(0);
+            self.wrapped[line] = false;
+            self.set_ring_line_attribute(line, LineAttribute::Single);
+            self.dirty_regions.mark_range(range);
+        }
+
+        let offset = self.scrollback.viewport_offset();
+        if offset_before == 0 || offset != offset_before + lines as usize {
+            // the content moved underneath the selection
+            self.clear_selection();
+        }
+    }
+
+    /// Returns the scrollback ring mapping rows to lines of the cell buffer.
+    pub(super) fn scrollback(&self) -> ScrollbackRing {
+        self.scrollback
+    }
+
+    /// Returns the cell buffer, laid out as a ring of lines.
+    pub(super) fn cells(&self) -> &[CellDynamic] {
+        &self.cells
+    }
+
+    /// Returns the line attribute of each line of the cell ring if they
+    /// changed since the last call, and marks them as synchronized.
+    pub(super) fn take_line_attribute_update(&mut self) -> Option<&[LineAttribute]> {
+        std::mem::take(&mut self.line_attributes_dirty).then_some(&self.line_attributes[..])
+    }
+
+    /// Returns the line attribute of each line of the cell ring.
+    pub(super) fn line_attributes(&self) -> &[LineAttribute] {
+        &self.line_attributes
+    }
+
+    /// Returns the cell buffer along with the regions modified since the last
+    /// GPU synchronization.
+    pub(super) fn cells_and_dirty_regions(&mut self) -> (&[CellDynamic], &mut DirtyRegions) {
+        (&self.cells, &mut self.dirty_regions)
+    }
+
+    /// Clears the selection if the content underneath it has changed since the
+    /// selection was completed.
+    pub(super) fn clear_stale_selection(&self) {
+        if let Some(query) = self.selection_tracker().get_query()
+            && let Some(hash) = query.content_hash
+            && hash != self.hash_cells(query)
+        {
+            self.selection.clear();
+        }
+    }
+
+    /// Clears the selection. The highlight is drawn by the shader, so no cell
+    /// data needs to be re-uploaded.
+    pub(super) fn clear_selection(&self) {
+        self.selection.clear();
+    }
+
+    /// Resolves the glyph used for filling cells, ignoring double-width glyphs.
+    fn resolve_fill_glyph(&mut self, fill: CellData) -> u16 {
+        match self
+            .atlas
+            .resolve_glyph_slot(fill.symbol, fill.style_bits)
+        {
+            Some(GlyphSlot::Normal(id)) => id,
+            _ => self.fallback_glyph,
+        }
+    }
+
+    /// Lays out the cells in a new ring of `new_size` and `capacity` history lines.
+    ///
+    /// The retained history lines are placed first, followed by the live screen,
+    /// and the viewport is reset to the live screen. Cells outside the new size
+    /// are discarded.
+    fn resize_cell_grid(&mut self, new_size: TerminalSize, capacity: usize) {
+        let empty_cell = CellDynamic::new(space_glyph_id(&mut self.atlas), 0xFFFFFF, 0x000000);
+
+        let (old_cols, old_rows) = (
+            self.terminal_size.cols as usize,
+            self.terminal_size.rows as usize,
+        );
+        let (new_cols, new_rows) = (new_size.cols as usize, new_size.rows as usize);
+
+        let old_ring = self.scrollback;
+        let history = old_ring.history_len().min(capacity);
+        let new_ring = ScrollbackRing::with_history(new_rows, capacity, history);
+
+        let mut new_cells = vec![empty_cell; new_ring.ring_lines() * new_cols];
+        let mut new_links = vec![0; new_cells.len()];
+        let mut new_wrapped = vec![false; new_ring.ring_lines()];
+        let mut new_line_attributes = vec![LineAttribute::Single; new_ring.ring_lines()];
+
+        // most recent history lines first, then the live screen
+        let old_lines = (old_ring.history_len() - history..old_ring.history_len())
+            .map(|line| old_ring.history_line(line))
+            .chain((0..min(old_rows, new_rows)).map(|row| old_ring.live_line(row)));
+
+        let cols = min(old_cols, new_cols);
+        for (new_line, old_line) in old_lines.enumerate() {
+            let src = old_line * old_cols;
+            let dst = new_line * new_cols;
+            new_cells[dst..dst + cols].copy_from_slice(&self.cells[src..src + cols]);
+            new_links[dst..dst + cols].copy_from_slice(&self.links[src..src + cols]);
+            new_wrapped[new_line] = self.wrapped[old_line];
+            new_line_attributes[new_line] = self.line_attributes[old_line];
+        }
+
+        self.dirty_regions.resize(new_cells.len());
+        self.dirty_regions.mark_all();
+
+        self.cells = new_cells;
+        self.links = new_links;
+        self.wrapped = new_wrapped;
+        self.line_attributes = new_line_attributes;
+        self.line_attributes_dirty = true;
+        self.scrollback = new_ring;
+        self.terminal_size = new_size;
+    }
+}
+
+/// Returns the glyph id of an unstyled space.
+fn space_glyph_id(atlas: &mut impl GlyphLookup) -> u16 {
+    atlas
+        .resolve_glyph_slot(" ", 0x0)
+        .map_or(' ' as u16, |slot| slot.slot_id())
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, Glyph};
+
+    use super::*;
+    use crate::{
+        CellColor, SelectionMode, StaticGlyphLookup, UnderlineStyle, find_url_at_cursor_in_model,
+        select,
+    };
+
+    fn model(cols: u16, rows: u16) -> GridModel<StaticGlyphLookup> {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        GridModel::new(lookup, TerminalSize::new(cols, rows))
+    }
+
+    fn write_row(model: &mut GridModel<StaticGlyphLookup>, row: u16, text: &str) {
+        let symbols: Vec<String> = text.chars().map(String::from).collect();
+        let cells = symbols.iter().enumerate().map(|(col, s)| {
+            (
+                col as u16,
+                row,
+                CellData::new_with_style_bits(s, 0, 0xFFFFFF, 0),
+            )
+        });
+
+        model.update_cells_by_position(cells).unwrap();
+    }
+
+    fn row_text(model: &GridModel<StaticGlyphLookup>, row: u16) -> CompactString {
+        let cols = model.terminal_size().cols;
+        let query = select(SelectionMode::Linear)
+            .start((0, row))
+            .end((cols - 1, row));
+
+        model.get_text(query)
+    }
+
+    #[test]
+    fn underline_attributes_are_packed_into_cells() {
+        let mut model = model(2, 1);
+        let curly = CellData::new_with_style_bits("x", 0, 0xFFFFFF, 0)
+            .with_underline(UnderlineStyle::Curly)
+            .with_underline_color(0xFF0000);
+        let plain = CellData::new_with_style_bits("y", 0, 0xFFFFFF, 0);
+        model
+            .update_cells_by_position([(0, 0, curly), (1, 0, plain)].into_iter())
+            .unwrap();
+
+        let cell = model.cell_data_mut(0, 0).copied().unwrap();
+        assert_eq!(cell.get_style(), Glyph::UNDERLINE_FLAG);
+        assert_eq!(cell.get_underline_style(), UnderlineStyle::Curly);
+        assert_eq!(cell.get_underline_color(), Some(0xFF0000));
+
+        let cell = model.cell_data_mut(1, 0).copied().unwrap();
+        assert_eq!(cell.get_style(), 0);
+        assert_eq!(cell.get_underline_color(), None);
+    }
+
+    #[test]
+    fn palette_colors_survive_color_flips() {
+        let mut model = model(1, 1);
+        let cell = CellData::new_with_style_bits("x", 0, 0, 0)
+            .with_fg(CellColor::Indexed(9))
+            .with_bg(CellColor::Rgb(0x123456));
+        model
+            .update_cells_by_position(std::iter::once((0, 0, cell)))
+            .unwrap();
+
+        let cell = model.cell_data_mut(0, 0).unwrap();
+        assert_eq!(cell.get_fg(), CellColor::Indexed(9));
+        assert_eq!(cell.get_bg(), CellColor::Rgb(0x123456));
+
+        cell.flip_colors();
+        assert_eq!(cell.get_fg(), CellColor::Rgb(0x123456));
+        assert_eq!(cell.get_bg(), CellColor::Indexed(9));
+
+        cell.bg(CellColor::DefaultBg);
+        cell.fg_color(0xABCDEF);
+        assert_eq!(cell.get_fg(), CellColor::Rgb(0xABCDEF));
+        assert_eq!(cell.get_bg(), CellColor::DefaultBg);
+    }
+
+    #[test]
+    fn packed_colors_round_trip_in_twelve_bytes() {
+        assert_eq!(size_of::<CellDynamic>(), 12);
+
+        let colors = [
+            CellColor::Rgb(0x123456),
+            CellColor::Rgb(0xFFFFFF),
+            CellColor::Indexed(9),
+            CellColor::Indexed(255),
+            CellColor::DefaultFg,
+            CellColor::DefaultBg,
+        ];
+        let underlines = [None, Some(0x000000), Some(0xABCDEF)];
+        let styles = [UnderlineStyle::Single, UnderlineStyle::Curly, UnderlineStyle::Dashed];
+
+        for (fg, bg) in colors
+            .iter()
+            .flat_map(|&fg| colors.iter().map(move |&bg| (fg, bg)))
+        {
+            for underline in underlines {
+                for style in styles {
+                    let mut cell = CellDynamic::new(0x1234, 0, 0);
+                    cell.underline_style(style);
+                    cell.fg(fg);
+                    cell.bg(bg);
+                    cell.underline_color(underline);
+
+                    let expect = |cell: &CellDynamic, fg, bg| {
+                        assert_eq!(cell.get_fg(), fg);
+                        assert_eq!(cell.get_bg(), bg);
+                        assert_eq!(cell.get_underline_color(), underline);
+                        assert_eq!(cell.get_underline_style(), style);
+                        assert_eq!(cell.glyph_id(), 0x1234);
+                    };
+                    expect(&cell, fg, bg);
+
+                    cell.flip_colors();
+                    expect(&cell, bg, fg);
+
+                    cell.flip_colors();
+                    expect(&cell, fg, bg);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn text_round_trips_through_cells() {
+        let mut model = model(8, 2);
+        write_row(&mut model, 1, "beamterm");
+
+        assert_eq!(row_text(&model, 0), "        ");
+        assert_eq!(row_text(&model, 1), "beamterm");
+    }
+
+    #[test]
+    fn history_is_shown_when_scrolled_back() {
+        let mut model = model(4, 2);
+        model.set_scrollback_capacity(8);
+        write_row(&mut model, 0, "abcd");
+
+        let fill = CellData::new_with_style_bits(" ", 0, 0xFFFFFF, 0);
+        model.scroll_into_history(1, fill);
+        assert_eq!(model.scrollback_len(), 1);
+        assert_eq!(row_text(&model, 0), "    ");
+
+        assert_eq!(model.set_scrollback_offset(5), 1);
+        assert_eq!(row_text(&model, 0), "abcd");
+    }
+
+    #[test]
+    fn scroll_region_moves_rows_and_fills_vacated() {
+        let mut model = model(3, 3);
+        write_row(&mut model, 0, "aaa");
+        write_row(&mut model, 1, "bbb");
+        write_row(&mut model, 2, "ccc");
+
+        let fill = CellData::new_with_style_bits("-", 0, 0xFFFFFF, 0);
+        model.scroll_region(0, 3, 1, fill);
+
+        assert_eq!(row_text(&model, 0), "bbb");
+        assert_eq!(row_text(&model, 1), "ccc");
+        assert_eq!(row_text(&model, 2), "---");
+    }
+
+    #[test]
+    fn line_attributes_follow_their_rows() {
+        let mut model = model(4, 3);
+        model.set_line_attribute(1, LineAttribute::DoubleWidth);
+        model.set_line_attribute(9, LineAttribute::DoubleWidth);
+        assert_eq!(model.line_attribute(1), LineAttribute::DoubleWidth);
+        assert_eq!(model.line_attribute(9), LineAttribute::Single);
+
+        // cell updates keep the attribute of the row
+        write_row(&mut model, 1, "ab");
+        assert_eq!(model.line_attribute(1), LineAttribute::DoubleWidth);
+
+        let fill = CellData::new_with_style_bits(" ", 0, 0xFFFFFF, 0);
+        model.scroll_region(0, 3, 1, fill);
+        assert_eq!(model.line_attribute(0), LineAttribute::DoubleWidth);
+        assert_eq!(row_text(&model, 0), "ab  ");
+        assert_eq!(model.line_attribute(2), LineAttribute::Single);
+
+        model.scroll_into_history(1, fill);
+        assert_eq!(
+            (0..3)
+                .map(|row| model.line_attribute(row))
+                .collect::<Vec<_>>(),
+            vec![LineAttribute::Single; 3]
+        );
+    }
+
+    #[test]
+    fn wrapped_lines_follow_their_rows() {
+        let mut model = model(4, 3);
+        model.set_line_wrapped(1, true);
+        model.set_line_wrapped(9, true);
+        assert!(model.is_line_wrapped(1));
+        assert!(!model.is_line_wrapped(9));
+
+        let fill = CellData::new_with_style_bits(" ", 0, 0xFFFFFF, 0);
+        model.scroll_region(0, 3, 1, fill);
+        assert!(model.is_line_wrapped(0));
+        assert!(!model.is_line_wrapped(1));
+
+        model.set_scrollback_capacity(2);
+        model.resize(TerminalSize::new(6, 3));
+        assert!(model.is_line_wrapped(0));
+
+        // scrolled into history, the row keeps its flag in the viewport
+        model.scroll_into_history(1, fill);
+        assert!(!model.is_line_wrapped(0));
+        model.set_scrollback_offset(1);
+        assert!(model.is_viewport_line_wrapped(0));
+    }
+
+    #[test]
+    fn resized_rows_keep_their_line_attribute() {
+        let mut model = model(2, 2);
+        model.set_line_attribute(1, LineAttribute::DoubleHeightTop);
+        model.set_scrollback_capacity(2);
+        model.resize(TerminalSize::new(6, 2));
+        assert_eq!(model.viewport_line_attributes(), vec![
+            LineAttribute::Single,
+            LineAttribute::DoubleHeightTop
+        ]);
+
+        // scrolled into history, the row keeps its attribute in the viewport
+        let fill = CellData::new_with_style_bits(" ", 0, 0xFFFFFF, 0);
+        model.scroll_into_history(2, fill);
+        assert_eq!(model.viewport_line_attributes(), vec![
+            LineAttribute::Single;
+            2
+        ]);
+        model.set_scrollback_offset(1);
+        assert_eq!(
+            model.viewport_line_attribute(0),
+            LineAttribute::DoubleHeightTop
+        );
+    }
+
+    #[test]
+    fn scrolling_clears_selection() {
+        let mut model = model(4, 2);
+        model.selection_tracker().set_query(
+            select(SelectionMode::Block)
+                .start((0, 0))
+                .end((1, 0)),
+        );
+
+        let fill = CellData::new_with_style_bits(" ", 0, 0xFFFFFF, 0);
+        model.scroll_into_history(1, fill);
+
+        assert!(model.selection_tracker().get_query().is_none());
+    }
+
+    #[test]
+    fn resize_keeps_overlapping_cells() {
+        let mut model = model(4, 2);
+        write_row(&mut model, 0, "wxyz");
+
+        assert!(model.resize(TerminalSize::new(2, 3)));
+        assert!(!model.resize(TerminalSize::new(2, 3)));
+        assert_eq!(model.cell_count(), 6);
+        assert_eq!(row_text(&model, 0), "wx");
+    }
+
+    #[test]
+    fn detects_urls_without_gl_context() {
+        let mut model = model(32, 1);
+        write_row(&mut model, 0, "see https://example.com/a. ok");
+
+        let url = find_url_at_cursor_in_model(CursorPosition::new(10, 0), &model).unwrap();
+        assert_eq!(url.url, "https://example.com/a");
+    }
+
+    fn write_link(
+        model: &mut GridModel<StaticGlyphLookup>,
+        idx: usize,
+        label: &str,
+        link: HyperlinkId,
+    ) {
+        let symbols: Vec<String> = label.chars().map(String::from).collect();
+        let cells = symbols.iter().enumerate().map(|(i, s)| {
+            let cell = CellData::new_with_style_bits(s, 0, 0xFFFFFF, 0).with_hyperlink(link);
+            (idx + i, cell)
+        });
+
+        model.update_cells_by_index(cells).unwrap();
+    }
+
+    #[test]
+    fn hyperlinks_span_wrapped_rows() {
+        let mut model = model(4, 3);
+        write_row(&mut model, 0, "ls: ");
+        let link = model.register_hyperlink("file:///home/user/notes.txt", None);
+        write_link(&mut model, 2, "notes", link);
+
+        let hit = model
+            .hyperlink_at(CursorPosition::new(1, 1))
+            .unwrap();
+        assert_eq!(hit.url, "file:///home/user/notes.txt");
+        assert_eq!(hit.query.range(), Some(((2, 0), (2, 1))));
+        assert_eq!(model.get_text(hit.query), "no\ntes");
+
+        assert!(
+            model
+                .hyperlink_at(CursorPosition::new(1, 0))
+                .is_none()
+        );
+        assert!(
+            model
+                .hyperlink_at(CursorPosition::new(3, 1))
+                .is_none()
+        );
+
+        // overwriting a cell unlinks it
+        write_row(&mut model, 0, "ls: ");
+        let hit = model
+            .hyperlink_at(CursorPosition::new(0, 1))
+            .unwrap();
+        assert_eq!(hit.query.range(), Some(((0, 1), (2, 1))));
+    }
+
+    #[test]
+    fn hyperlinks_survive_resize_and_scrolling() {
+        let mut model = model(8, 2);
+        let link = model.register_hyperlink("https://gcc.gnu.org/", Some("warn"));
+        write_link(&mut model, 9, "gcc", link);
+
+        assert!(model.resize(TerminalSize::new(6, 3)));
+        let hit = model
+            .hyperlink_at(CursorPosition::new(2, 1))
+            .unwrap();
+        assert_eq!(hit.url, "https://gcc.gnu.org/");
+        assert_eq!(hit.query.range(), Some(((1, 1), (3, 1))));
+
+        let fill = CellData::new_with_style_bits(" ", 0, 0xFFFFFF, 0);
+        model.scroll_region(0, 3, 1, fill);
+        let hit = model
+            .hyperlink_at(CursorPosition::new(1, 0))
+            .unwrap();
+        assert_eq!(hit.query.range(), Some(((1, 0), (3, 0))));
+        assert!(
+            model
+                .hyperlink_at(CursorPosition::new(1, 1))
+                .is_none()
+        );
+    }
+}
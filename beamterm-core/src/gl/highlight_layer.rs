@@ -0,0 +1,491 @@
+use beamterm_data::TerminalSize;
+use glow::HasContext;
+
+use crate::{
+    SelectionMode,
+    error::Error,
+    gl::{
+        CellQuery, GlState, GlyphLookup, GridModel, ShaderProgram, buffer, image_layer::ImageFrame,
+    },
+};
+
+/// Identifier of a highlight added to a [`TerminalGrid`](crate::TerminalGrid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HighlightId(u32);
+
+/// Tints the background of a range of cells, e.g. a search hit or a
+/// diagnostic span.
+///
+/// Highlights are drawn between the cell backgrounds and the text, so the
+/// cell data is left untouched. Where highlights overlap, only the one with
+/// the highest priority is drawn; the selection is drawn over all of them.
+///
+/// # Example
+/// ```
+/// use beamterm_core::{Highlight, SelectionMode, select};
+///
+/// let hit = select(SelectionMode::Linear).start((4, 2)).end((9, 2));
+///
+/// let search_hit = Highlight::new(hit, 0x515C6A).with_alpha(0.6);
+/// let current_match = Highlight::new(hit, 0xE5C07B).with_priority(1);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Highlight {
+    /// The highlighted cells, in viewport coordinates.
+    pub query: CellQuery,
+    /// Tint color as 0xRRGGBB.
+    pub color: u32,
+    /// Opacity of the tint over the cell background, from `0.0` to `1.0`.
+    pub alpha: f32,
+    /// Overlap resolution; higher priorities win. Highlights with equal
+    /// priority are resolved in favor of the most recently added one.
+    pub priority: i32,
+}
+
+impl Highlight {
+    /// Highlights the cells of `query` with an opaque `color`.
+    #[must_use]
+    pub const fn new(query: CellQuery, color: u32) -> Self {
+        Self { query, color, alpha: 1.0, priority: 0 }
+    }
+
+    /// Sets the opacity of the tint, from `0.0` to `1.0`.
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Sets the priority of the highlight where highlights overlap.
+    #[must_use]
+    pub const fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns the tint as normalized RGBA.
+    fn rgba(&self) -> [f32; 4] {
+        let channel = |shift: u32| ((self.color >> shift) & 0xFF) as f32 / 255.0;
+        [channel(16), channel(8), channel(0), self.alpha.clamp(0.0, 1.0)]
+    }
+}
+
+/// Consecutive cells of a row sharing the same highlight, in screen columns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HighlightRun {
+    col: u16,
+    row: u16,
+    len: u16,
+    rgba: [f32; 4],
+}
+
+/// Instance data of a highlight run, as read by `highlight.vert`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct RunInstance {
+    /// Column, row and length of the run, in cells.
+    cells: [f32; 3],
+    /// Tint color with non-premultiplied alpha.
+    rgba: [f32; 4],
+}
+
+impl From<HighlightRun> for RunInstance {
+    fn from(run: HighlightRun) -> Self {
+        Self {
+            cells: [run.col as f32, run.row as f32, run.len as f32],
+            rgba: run.rgba,
+        }
+    }
+}
+
+/// Inputs the resolved runs depend on, besides the highlights themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RunLayout {
+    selection: Option<CellQuery>,
+    size: TerminalSize,
+    viewport_offset: usize,
+}
+
+/// Highlighted cell ranges drawn over the cell backgrounds of a
+/// [`TerminalGrid`].
+///
+/// [`TerminalGrid`]: crate::TerminalGrid
+#[derive(Debug)]
+pub(super) struct HighlightLayer {
+    /// Shader program and vertex array for drawing highlight quads.
+    gpu: HighlightGpuResources,
+    /// Highlights sorted by priority, then insertion order.
+    highlights: Vec<(HighlightId, Highlight)>,
+    /// Layout the uploaded runs were resolved against, or `None` if stale.
+    synced_layout: Option<RunLayout>,
+    /// Number of runs in the instance buffer.
+    run_count: usize,
+    /// Next highlight identifier.
+    next_id: u32,
+}
+
+#[derive(Debug)]
+struct HighlightGpuResources {
+    shader: ShaderProgram,
+    /// Vertex array of the run instances; quad corners are derived from `gl_VertexID`.
+    vao: glow::VertexArray,
+    /// One [`RunInstance`] per highlight run.
+    instance_buffer: glow::Buffer,
+    projection_loc: glow::UniformLocation,
+    cell_size_loc: glow::UniformLocation,
+    scroll_offset_loc: glow::UniformLocation,
+}
+
+impl HighlightGpuResources {
+    const FRAGMENT_GLSL: &'static str = include_str!("../shaders/highlight.frag");
+    const VERTEX_GLSL: &'static str = include_str!("../shaders/highlight.vert");
+
+    fn new(gl: &glow::Context, glsl_version: crate::GlslVersion) -> Result<Self, Error> {
+        let vertex_source = format!("{}{}", glsl_version.vertex_preamble(), Self::VERTEX_GLSL);
+        let fragment_source = format!(
+            "{}{}",
+            glsl_version.fragment_preamble(),
+            Self::FRAGMENT_GLSL
+        );
+        let shader = ShaderProgram::create(gl, &vertex_source, &fragment_source)?;
+
+        let uniform_location = |name: &'static str| {
+            unsafe { gl.get_uniform_location(shader.program, name) }
+                .ok_or(Error::uniform_location_failed(name))
+        };
+        let projection_loc = uniform_location("u_projection")?;
+        let cell_size_loc = uniform_location("u_cell_size")?;
+        let scroll_offset_loc = uniform_location("u_scroll_offset")?;
+
+        let vao =
+            unsafe { gl.create_vertex_array() }.map_err(Error::vertex_array_creation_failed)?;
+        let instance_buffer = unsafe { gl.create_buffer() }
+            .map_err(|e| Error::buffer_creation_failed("highlight-instance-buffer", e))?;
+
+        let stride = size_of::<RunInstance>() as i32;
+        unsafe {
+            gl.bind_vertex_array(Some(vao));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(instance_buffer));
+            for (index, size, offset) in [(0, 3, 0), (1, 4, size_of::<[f32; 3]>() as i32)] {
+                gl.enable_vertex_attrib_array(index);
+                gl.vertex_attrib_pointer_f32(index, size, glow::FLOAT, false, stride, offset);
+                gl.vertex_attrib_divisor(index, 1);
+            }
+            gl.bind_vertex_array(None);
+            gl.bind_buffer(glow::ARRAY_BUFFER, None);
+        }
+
+        Ok(Self {
+            shader,
+            vao,
+            instance_buffer,
+            projection_loc,
+            cell_size_loc,
+            scroll_offset_loc,
+        })
+    }
+
+    fn delete(&self, gl: &glow::Context) {
+        self.shader.delete(gl);
+        unsafe {
+            gl.delete_vertex_array(self.vao);
+            gl.delete_buffer(self.instance_buffer);
+        }
+    }
+}
+
+impl HighlightLayer {
+    pub(super) fn new(gl: &glow::Context, glsl_version: crate::GlslVersion) -> Result<Self, Error> {
+        Ok(Self {
+            gpu: HighlightGpuResources::new(gl, glsl_version)?,
+            highlights: Vec::new(),
+            synced_layout: None,
+            run_count: 0,
+            next_id: 0,
+        })
+    }
+
+    /// Adds a highlight.
+    pub(super) fn add(&mut self, highlight: Highlight) -> HighlightId {
+        let id = HighlightId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+
+        let idx = self
+            .highlights
+            .partition_point(|(_, h)| h.priority <= highlight.priority);
+        self.highlights.insert(idx, (id, highlight));
+        self.synced_layout = None;
+
+        id
+    }
+
+    /// Removes a highlight.
+    pub(super) fn remove(&mut self, id: HighlightId) -> bool {
+        let len = self.highlights.len();
+        self.highlights
+            .retain(|(highlight_id, _)| *highlight_id != id);
+        if self.highlights.len() == len {
+            return false;
+        }
+
+        self.synced_layout = None;
+        true
+    }
+
+    /// Returns the highlights in ascending priority.
+    pub(super) fn highlights(&self) -> impl Iterator<Item = (HighlightId, &Highlight)> {
+        self.highlights
+            .iter()
+            .map(|(id, highlight)| (*id, highlight))
+    }
+
+    /// Removes all highlights.
+    pub(super) fn clear(&mut self) {
+        self.highlights.clear();
+        self.synced_layout = None;
+    }
+
+    /// Returns `true` if there are no highlights.
+    pub(super) fn is_empty(&self) -> bool {
+        self.highlights.is_empty()
+    }
+
+    /// Resolves the highlight runs, leaving out the cells of `selection`, and
+    /// uploads them to the instance buffer. The previous runs are kept while
+    /// the highlights, the selection and the grid layout are unchanged, and,
+    /// for word queries, while `content_changed` is `false`.
+    pub(super) fn sync<A: GlyphLookup>(
+        &mut self,
+        gl: &glow::Context,
+        model: &GridModel<A>,
+        selection: Option<CellQuery>,
+        content_changed: bool,
+    ) {
+        let layout = RunLayout {
+            selection,
+            size: model.terminal_size(),
+            viewport_offset: model.scrollback_offset(),
+        };
+
+        let word_queries = || {
+            self.highlights
+                .iter()
+                .map(|(_, highlight)| highlight.query)
+                .chain(selection)
+                .any(|query| query.mode == SelectionMode::Word)
+        };
+        if self.synced_layout == Some(layout) && !(content_changed && word_queries()) {
+            return;
+        }
+
+        let instances: Vec<RunInstance> = resolve_runs(&self.highlights, model, selection)
+            .into_iter()
+            .map(RunInstance::from)
+            .collect();
+
+        if !instances.is_empty() {
+            unsafe {
+                gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.gpu.instance_buffer));
+                buffer::buffer_upload_array(gl, glow::ARRAY_BUFFER, &instances, glow::DYNAMIC_DRAW);
+                gl.bind_buffer(glow::ARRAY_BUFFER, None);
+            }
+        }
+
+        self.run_count = instances.len();
+        self.synced_layout = Some(layout);
+    }
+
+    /// Draws the runs resolved by the last [`sync`](Self::sync) as one
+    /// instanced batch, blended over the framebuffer. Leaves the highlight
+    /// program bound; the caller restores its own program and vertex array.
+    pub(super) fn draw(&self, gl: &glow::Context, state: &mut GlState, frame: &ImageFrame) {
+        if self.run_count == 0 {
+            return;
+        }
+
+        self.gpu.shader.use_program(gl);
+        state.blend(gl, true).blend_func_separate(
+            gl,
+            glow::SRC_ALPHA,
+            glow::ONE_MINUS_SRC_ALPHA,
+            glow::ONE,
+            glow::ONE_MINUS_SRC_ALPHA,
+        );
+
+        let (cw, ch) = (frame.cell_size.width as f32, frame.cell_size.height as f32);
+        let (scroll_x, scroll_y) = frame.scroll_offset;
+        unsafe {
+            gl.bind_vertex_array(Some(self.gpu.vao));
+            gl.uniform_matrix_4_f32_slice(Some(&self.gpu.projection_loc), false, &frame.projection);
+            gl.uniform_2_f32(Some(&self.gpu.cell_size_loc), cw, ch);
+            gl.uniform_2_f32(Some(&self.gpu.scroll_offset_loc), scroll_x, scroll_y);
+            gl.draw_arrays_instanced(glow::TRIANGLE_STRIP, 0, 4, self.run_count as i32);
+            gl.bind_vertex_array(None);
+        }
+    }
+
+    /// Recreates the shader program after a context loss.
+    ///
+    /// Old GL resources are already invalid, so they are not deleted.
+    pub(super) fn recreate(
+        &mut self,
+        gl: &glow::Context,
+        glsl_version: crate::GlslVersion,
+    ) -> Result<(), Error> {
+        self.gpu = HighlightGpuResources::new(gl, glsl_version)?;
+        self.synced_layout = None;
+        Ok(())
+    }
+
+    /// Deletes all GPU resources of the layer.
+    pub(super) fn delete(&self, gl: &glow::Context) {
+        self.gpu.delete(gl);
+    }
+}
+
+/// Resolves overlapping highlights to the highest priority per cell and
+/// merges the cells into runs per row. Runs on double-width rows span twice
+/// as many screen columns, and cells past the middle of those rows are dropped
+/// like the cells they highlight.
+fn resolve_runs<A: GlyphLookup>(
+    highlights: &[(HighlightId, Highlight)],
+    model: &GridModel<A>,
+    selection: Option<CellQuery>,
+) -> Vec<HighlightRun> {
+    const NONE: usize = usize::MAX;
+    const SELECTED: usize = usize::MAX - 1;
+
+    if highlights.is_empty() {
+        return Vec::new();
+    }
+
+    let mut owners = vec![NONE; model.cell_count()];
+    if let Some(selection) = selection {
+        model
+            .cell_iter(selection)
+            .for_each(|(idx, _)| owners[idx] = SELECTED);
+    }
+
+    // the highest priority claims its cells first
+    for (owner, (_, highlight)) in highlights.iter().enumerate().rev() {
+        for (idx, _) in model.cell_iter(highlight.query) {
+            if owners[idx] == NONE {
+                owners[idx] = owner;
+            }
+        }
+    }
+
+    let cols = model.terminal_size().cols as usize;
+    let mut runs = Vec::new();
+    for (row, row_owners) in owners.chunks(cols.max(1)).enumerate() {
+        let width_scale = if model
+            .viewport_line_attribute(row as u16)
+            .is_double_width()
+        {
+            2
+        } else {
+            1
+        };
+        let visible_cols = cols / width_scale;
+
+        let mut col = 0;
+        while col < visible_cols {
+            let owner = row_owners[col];
+            let len = row_owners[col..visible_cols]
+                .iter()
+                .take_while(|&&o| o == owner)
+                .count();
+
+            if owner < highlights.len() {
+                runs.push(HighlightRun {
+                    col: (col * width_scale) as u16,
+                    row: row as u16,
+                    len: (len * width_scale) as u16,
+                    rgba: highlights[owner].1.rgba(),
+                });
+            }
+            col += len;
+        }
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, TerminalSize};
+
+    use super::*;
+    use crate::{LineAttribute, SelectionMode, StaticGlyphLookup, select};
+
+    fn highlight(
+        id: u32,
+        start: (u16, u16),
+        end: (u16, u16),
+        color: u32,
+    ) -> (HighlightId, Highlight) {
+        let query = select(SelectionMode::Linear)
+            .start(start)
+            .end(end);
+        (HighlightId(id), Highlight::new(query, color))
+    }
+
+    fn model() -> GridModel<StaticGlyphLookup> {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        GridModel::new(lookup, TerminalSize::new(6, 3))
+    }
+
+    fn runs(
+        highlights: &[(HighlightId, Highlight)],
+        model: &GridModel<StaticGlyphLookup>,
+        selection: Option<CellQuery>,
+    ) -> Vec<(u16, u16, u16, u32)> {
+        resolve_runs(highlights, model, selection)
+            .into_iter()
+            .map(|run| {
+                let [r, g, b, _] = run.rgba.map(|c| (c * 255.0).round() as u32);
+                (run.col, run.row, run.len, (r << 16) | (g << 8) | b)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn runs_follow_rows() {
+        let highlights = [highlight(0, (4, 0), (1, 1), 0xFF0000)];
+
+        assert_eq!(runs(&highlights, &model(), None), vec![
+            (4, 0, 2, 0xFF0000),
+            (0, 1, 2, 0xFF0000)
+        ]);
+    }
+
+    #[test]
+    fn runs_on_double_width_rows_span_screen_columns() {
+        let mut model = model();
+        model.set_line_attribute(0, LineAttribute::DoubleWidth);
+        let highlights = [highlight(0, (1, 0), (1, 1), 0xFF0000)];
+
+        // only the cells in the left half of the row are drawn
+        assert_eq!(runs(&highlights, &model, None), vec![
+            (2, 0, 4, 0xFF0000),
+            (0, 1, 2, 0xFF0000)
+        ]);
+    }
+
+    #[test]
+    fn higher_priorities_and_the_selection_win_overlaps() {
+        // sorted by ascending priority, as stored by the layer
+        let mut low = highlight(0, (0, 0), (5, 0), 0x0000FF);
+        low.1.priority = -1;
+        let highlights = [low, highlight(1, (2, 0), (3, 0), 0x00FF00)];
+        let selection = select(SelectionMode::Block)
+            .start((5, 0))
+            .end((5, 1));
+
+        assert_eq!(runs(&highlights, &model(), Some(selection)), vec![
+            (0, 0, 2, 0x0000FF),
+            (2, 0, 2, 0x00FF00),
+            (4, 0, 1, 0x0000FF)
+        ]);
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::HashMap, num::NonZeroU32};
+
+use compact_str::CompactString;
+
+/// Identifier of a hyperlink target registered with
+/// [`GridModel::register_hyperlink`](crate::GridModel::register_hyperlink).
+///
+/// Cells are linked to a target with [`CellData::with_hyperlink`](crate::CellData::with_hyperlink).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HyperlinkId(NonZeroU32);
+
+impl HyperlinkId {
+    /// Returns the per-cell representation of the id; `0` marks unlinked cells.
+    pub(super) const fn cell_value(id: Option<Self>) -> u32 {
+        match id {
+            Some(id) => id.0.get(),
+            None => 0,
+        }
+    }
+}
+
+/// Registered hyperlink targets, e.g. from OSC 8 escape sequences.
+///
+/// Targets are kept until no cell references them anymore; unreferenced
+/// targets are pruned once the registry has doubled in size since the last
+/// pruning. The most recently registered target is always kept, as it is
+/// usually the open OSC 8 link whose cells are yet to be written.
+#[derive(Debug)]
+pub(super) struct HyperlinkRegistry {
+    /// Target URI by cell value.
+    uris: HashMap<u32, CompactString>,
+    /// Explicitly identified links by `(id, uri)`, so that all cells printed
+    /// with the same OSC 8 `id` parameter share a single target.
+    by_key: HashMap<(CompactString, CompactString), u32>,
+    next_id: u32,
+    /// Cell value of the most recently registered target.
+    last_registered: u32,
+    /// Number of targets at which unreferenced targets are pruned.
+    prune_threshold: usize,
+}
+
+impl HyperlinkRegistry {
+    const MIN_PRUNE_THRESHOLD: usize = 1024;
+
+    pub(super) fn new() -> Self {
+        Self {
+            uris: HashMap::new(),
+            by_key: HashMap::new(),
+            next_id: 1,
+            last_registered: 0,
+            prune_threshold: Self::MIN_PRUNE_THRESHOLD,
+        }
+    }
+
+    /// Registers a target, returning the existing id for a known `(id, uri)` pair.
+    pub(super) fn register(&mut self, uri: &str, id: Option<&str>) -> HyperlinkId {
+        let key = id.map(|id| (CompactString::new(id), CompactString::new(uri)));
+        let value = match key.as_ref().and_then(|key| self.by_key.get(key)) {
+            Some(&value) => value,
+            None => {
+                let value = self.allocate_id();
+                self.uris.insert(value, CompactString::new(uri));
+                if let Some(key) = key {
+                    self.by_key.insert(key, value);
+                }
+                value
+            },
+        };
+
+        self.last_registered = value;
+        HyperlinkId(NonZeroU32::new(value).expect("allocated ids are non-zero"))
+    }
+
+    /// Returns the URI of the target with the given cell value.
+    pub(super) fn uri(&self, cell_value: u32) -> Option<&str> {
+        self.uris
+            .get(&cell_value)
+            .map(CompactString::as_str)
+    }
+
+    /// Returns `true` if unreferenced targets should be pruned.
+    pub(super) fn needs_pruning(&self) -> bool {
+        self.uris.len() >= self.prune_threshold
+    }
+
+    /// Removes all targets not referenced by `cell_values`.
+    pub(super) fn prune(&mut self, cell_values: &[u32]) {
+        let mut referenced: Vec<u32> = cell_values
+            .iter()
+            .copied()
+            .filter(|&v| v != 0)
+            .collect();
+        referenced.sort_unstable();
+        referenced.dedup();
+
+        let last_registered = self.last_registered;
+        let is_referenced =
+            |value: &u32| *value == last_registered || referenced.binary_search(value).is_ok();
+        self.uris.retain(|value, _| is_referenced(value));
+        self.by_key
+            .retain(|_, value| is_referenced(value));
+
+        self.prune_threshold = (self.uris.len() * 2).max(Self::MIN_PRUNE_THRESHOLD);
+    }
+
+    /// Returns the next unused cell value, skipping `0` and live targets on wrap-around.
+    fn allocate_id(&mut self) -> u32 {
+        loop {
+            let value = self.next_id;
+            self.next_id = self.next_id.checked_add(1).unwrap_or(1);
+            if !self.uris.contains_key(&value) {
+                return value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identified_links_share_a_target() {
+        let mut registry = HyperlinkRegistry::new();
+
+        let a = registry.register("https://a.example", Some("1"));
+        let b = registry.register("https://a.example", Some("1"));
+        let c = registry.register("https://a.example", None);
+        let d = registry.register("https://a.example", None);
+        let e = registry.register("https://b.example", Some("1"));
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(c, d);
+        assert_ne!(a, e);
+        assert_eq!(
+            registry.uri(HyperlinkId::cell_value(Some(e))),
+            Some("https://b.example")
+        );
+    }
+
+    #[test]
+    fn prune_keeps_referenced_targets() {
+        let mut registry = HyperlinkRegistry::new();
+        let kept = registry.register("file:///kept", Some("k"));
+        let dropped = registry.register("file:///dropped", Some("d"));
+        let open = registry.register("file:///open", None);
+
+        let kept_value = HyperlinkId::cell_value(Some(kept));
+        registry.prune(&[0, kept_value, kept_value]);
+
+        assert_eq!(registry.uri(kept_value), Some("file:///kept"));
+        assert_eq!(registry.uri(HyperlinkId::cell_value(Some(dropped))), None);
+        assert_ne!(registry.register("file:///dropped", Some("d")), dropped);
+        // the latest target may not have been written to any cell yet
+        assert_eq!(
+            registry.uri(HyperlinkId::cell_value(Some(open))),
+            Some("file:///open")
+        );
+    }
+}
@@ -0,0 +1,657 @@
+use std::collections::HashMap;
+
+use beamterm_data::{CellSize, TerminalSize};
+use glow::HasContext;
+
+use crate::{
+    error::Error,
+    gl::{GlState, ShaderProgram},
+};
+
+/// Identifier of an image uploaded to a [`TerminalGrid`](crate::TerminalGrid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageId(pub(crate) u32);
+
+/// Identifier of an image placed on a [`TerminalGrid`](crate::TerminalGrid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlacementId(u32);
+
+/// Anchors an uploaded image to a rectangle of grid cells.
+///
+/// The image is stretched over the rectangle. As the rectangle is given in
+/// cells, the image follows the cell size when the grid is resized, and is
+/// cropped where the rectangle extends past the grid. Once placed, the image
+/// is anchored to the content lines it covers and scrolls along with them.
+///
+/// # Example
+/// ```
+/// use beamterm_core::{ImageId, ImagePlacement};
+///
+/// fn thumbnail(image: ImageId) -> ImagePlacement {
+///     // 20x10 cells at column 2, row 1, drawn below the text
+///     ImagePlacement::new(image, 2, 1, 20, 10).with_z_index(-1)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImagePlacement {
+    /// The placed image.
+    pub image: ImageId,
+    /// Column of the top-left cell.
+    pub col: u16,
+    /// Row of the top-left cell, in viewport coordinates at the time of
+    /// placement.
+    pub row: u16,
+    /// Width of the rectangle in cells.
+    pub cols: u16,
+    /// Height of the rectangle in cells.
+    pub rows: u16,
+    /// Drawing order; negative values are drawn below the text, all others
+    /// above it. Placements with equal z-index are drawn in placement order.
+    pub z_index: i32,
+}
+
+impl ImagePlacement {
+    /// Places `image` over `cols` x `rows` cells, starting at `col` and `row`.
+    /// The image is drawn above the text.
+    #[must_use]
+    pub const fn new(image: ImageId, col: u16, row: u16, cols: u16, rows: u16) -> Self {
+        Self { image, col, row, cols, rows, z_index: 0 }
+    }
+
+    /// Sets the drawing order; negative values are drawn below the text.
+    #[must_use]
+    pub const fn with_z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Returns `true` if the image is drawn below the text.
+    #[must_use]
+    pub const fn is_below_text(&self) -> bool {
+        self.z_index < 0
+    }
+}
+
+/// A placement anchored to the content line of its top row.
+#[derive(Debug, Clone, Copy)]
+struct Placed {
+    id: PlacementId,
+    placement: ImagePlacement,
+    /// Live screen row of the top edge; negative rows lie in the scrollback history.
+    line: isize,
+}
+
+impl Placed {
+    /// Returns the pixel rectangle `[x, y, width, height]` of the part of the
+    /// placement inside the viewport of `frame`, along with the visible part of
+    /// the image as `[u, v, width, height]` texture coordinates, or `None` if
+    /// the placement lies outside the viewport.
+    fn visible_rect(&self, frame: &ImageFrame) -> Option<([f32; 4], [f32; 4])> {
+        let placement = &self.placement;
+        let grid_size = frame.grid_size;
+        if placement.cols == 0 || placement.rows == 0 || placement.col >= grid_size.cols {
+            return None;
+        }
+
+        // rows above the viewport or below the grid are cropped
+        let row = self.line + frame.viewport_offset as isize;
+        let first_row = row.max(0);
+        let end_row = (row + placement.rows as isize).min(grid_size.rows as isize);
+        if first_row >= end_row {
+            return None;
+        }
+
+        let cols = placement.cols.min(grid_size.cols - placement.col);
+        let rows = (end_row - first_row) as f32;
+        let cropped_rows = (first_row - row) as f32;
+
+        // pixel-snapped, like the cells
+        let (cw, ch) = (frame.cell_size.width as f32, frame.cell_size.height as f32);
+        let (scroll_x, scroll_y) = frame.scroll_offset;
+        let x = ((placement.col as f32 + scroll_x) * cw + 0.5).floor();
+        let y = ((first_row as f32 + scroll_y) * ch + 0.5).floor();
+
+        let total_rows = placement.rows as f32;
+        Some(([x, y, cols as f32 * cw, rows * ch], [
+            0.0,
+            cropped_rows / total_rows,
+            cols as f32 / placement.cols as f32,
+            rows / total_rows,
+        ]))
+    }
+}
+
+/// Image placements, anchored to the content lines they cover.
+#[derive(Debug, Default)]
+struct Placements {
+    /// Placements sorted by z-index, then placement order.
+    entries: Vec<Placed>,
+}
+
+impl Placements {
+    /// Inserts a placement with its top row at the live screen `line`, after
+    /// all placements with a lower or equal z-index.
+    fn insert(&mut self, id: PlacementId, placement: ImagePlacement, line: isize) {
+        let idx = self
+            .entries
+            .partition_point(|placed| placed.placement.z_index <= placement.z_index);
+        self.entries
+            .insert(idx, Placed { id, placement, line });
+    }
+
+    /// Removes the placements matching `remove`, returning `true` if any were removed.
+    fn remove_where(&mut self, remove: impl Fn(&Placed) -> bool) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|placed| !remove(placed));
+
+        self.entries.len() != len
+    }
+
+    /// Moves the placements anchored in the live rows `top..bottom` along with
+    /// a region scroll of `lines`, dropping those scrolled out of the region.
+    fn scroll_region(&mut self, top: u16, bottom: u16, lines: i32) {
+        let (top, bottom) = (top as isize, bottom as isize);
+        self.entries.retain_mut(|placed| {
+            if !(top..bottom).contains(&placed.line) {
+                return true;
+            }
+
+            placed.line -= lines as isize;
+            placed.line < bottom && placed.line + placed.placement.rows as isize > top
+        });
+    }
+
+    /// Moves all placements up along with `lines` scrolled into the history,
+    /// dropping those scrolled past the `history_len` retained lines.
+    fn scroll_into_history(&mut self, lines: u16, history_len: usize) {
+        self.entries.retain_mut(|placed| {
+            placed.line -= lines as isize;
+            placed.line + placed.placement.rows as isize > -(history_len as isize)
+        });
+    }
+}
+
+/// Grid layout shared by all image placements of a frame.
+pub(super) struct ImageFrame {
+    /// Orthographic projection of the canvas.
+    pub projection: [f32; 16],
+    /// Size of the terminal grid in cells.
+    pub grid_size: TerminalSize,
+    /// Size of a cell in pixels.
+    pub cell_size: CellSize,
+    /// Sub-cell scroll offset of the content, in cells.
+    pub scroll_offset: (f32, f32),
+    /// Number of lines the viewport is scrolled back into the history.
+    pub viewport_offset: usize,
+}
+
+/// Inline images drawn over or under the cells of a [`TerminalGrid`].
+///
+/// Uploaded pixels are retained for recreating the textures after a context
+/// loss.
+///
+/// [`TerminalGrid`]: crate::TerminalGrid
+#[derive(Debug)]
+pub(super) struct ImageLayer {
+    /// Shader program and vertex array for drawing image quads.
+    gpu: ImageGpuResources,
+    /// Uploaded images by id.
+    images: HashMap<ImageId, Image>,
+    /// Placements anchored to the content lines they cover.
+    placements: Placements,
+    /// Next image or placement identifier.
+    next_id: u32,
+}
+
+#[derive(Debug)]
+struct Image {
+    width: i32,
+    height: i32,
+    pixels: Vec<u8>,
+    texture: glow::Texture,
+}
+
+#[derive(Debug)]
+struct ImageGpuResources {
+    shader: ShaderProgram,
+    /// Empty vertex array; quad corners are derived from `gl_VertexID`.
+    vao: glow::VertexArray,
+    projection_loc: glow::UniformLocation,
+    rect_loc: glow::UniformLocation,
+    tex_rect_loc: glow::UniformLocation,
+    sampler_loc: glow::UniformLocation,
+}
+
+impl ImageGpuResources {
+    const FRAGMENT_GLSL: &'static str = include_str!("../shaders/image.frag");
+    const VERTEX_GLSL: &'static str = include_str!("../shaders/image.vert");
+
+    fn new(gl: &glow::Context, glsl_version: crate::GlslVersion) -> Result<Self, Error> {
+        let vertex_source = format!("{}{}", glsl_version.vertex_preamble(), Self::VERTEX_GLSL);
+        let fragment_source = format!(
+            "{}{}",
+            glsl_version.fragment_preamble(),
+            Self::FRAGMENT_GLSL
+        );
+        let shader = ShaderProgram::create(gl, &vertex_source, &fragment_source)?;
+
+        let uniform_location = |name: &'static str| {
+            unsafe { gl.get_uniform_location(shader.program, name) }
+                .ok_or(Error::uniform_location_failed(name))
+        };
+        let projection_loc = uniform_location("u_projection")?;
+        let rect_loc = uniform_location("u_rect")?;
+        let tex_rect_loc = uniform_location("u_tex_rect")?;
+        let sampler_loc = uniform_location("u_image")?;
+
+        let vao =
+            unsafe { gl.create_vertex_array() }.map_err(Error::vertex_array_creation_failed)?;
+
+        Ok(Self {
+            shader,
+            vao,
+            projection_loc,
+            rect_loc,
+            tex_rect_loc,
+            sampler_loc,
+        })
+    }
+
+    fn delete(&self, gl: &glow::Context) {
+        self.shader.delete(gl);
+        unsafe { gl.delete_vertex_array(self.vao) };
+    }
+}
+
+impl ImageLayer {
+    pub(super) fn new(gl: &glow::Context, glsl_version: crate::GlslVersion) -> Result<Self, Error> {
+        Ok(Self {
+            gpu: ImageGpuResources::new(gl, glsl_version)?,
+            images: HashMap::new(),
+            placements: Placements::default(),
+            next_id: 0,
+        })
+    }
+
+    /// Uploads an image of `width` x `height` RGBA pixels, in row-major order
+    /// with non-premultiplied alpha.
+    pub(super) fn upload(
+        &mut self,
+        gl: &glow::Context,
+        width: u32,
+        height: u32,
+        pixels: Vec<u8>,
+    ) -> Result<ImageId, Error> {
+        let expected_len = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|len| len.checked_mul(4));
+        if width == 0 || height == 0 || expected_len != Some(pixels.len()) {
+            return Err(Error::invalid_image_data(format_args!(
+                "{} bytes for a {width}x{height} RGBA image",
+                pixels.len()
+            )));
+        }
+
+        let (width, height) = (width as i32, height as i32);
+        let texture = create_texture(gl, width, height, &pixels)?;
+
+        let id = ImageId(self.next_id());
+        self.images
+            .insert(id, Image { width, height, pixels, texture });
+
+        Ok(id)
+    }
+
+    /// Deletes an image along with all its placements.
+    pub(super) fn delete_image(&mut self, gl: &glow::Context, image: ImageId) -> bool {
+        let Some(removed) = self.images.remove(&image) else {
+            return false;
+        };
+
+        unsafe { gl.delete_texture(removed.texture) };
+        self.placements
+            .remove_where(|placed| placed.placement.image == image);
+
+        true
+    }
+
+    /// Places an uploaded image on the grid, anchored to the content line shown
+    /// at its row of a viewport scrolled back by `viewport_offset` lines.
+    pub(super) fn place(
+        &mut self,
+        placement: ImagePlacement,
+        viewport_offset: usize,
+    ) -> Result<PlacementId, Error> {
+        if !self.images.contains_key(&placement.image) {
+            return Err(Error::invalid_image_data(format_args!(
+                "no image with id {}",
+                placement.image.0
+            )));
+        }
+
+        let id = PlacementId(self.next_id());
+        let line = placement.row as isize - viewport_offset as isize;
+        self.placements.insert(id, placement, line);
+
+        Ok(id)
+    }
+
+    /// Removes a placement, keeping its image.
+    pub(super) fn remove_placement(&mut self, id: PlacementId) -> bool {
+        self.placements
+            .remove_where(|placed| placed.id == id)
+    }
+
+    /// Returns the placements in drawing order.
+    pub(super) fn placements(&self) -> impl Iterator<Item = (PlacementId, &ImagePlacement)> {
+        self.placements
+            .entries
+            .iter()
+            .map(|placed| (placed.id, &placed.placement))
+    }
+
+    /// Moves the placements along with a scroll of the live rows `top..bottom`
+    /// by `lines`; see [`TerminalGrid::scroll_region`](crate::TerminalGrid::scroll_region).
+    pub(super) fn scroll_region(&mut self, top: u16, bottom: u16, lines: i32) {
+        self.placements.scroll_region(top, bottom, lines);
+    }
+
+    /// Moves the placements along with `lines` scrolled into a history that
+    /// now holds `history_len` lines.
+    pub(super) fn scroll_into_history(&mut self, lines: u16, history_len: usize) {
+        self.placements
+            .scroll_into_history(lines, history_len);
+    }
+
+    /// Deletes all images and placements.
+    pub(super) fn clear(&mut self, gl: &glow::Context) {
+        for image in self.images.values() {
+            unsafe { gl.delete_texture(image.texture) };
+        }
+
+        self.images.clear();
+        self.placements.entries.clear();
+    }
+
+    /// Returns `true` if any placement is drawn below the text.
+    pub(super) fn has_below_text(&self) -> bool {
+        self.placements
+            .entries
+            .first()
+            .is_some_and(|placed| placed.placement.is_below_text())
+    }
+
+    /// Draws the placements either below or above the text, blended over the
+    /// framebuffer. Leaves the image program bound; the caller restores its
+    /// own program and vertex array.
+    pub(super) fn draw(
+        &self,
+        gl: &glow::Context,
+        state: &mut GlState,
+        frame: &ImageFrame,
+        below_text: bool,
+    ) {
+        let mut visible = self
+            .placements
+            .entries
+            .iter()
+            .filter(|placed| placed.placement.is_below_text() == below_text)
+            .filter_map(|placed| {
+                let image = self.images.get(&placed.placement.image)?;
+                let rect = placed.visible_rect(frame)?;
+                Some((image, rect))
+            })
+            .peekable();
+
+        if visible.peek().is_none() {
+            return;
+        }
+
+        self.gpu.shader.use_program(gl);
+        state.active_texture(gl, glow::TEXTURE0);
+        state.blend(gl, true).blend_func_separate(
+            gl,
+            glow::SRC_ALPHA,
+            glow::ONE_MINUS_SRC_ALPHA,
+            glow::ONE,
+            glow::ONE_MINUS_SRC_ALPHA,
+        );
+
+        unsafe {
+            gl.bind_vertex_array(Some(self.gpu.vao));
+            gl.uniform_matrix_4_f32_slice(Some(&self.gpu.projection_loc), false, &frame.projection);
+            gl.uniform_1_i32(Some(&self.gpu.sampler_loc), 0);
+        }
+
+        for (image, ([x, y, w, h], [u, v, tw, th])) in visible {
+            unsafe {
+                gl.bind_texture(glow::TEXTURE_2D, Some(image.texture));
+                gl.uniform_4_f32(Some(&self.gpu.rect_loc), x, y, w, h);
+                gl.uniform_4_f32(Some(&self.gpu.tex_rect_loc), u, v, tw, th);
+                gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+            }
+        }
+
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, None);
+            gl.bind_vertex_array(None);
+        }
+    }
+
+    /// Recreates the shader program and image textures after a context loss.
+    ///
+    /// Old GL resources are already invalid, so they are not deleted.
+    pub(super) fn recreate(
+        &mut self,
+        gl: &glow::Context,
+        glsl_version: crate::GlslVersion,
+    ) -> Result<(), Error> {
+        self.gpu = ImageGpuResources::new(gl, glsl_version)?;
+        for image in self.images.values_mut() {
+            image.texture = create_texture(gl, image.width, image.height, &image.pixels)?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes all GPU resources of the layer.
+    pub(super) fn delete(&self, gl: &glow::Context) {
+        self.gpu.delete(gl);
+        for image in self.images.values() {
+            unsafe { gl.delete_texture(image.texture) };
+        }
+    }
+
+    fn next_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        id
+    }
+}
+
+fn create_texture(
+    gl: &glow::Context,
+    width: i32,
+    height: i32,
+    pixels: &[u8],
+) -> Result<glow::Texture, Error> {
+    let texture = unsafe { gl.create_texture() }.map_err(Error::texture_creation_failed)?;
+
+    unsafe {
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            width,
+            height,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(Some(pixels)),
+        );
+
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MIN_FILTER,
+            glow::LINEAR as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MAG_FILTER,
+            glow::LINEAR as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_WRAP_S,
+            glow::CLAMP_TO_EDGE as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_WRAP_T,
+            glow::CLAMP_TO_EDGE as i32,
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+    }
+
+    Ok(texture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELL: CellSize = CellSize { width: 10, height: 20 };
+
+    fn placement(z_index: i32) -> ImagePlacement {
+        ImagePlacement::new(ImageId(0), 2, 1, 4, 3).with_z_index(z_index)
+    }
+
+    fn placed(placement: ImagePlacement) -> Placed {
+        Placed {
+            id: PlacementId(0),
+            placement,
+            line: placement.row as isize,
+        }
+    }
+
+    fn frame(cols: u16, rows: u16) -> ImageFrame {
+        ImageFrame {
+            projection: [0.0; 16],
+            grid_size: TerminalSize::new(cols, rows),
+            cell_size: CELL,
+            scroll_offset: (0.0, 0.0),
+            viewport_offset: 0,
+        }
+    }
+
+    fn visible_rows(placements: &Placements, frame: &ImageFrame) -> Vec<(u32, f32, f32)> {
+        placements
+            .entries
+            .iter()
+            .filter_map(|placed| {
+                let ([_, y, _, h], _) = placed.visible_rect(frame)?;
+                Some((placed.id.0, y / CELL.height as f32, h / CELL.height as f32))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn placements_are_sorted_by_z_index_then_order() {
+        let mut placements = Placements::default();
+        for (id, z) in [(0, 1), (1, -1), (2, 0), (3, 1), (4, -5)] {
+            placements.insert(PlacementId(id), placement(z), 1);
+        }
+
+        let ids: Vec<_> = placements
+            .entries
+            .iter()
+            .map(|placed| placed.id.0)
+            .collect();
+        assert_eq!(ids, vec![4, 1, 2, 0, 3]);
+    }
+
+    #[test]
+    fn visible_rect_scales_with_cell_size() {
+        let (rect, tex_rect) = placed(placement(0))
+            .visible_rect(&frame(80, 24))
+            .unwrap();
+        assert_eq!(rect, [20.0, 20.0, 40.0, 60.0]);
+        assert_eq!(tex_rect, [0.0, 0.0, 1.0, 1.0]);
+
+        let frame = ImageFrame {
+            cell_size: CellSize::new(20, 40),
+            scroll_offset: (0.0, 0.5),
+            ..frame(80, 24)
+        };
+        let (rect, _) = placed(placement(0)).visible_rect(&frame).unwrap();
+        assert_eq!(rect, [40.0, 60.0, 80.0, 120.0]);
+    }
+
+    #[test]
+    fn visible_rect_is_cropped_at_grid_edges() {
+        let (rect, tex_rect) = placed(placement(0))
+            .visible_rect(&frame(4, 2))
+            .unwrap();
+        assert_eq!(rect, [20.0, 20.0, 20.0, 20.0]);
+        assert_eq!(tex_rect, [0.0, 0.0, 0.5, 1.0 / 3.0]);
+
+        let above = Placed { line: -2, ..placed(placement(0)) };
+        let (rect, tex_rect) = above.visible_rect(&frame(80, 24)).unwrap();
+        assert_eq!(rect, [20.0, 0.0, 40.0, 20.0]);
+        assert_eq!(tex_rect, [0.0, 2.0 / 3.0, 1.0, 1.0 / 3.0]);
+
+        assert!(
+            placed(placement(0))
+                .visible_rect(&frame(2, 24))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn placements_scroll_with_their_content_lines() {
+        let mut placements = Placements::default();
+        placements.insert(PlacementId(0), placement(0), 1); // rows 1..4
+        placements.insert(PlacementId(1), placement(0), 6); // rows 6..9
+
+        // scrolled partially past the top of the live screen
+        placements.scroll_into_history(2, 2);
+        let mut frame = frame(80, 8);
+        assert_eq!(visible_rows(&placements, &frame), vec![
+            (0, 0.0, 2.0),
+            (1, 4.0, 3.0)
+        ]);
+
+        // scrolling the viewport back reveals the lines in the history
+        frame.viewport_offset = 2;
+        assert_eq!(visible_rows(&placements, &frame), vec![
+            (0, 1.0, 3.0),
+            (1, 6.0, 2.0)
+        ]);
+
+        // dropped once scrolled past the retained history
+        placements.scroll_into_history(6, 4);
+        assert_eq!(placements.entries.len(), 1);
+        frame.viewport_offset = 0;
+        assert_eq!(visible_rows(&placements, &frame), vec![(1, 0.0, 1.0)]);
+    }
+
+    #[test]
+    fn placements_scroll_within_their_region() {
+        let mut placements = Placements::default();
+        placements.insert(PlacementId(0), placement(0), 0); // outside the region
+        placements.insert(PlacementId(1), placement(0), 4);
+        placements.insert(PlacementId(2), placement(0), 6);
+
+        placements.scroll_region(3, 8, -2);
+        let lines: Vec<_> = placements
+            .entries
+            .iter()
+            .map(|placed| (placed.id.0, placed.line))
+            .collect();
+        assert_eq!(lines, vec![(0, 0), (1, 6)]);
+
+        placements.scroll_region(3, 8, 6);
+        assert_eq!(placements.entries.len(), 1);
+    }
+}
@@ -6,10 +6,15 @@ mod dirty_regions;
 pub(crate) mod dynamic_atlas;
 pub(crate) mod glyph_cache;
 pub(crate) mod glyph_rasterizer;
+mod grid_model;
+mod highlight_layer;
+mod hyperlink;
+mod image_layer;
 #[cfg(feature = "native-dynamic-atlas")]
 mod native_dynamic_atlas;
 mod program;
 pub(crate) mod renderer;
+mod scrollback;
 pub(crate) mod selection;
 pub(crate) mod static_atlas;
 pub(crate) mod terminal_grid;
@@ -18,10 +23,10 @@ mod ubo;
 
 // Primary API re-exports
 // Re-exports for sibling crates (beamterm-renderer)
-pub use atlas::{Atlas, FontAtlas, GlyphSlot, GlyphTracker, sealed};
+pub use atlas::{Atlas, FontAtlas, GlyphLookup, GlyphSlot, GlyphTracker, sealed};
 // Crate-internal re-exports
 use buffer::*;
-pub use cell_query::{CellIterator, CellQuery, SelectionMode, select};
+pub use cell_query::{CellIterator, CellQuery, DEFAULT_WORD_SEPARATORS, SelectionMode, select};
 pub use context::GlState;
 /// internal type; not covered by semver guarantees.
 /// Use [`Terminal::builder()`](beamterm_renderer::Terminal::builder) or the
@@ -32,13 +37,20 @@ pub use dynamic_atlas::DynamicFontAtlas;
 /// Use pre-built implementations ([`NativeGlyphRasterizer`] or `CanvasRasterizer`) instead.
 #[doc(hidden)]
 pub use glyph_rasterizer::GlyphRasterizer;
+pub use grid_model::GridModel;
+pub use highlight_layer::{Highlight, HighlightId};
+pub use hyperlink::HyperlinkId;
+pub use image_layer::{ImageId, ImagePlacement, PlacementId};
 #[cfg(feature = "native-dynamic-atlas")]
 pub use native_dynamic_atlas::{NativeDynamicAtlas, NativeGlyphRasterizer};
 pub(crate) use program::*;
 pub use renderer::{Drawable, RenderContext};
 pub use selection::SelectionTracker;
-pub use static_atlas::StaticFontAtlas;
-pub use terminal_grid::{CellData, CellDynamic, TerminalGrid};
+pub use static_atlas::{StaticFontAtlas, StaticGlyphLookup};
+pub use terminal_grid::{
+    CellAttributes, CellData, CellDynamic, Cursor, CursorShape, LineAttribute, SelectionStyle,
+    TerminalGrid, UnderlineStyle,
+};
 /// internal type; not covered by semver guarantees.
 #[doc(hidden)]
 pub use texture::RasterizedGlyph;
@@ -0,0 +1,384 @@
+use std::ops::Range;
+
+/// Line bookkeeping for the live screen and its scrollback history.
+///
+/// Cells are stored as a ring of `rows + capacity` lines. Scrolling content
+/// into the history advances the ring's top line instead of moving cells, and
+/// the visible viewport is an offset into the ring. Neither operation touches
+/// the cell data already uploaded to the GPU.
+///
+/// All line indices returned by this type are ring lines, i.e. line offsets
+/// into the cell storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ScrollbackRing {
+    /// Rows of the live screen.
+    rows: usize,
+    /// Maximum number of history lines.
+    capacity: usize,
+    /// Ring line holding the first row of the live screen.
+    top: usize,
+    /// Number of history lines currently stored.
+    history: usize,
+    /// Number of lines the viewport is scrolled back into the history.
+    viewport_offset: usize,
+}
+
+impl ScrollbackRing {
+    pub(super) fn new(rows: usize, capacity: usize) -> Self {
+        debug_assert!(rows > 0, "requires a non-zero sized terminal");
+        Self {
+            rows,
+            capacity,
+            top: 0,
+            history: 0,
+            viewport_offset: 0,
+        }
+    }
+
+    /// Creates a ring whose first `history` lines are already filled with history.
+    pub(super) fn with_history(rows: usize, capacity: usize, history: usize) -> Self {
+        debug_assert!(history <= capacity);
+        Self { top: history, history, ..Self::new(rows, capacity) }
+    }
+
+    /// Total number of lines in the ring.
+    pub(super) fn ring_lines(&self) -> usize {
+        self.rows + self.capacity
+    }
+
+    pub(super) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(super) fn history_len(&self) -> usize {
+        self.history
+    }
+
+    pub(super) fn viewport_offset(&self) -> usize {
+        self.viewport_offset
+    }
+
+    /// Ring line of the given row on the live screen.
+    pub(super) fn live_line(&self, row: usize) -> usize {
+        (self.top + row) % self.ring_lines()
+    }
+
+    /// Ring line of the given history line, where `0` is the oldest line.
+    pub(super) fn history_line(&self, line: usize) -> usize {
+        debug_assert!(line < self.history);
+        (self.top + self.ring_lines() - self.history + line) % self.ring_lines()
+    }
+
+    /// Ring line of the given row in the (possibly scrolled back) viewport.
+    pub(super) fn viewport_line(&self, row: usize) -> usize {
+        (self.top + self.ring_lines() - self.viewport_offset + row) % self.ring_lines()
+    }
+
+    /// Ring line directly above the viewport, if it holds history.
+    pub(super) fn line_above_viewport(&self) -> Option<usize> {
+        (self.viewport_offset < self.history)
+            .then(|| (self.viewport_line(0) + self.ring_lines() - 1) % self.ring_lines())
+    }
+
+    /// Ring line directly below the viewport, if the viewport is scrolled back.
+    pub(super) fn line_below_viewport(&self) -> Option<usize> {
+        (self.viewport_offset > 0).then(|| self.viewport_line(self.rows))
+    }
+
+    /// Ring line and grid position row of the partially visible row revealed
+    /// by a sub-cell `scroll_offset`, if it holds content. Grid position rows
+    /// are offset by the margin row above the grid.
+    ///
+    /// Only vertical offsets reveal a line; the grid has no columns beyond its
+    /// edges, so a horizontal offset merely shifts the viewport.
+    pub(super) fn partial_line(&self, scroll_offset: (f32, f32)) -> Option<(usize, usize)> {
+        match scroll_offset.1 {
+            y if y > 0.0 => self.line_above_viewport().map(|l| (l, 0)),
+            y if y < 0.0 => self
+                .line_below_viewport()
+                .map(|l| (l, self.rows + 1)),
+            _ => None,
+        }
+    }
+
+    /// Maps a flat cell index on the live screen to its index in the cell ring.
+    pub(super) fn live_cell(&self, idx: usize, cols: usize) -> usize {
+        self.live_line(idx / cols) * cols + idx % cols
+    }
+
+    /// Maps a flat cell index in the viewport to its index in the cell ring.
+    pub(super) fn viewport_cell(&self, idx: usize, cols: usize) -> usize {
+        self.viewport_line(idx / cols) * cols + idx % cols
+    }
+
+    /// Scrolls the viewport back by `offset` lines, clamped to the stored
+    /// history. Returns the effective offset.
+    pub(super) fn set_viewport_offset(&mut self, offset: usize) -> usize {
+        self.viewport_offset = offset.min(self.history);
+        self.viewport_offset
+    }
+
+    /// Moves the top `lines` rows of the live screen into the history.
+    ///
+    /// Returns the ring lines that now hold stale data and must be refilled:
+    /// the new bottom rows of the live screen, plus any history lines that
+    /// were pushed through without ever being visible.
+    pub(super) fn push(&mut self, lines: usize) -> impl Iterator<Item = usize> + use<> {
+        let ring_lines = self.ring_lines();
+        let lines_to_clear = lines.min(ring_lines);
+
+        self.top = (self.top + lines) % ring_lines;
+        self.history = (self.history + lines).min(self.capacity);
+
+        // keep a scrolled-back viewport anchored to the content it shows
+        if self.viewport_offset > 0 {
+            self.viewport_offset = (self.viewport_offset + lines).min(self.history);
+        }
+
+        let end = self.top + self.rows; // one past the last live row
+        (0..lines_to_clear).map(move |i| (end + 2 * ring_lines - lines_to_clear + i) % ring_lines)
+    }
+
+    /// Line copies that scroll the live rows `top..bottom` by `lines`, where
+    /// positive values move content up. Returned as `(src_line, dst_line, count)`
+    /// runs of ring lines, in an order where no source line is overwritten
+    /// before it is copied. Runs are only split where the ring wraps around,
+    /// so source and destination of a run may overlap.
+    pub(super) fn region_moves(
+        &self,
+        top: usize,
+        bottom: usize,
+        lines: isize,
+    ) -> Vec<(usize, usize, usize)> {
+        let distance = lines.unsigned_abs();
+        let moved = (bottom - top).saturating_sub(distance);
+
+        // (src_row, dst_row), with destinations ordered away from the vacated rows
+        let row_moves = (0..moved).map(|i| {
+            if lines > 0 {
+                (top + i + distance, top + i)
+            } else {
+                (bottom - 1 - i - distance, bottom - 1 - i)
+            }
+        });
+
+        let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+        for (src_row, dst_row) in row_moves {
+            let (src, dst) = (self.live_line(src_row), self.live_line(dst_row));
+
+            let extended = runs.last_mut().is_some_and(|run| {
+                let (run_src, run_dst, count) = *run;
+                if lines > 0 && src == run_src + count && dst == run_dst + count {
+                    *run = (run_src, run_dst, count + 1);
+                    true
+                } else if lines < 0 && src + 1 == run_src && dst + 1 == run_dst {
+                    *run = (src, dst, count + 1);
+                    true
+                } else {
+                    false
+                }
+            });
+
+            if !extended {
+                runs.push((src, dst, 1));
+            }
+        }
+
+        runs
+    }
+
+    /// Contiguous ring segments covering the live screen, as
+    /// `(first_ring_line, first_row, line_count)`.
+    pub(super) fn live_segments(&self) -> impl Iterator<Item = (usize, usize, usize)> + use<> {
+        self.segments(self.top)
+    }
+
+    /// Contiguous ring segments covering the viewport, as
+    /// `(first_ring_line, first_row, line_count)`.
+    pub(super) fn viewport_segments(&self) -> impl Iterator<Item = (usize, usize, usize)> + use<> {
+        self.segments(self.viewport_line(0))
+    }
+
+    /// Cell ranges of the live screen within the ring, for `cols` cells per line.
+    pub(super) fn live_cell_ranges(
+        &self,
+        cols: usize,
+    ) -> impl Iterator<Item = Range<usize>> + use<> {
+        self.live_segments()
+            .map(move |(line, _, count)| line * cols..(line + count) * cols)
+    }
+
+    fn segments(&self, first_line: usize) -> impl Iterator<Item = (usize, usize, usize)> + use<> {
+        let first_count = self.rows.min(self.ring_lines() - first_line);
+        let wrapped_count = self.rows - first_count;
+
+        [(first_line, 0, first_count), (0, first_count, wrapped_count)]
+            .into_iter()
+            .filter(|&(_, _, count)| count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn without_history_lines_map_directly() {
+        let ring = ScrollbackRing::new(4, 0);
+        assert_eq!(ring.ring_lines(), 4);
+        assert_eq!(
+            (0..4)
+                .map(|r| ring.live_line(r))
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(ring.viewport_segments().collect::<Vec<_>>(), [(0, 0, 4)]);
+    }
+
+    #[test]
+    fn push_rotates_ring_and_grows_history() {
+        let mut ring = ScrollbackRing::new(3, 5);
+        let cleared: Vec<_> = ring.push(2).collect();
+
+        assert_eq!(ring.history_len(), 2);
+        assert_eq!(ring.live_line(0), 2);
+        assert_eq!(cleared, [3, 4]);
+        assert_eq!(ring.history_line(0), 0);
+        assert_eq!(ring.history_line(1), 1);
+    }
+
+    #[test]
+    fn history_is_capped_at_capacity() {
+        let mut ring = ScrollbackRing::new(3, 2);
+        ring.push(4).for_each(drop);
+
+        assert_eq!(ring.history_len(), 2);
+        assert_eq!(ring.live_line(0), 4);
+        // oldest retained history line sits right behind the live screen
+        assert_eq!(ring.history_line(0), 2);
+        assert_eq!(ring.history_line(1), 3);
+    }
+
+    #[test]
+    fn push_beyond_ring_clears_every_line() {
+        let mut ring = ScrollbackRing::new(2, 1);
+        let mut cleared: Vec<_> = ring.push(10).collect();
+        cleared.sort_unstable();
+        assert_eq!(cleared, [0, 1, 2]);
+    }
+
+    #[test]
+    fn viewport_offset_is_clamped_to_history() {
+        let mut ring = ScrollbackRing::new(3, 10);
+        ring.push(4).for_each(drop);
+
+        assert_eq!(ring.set_viewport_offset(100), 4);
+        assert_eq!(ring.viewport_line(0), ring.history_line(0));
+        assert_eq!(ring.set_viewport_offset(1), 1);
+        assert_eq!(ring.viewport_line(1), ring.live_line(0));
+    }
+
+    #[test]
+    fn scrolled_back_viewport_stays_anchored() {
+        let mut ring = ScrollbackRing::new(3, 10);
+        ring.push(4).for_each(drop);
+        ring.set_viewport_offset(2);
+        let anchored = ring.viewport_line(0);
+
+        ring.push(3).for_each(drop);
+        assert_eq!(ring.viewport_offset(), 5);
+        assert_eq!(ring.viewport_line(0), anchored);
+    }
+
+    #[test]
+    fn lines_outside_viewport_require_content() {
+        let mut ring = ScrollbackRing::new(3, 10);
+        assert_eq!(ring.line_above_viewport(), None);
+        assert_eq!(ring.line_below_viewport(), None);
+
+        ring.push(2).for_each(drop);
+        assert_eq!(ring.line_above_viewport(), Some(ring.history_line(1)));
+        assert_eq!(ring.line_below_viewport(), None);
+
+        ring.set_viewport_offset(2);
+        assert_eq!(ring.line_above_viewport(), None);
+        assert_eq!(ring.line_below_viewport(), Some(ring.live_line(1)));
+    }
+
+    #[test]
+    fn only_vertical_scroll_offsets_reveal_a_partial_line() {
+        let mut ring = ScrollbackRing::new(3, 10);
+        ring.push(2).for_each(drop);
+        ring.set_viewport_offset(1);
+
+        assert_eq!(ring.partial_line((0.0, 0.0)), None);
+        assert_eq!(ring.partial_line((0.5, 0.0)), None);
+        assert_eq!(ring.partial_line((-0.5, 0.0)), None);
+
+        assert_eq!(
+            ring.partial_line((0.5, 0.25)),
+            Some((ring.history_line(0), 0))
+        );
+        assert_eq!(
+            ring.partial_line((-0.5, -0.25)),
+            Some((ring.live_line(2), 4))
+        );
+    }
+
+    #[test]
+    fn cell_indices_follow_line_mapping() {
+        let mut ring = ScrollbackRing::new(2, 2);
+        ring.push(3).for_each(drop); // top = 3, ring lines = 4
+        ring.set_viewport_offset(1);
+
+        assert_eq!(ring.live_cell(0, 5), 15);
+        assert_eq!(ring.live_cell(7, 5), 2); // row 1 wraps to ring line 0
+        assert_eq!(ring.viewport_cell(7, 5), 17);
+    }
+
+    #[test]
+    fn region_moves_up_in_a_single_run() {
+        let ring = ScrollbackRing::new(10, 0);
+
+        // rows 2..8 scrolled up by 2: rows 4..8 move to 2..6
+        assert_eq!(ring.region_moves(2, 8, 2), [(4, 2, 4)]);
+        assert_eq!(ring.region_moves(0, 10, 1), [(1, 0, 9)]);
+        // scrolling by the full region height moves nothing
+        assert!(ring.region_moves(2, 8, 6).is_empty());
+    }
+
+    #[test]
+    fn region_moves_down_start_at_the_bottom() {
+        let ring = ScrollbackRing::new(10, 0);
+
+        // rows 0..5 scrolled down by 2: rows 0..3 move to 2..5
+        assert_eq!(ring.region_moves(0, 5, -2), [(0, 2, 3)]);
+    }
+
+    #[test]
+    fn region_moves_split_at_ring_wraparound() {
+        let mut ring = ScrollbackRing::new(4, 0);
+        ring.push(2).for_each(drop); // live rows map to ring lines 2, 3, 0, 1
+
+        assert_eq!(ring.region_moves(0, 4, 1), [
+            (3, 2, 1),
+            (0, 3, 1),
+            (1, 0, 1)
+        ]);
+    }
+
+    #[test]
+    fn segments_split_at_ring_wraparound() {
+        let mut ring = ScrollbackRing::new(4, 2);
+        ring.push(4).for_each(drop); // top = 4, ring lines = 6
+
+        assert_eq!(ring.live_segments().collect::<Vec<_>>(), [
+            (4, 0, 2),
+            (0, 2, 2)
+        ]);
+        assert_eq!(ring.live_cell_ranges(10).collect::<Vec<_>>(), [
+            40..60,
+            0..20
+        ]);
+    }
+}
@@ -5,7 +5,7 @@ use compact_str::{CompactString, ToCompactString};
 
 use super::{
     atlas,
-    atlas::{Atlas, GlyphSlot, GlyphTracker},
+    atlas::{Atlas, GlyphLookup, GlyphSlot, GlyphTracker},
 };
 use crate::error::Error;
 
@@ -26,20 +26,14 @@ use crate::error::Error;
 pub struct StaticFontAtlas {
     /// The underlying texture
     texture: crate::gl::texture::Texture,
-    /// Symbol to 3d texture index
-    glyph_coords: HashMap<CompactString, u16>,
-    /// Base glyph identifier to symbol mapping
-    symbol_lookup: HashMap<u16, CompactString>,
+    /// Symbol to glyph id lookup, shared with headless grid models
+    glyphs: StaticGlyphLookup,
     /// The size of each character cell in pixels
     cell_size: beamterm_data::CellSize,
     /// Underline configuration
     underline: beamterm_data::LineDecoration,
     /// Strikethrough configuration
     strikethrough: beamterm_data::LineDecoration,
-    /// Tracks glyphs that were requested but not found in the atlas
-    glyph_tracker: GlyphTracker,
-    /// The last assigned halfwidth base glyph ID, before fullwidth
-    last_halfwidth_base_glyph_id: u16,
     /// Retained atlas data for context loss recovery
     atlas_data: FontAtlasData,
 }
@@ -53,6 +47,40 @@ impl StaticFontAtlas {
         let texture = crate::gl::texture::Texture::from_font_atlas_data(gl, &config)?;
 
         let beamterm_data::CellSize { width: cell_width, height: cell_height } = config.cell_size();
+
+        Ok(Self {
+            texture,
+            glyphs: StaticGlyphLookup::new(&config),
+            cell_size: beamterm_data::CellSize::new(cell_width, cell_height),
+            underline: config.underline(),
+            strikethrough: config.strikethrough(),
+            atlas_data: config,
+        })
+    }
+}
+
+/// GL-free glyph lookup for a static [`FontAtlasData`].
+///
+/// Resolves symbols to the same glyph ids as [`StaticFontAtlas`], without
+/// creating a texture. Use it with a [`GridModel`](crate::GridModel) to work
+/// with cells, selections and text in tests or application logic that has no
+/// GL context.
+#[derive(Debug)]
+pub struct StaticGlyphLookup {
+    /// Symbol to 3d texture index
+    glyph_coords: HashMap<CompactString, u16>,
+    /// Base glyph identifier to symbol mapping
+    symbol_lookup: HashMap<u16, CompactString>,
+    /// The last assigned halfwidth base glyph ID, before fullwidth
+    last_halfwidth_base_glyph_id: u16,
+    /// Tracks glyphs that were requested but not found in the atlas
+    glyph_tracker: GlyphTracker,
+}
+
+impl StaticGlyphLookup {
+    /// Builds the glyph lookup for the given atlas data.
+    #[must_use]
+    pub fn new(config: &FontAtlasData) -> Self {
         let mut layers = HashMap::new();
         let mut symbol_lookup = HashMap::new();
 
@@ -69,41 +97,75 @@ impl StaticFontAtlas {
                 layers.insert(CompactString::from(g.symbol()), g.id());
             });
 
-        Ok(Self {
-            texture,
+        Self {
             glyph_coords: layers,
-            last_halfwidth_base_glyph_id: config.max_halfwidth_base_glyph_id(),
             symbol_lookup,
-            cell_size: beamterm_data::CellSize::new(cell_width, cell_height),
-            underline: config.underline(),
-            strikethrough: config.strikethrough(),
+            last_halfwidth_base_glyph_id: config.max_halfwidth_base_glyph_id(),
             glyph_tracker: GlyphTracker::new(),
-            atlas_data: config,
-        })
+        }
     }
-}
 
-impl atlas::sealed::Sealed for StaticFontAtlas {}
+    /// Returns the base glyph identifier for the given key
+    pub fn get_base_glyph_id(&mut self, key: &str) -> Option<u16> {
+        if let Some(id) = ascii_glyph_id(key) {
+            return Some(id);
+        }
 
-impl Atlas for StaticFontAtlas {
-    fn get_glyph_id(&mut self, key: &str, style_bits: u16) -> Option<u16> {
-        let base_id = self.get_base_glyph_id(key)?;
-        Some(base_id | style_bits)
+        match self.glyph_coords.get(key) {
+            Some(id) => Some(*id),
+            None => {
+                self.glyph_tracker.record_missing(key);
+                None
+            },
+        }
     }
 
-    /// Returns the base glyph identifier for the given key
-    fn get_base_glyph_id(&mut self, key: &str) -> Option<u16> {
-        if key.len() == 1 {
-            let ch = key.chars().next().unwrap();
-            if ch.is_ascii() {
-                // 0x00..0x7f double as layer
-                let id = ch as u16;
-                return Some(id);
-            }
+    /// Returns the tracker of glyphs that were requested but not found.
+    #[must_use]
+    pub fn glyph_tracker(&self) -> &GlyphTracker {
+        &self.glyph_tracker
+    }
+
+    fn glyph_count(&self) -> u32 {
+        // ASCII printable characters (96 characters)
+        let ascii_count = ASCII_GLYPH_IDS.len() as u32;
+        // Non-ASCII glyphs stored in symbol_lookup
+        let non_ascii_count = self.symbol_lookup.len() as u32;
+        ascii_count + non_ascii_count
+    }
+
+    fn for_each_symbol(&self, f: &mut dyn FnMut(u16, &str)) {
+        // ASCII printable characters
+        for code in ASCII_GLYPH_IDS {
+            let ch = code as u8 as char;
+            let mut buf = [0u8; 4];
+            let s = ch.encode_utf8(&mut buf);
+            f(code, s);
+        }
+        // Non-ASCII glyphs from symbol lookup
+        for (glyph_id, symbol) in &self.symbol_lookup {
+            f(*glyph_id, symbol.as_str());
+        }
+    }
+}
+
+impl GlyphLookup for StaticGlyphLookup {
+    fn resolve_glyph_slot(&mut self, key: &str, style_bits: u16) -> Option<GlyphSlot> {
+        if let Some(id) = ascii_glyph_id(key) {
+            return Some(GlyphSlot::Normal(id | style_bits));
         }
 
         match self.glyph_coords.get(key) {
-            Some(id) => Some(*id),
+            Some(base_glyph_id) => {
+                let id = base_glyph_id | style_bits;
+                if *base_glyph_id >= self.last_halfwidth_base_glyph_id {
+                    Some(GlyphSlot::Wide(id))
+                } else if id & Glyph::EMOJI_FLAG != 0 {
+                    Some(GlyphSlot::Emoji(id))
+                } else {
+                    Some(GlyphSlot::Normal(id))
+                }
+            },
             None => {
                 self.glyph_tracker.record_missing(key);
                 None
@@ -111,6 +173,57 @@ impl Atlas for StaticFontAtlas {
         }
     }
 
+    fn get_symbol(&self, glyph_id: u16) -> Option<CompactString> {
+        let glyph_id = glyph_id & !(Glyph::UNDERLINE_FLAG | Glyph::STRIKETHROUGH_FLAG);
+        let base_glyph_id = if glyph_id & Glyph::EMOJI_FLAG != 0 {
+            glyph_id & Glyph::GLYPH_ID_EMOJI_MASK
+        } else {
+            glyph_id & Glyph::GLYPH_ID_MASK
+        };
+
+        match ascii_char(base_glyph_id) {
+            Some(ch) => Some(ch.to_compact_string()),
+            None => self.symbol_lookup.get(&base_glyph_id).cloned(),
+        }
+    }
+
+    fn get_ascii_char(&self, glyph_id: u16) -> Option<char> {
+        ascii_char(glyph_id & Glyph::GLYPH_ID_MASK)
+    }
+}
+
+/// Glyph ids of the printable ASCII characters, which equal their code points.
+const ASCII_GLYPH_IDS: std::ops::Range<u16> = 0x20..0x80;
+
+/// Returns the base glyph id of a single ASCII character; ASCII characters
+/// double as their glyph ids.
+fn ascii_glyph_id(key: &str) -> Option<u16> {
+    match key.as_bytes() {
+        [byte] if byte.is_ascii() => Some(*byte as u16),
+        _ => None,
+    }
+}
+
+/// Returns the printable ASCII character of a base glyph id.
+fn ascii_char(base_glyph_id: u16) -> Option<char> {
+    ASCII_GLYPH_IDS
+        .contains(&base_glyph_id)
+        .then_some(base_glyph_id as u8 as char)
+}
+
+impl atlas::sealed::Sealed for StaticFontAtlas {}
+
+impl Atlas for StaticFontAtlas {
+    fn get_glyph_id(&mut self, key: &str, style_bits: u16) -> Option<u16> {
+        let base_id = self.get_base_glyph_id(key)?;
+        Some(base_id | style_bits)
+    }
+
+    /// Returns the base glyph identifier for the given key
+    fn get_base_glyph_id(&mut self, key: &str) -> Option<u16> {
+        self.glyphs.get_base_glyph_id(key)
+    }
+
     fn cell_size(&self) -> beamterm_data::CellSize {
         beamterm_data::CellSize::new(
             self.cell_size.width - 2 * FontAtlasData::PADDING,
@@ -134,42 +247,19 @@ impl Atlas for StaticFontAtlas {
 
     /// Returns the symbol for the given glyph ID, if it exists
     fn get_symbol(&self, glyph_id: u16) -> Option<CompactString> {
-        let glyph_id = glyph_id & !(Glyph::UNDERLINE_FLAG | Glyph::STRIKETHROUGH_FLAG);
-        let base_glyph_id = if glyph_id & Glyph::EMOJI_FLAG != 0 {
-            glyph_id & Glyph::GLYPH_ID_EMOJI_MASK
-        } else {
-            glyph_id & Glyph::GLYPH_ID_MASK
-        };
-
-        if (0x20..0x80).contains(&base_glyph_id) {
-            // ASCII characters are directly mapped to their code point
-            let ch = base_glyph_id as u8 as char;
-            Some(ch.to_compact_string())
-        } else {
-            self.symbol_lookup.get(&base_glyph_id).cloned()
-        }
+        self.glyphs.get_symbol(glyph_id)
     }
 
     fn get_ascii_char(&self, glyph_id: u16) -> Option<char> {
-        // Static atlas: ASCII chars 0x20-0x7F have glyph_id == char code
-        let glyph_id = glyph_id & Glyph::GLYPH_ID_MASK;
-        if (0x20..0x80).contains(&glyph_id) {
-            Some(glyph_id as u8 as char)
-        } else {
-            None
-        }
+        self.glyphs.get_ascii_char(glyph_id)
     }
 
     fn glyph_tracker(&self) -> &GlyphTracker {
-        &self.glyph_tracker
+        self.glyphs.glyph_tracker()
     }
 
     fn glyph_count(&self) -> u32 {
-        // ASCII printable characters: 0x20..0x80 (96 characters)
-        let ascii_count = 0x80 - 0x20;
-        // Non-ASCII glyphs stored in symbol_lookup
-        let non_ascii_count = self.symbol_lookup.len() as u32;
-        ascii_count + non_ascii_count
+        self.glyphs.glyph_count()
     }
 
     fn flush(&mut self, _gl: &glow::Context) -> Result<(), Error> {
@@ -192,45 +282,11 @@ impl Atlas for StaticFontAtlas {
     }
 
     fn for_each_symbol(&self, f: &mut dyn FnMut(u16, &str)) {
-        // ASCII printable characters (0x20..0x80)
-        for code in 0x20u16..0x80 {
-            let ch = code as u8 as char;
-            let mut buf = [0u8; 4];
-            let s = ch.encode_utf8(&mut buf);
-            f(code, s);
-        }
-        // Non-ASCII glyphs from symbol lookup
-        for (glyph_id, symbol) in &self.symbol_lookup {
-            f(*glyph_id, symbol.as_str());
-        }
+        self.glyphs.for_each_symbol(f);
     }
 
     fn resolve_glyph_slot(&mut self, key: &str, style_bits: u16) -> Option<GlyphSlot> {
-        if key.len() == 1 {
-            let ch = key.chars().next().unwrap();
-            if ch.is_ascii() {
-                // 0x00..0x7f double as layer
-                let id = ch as u16;
-                return Some(GlyphSlot::Normal(id | style_bits));
-            }
-        }
-
-        match self.glyph_coords.get(key) {
-            Some(base_glyph_id) => {
-                let id = base_glyph_id | style_bits;
-                if *base_glyph_id >= self.last_halfwidth_base_glyph_id {
-                    Some(GlyphSlot::Wide(id))
-                } else if id & Glyph::EMOJI_FLAG != 0 {
-                    Some(GlyphSlot::Emoji(id))
-                } else {
-                    Some(GlyphSlot::Normal(id))
-                }
-            },
-            None => {
-                self.glyph_tracker.record_missing(key);
-                None
-            },
-        }
+        self.glyphs.resolve_glyph_slot(key, style_bits)
     }
 
     /// Returns `0x1FFF` to support the full glyph encoding from `beamterm-atlas`.
@@ -1,21 +1,30 @@
-use std::{cmp::min, fmt::Debug};
+use std::{fmt::Debug, time::Duration};
 
-use beamterm_data::{CellSize, FontAtlasData, FontStyle, Glyph, GlyphEffect, TerminalSize};
+use beamterm_data::{
+    CellSize, FontAtlasData, FontStyle, FrameDiff, FrameOp, Glyph, GlyphEffect, GridSnapshot,
+    TerminalSize,
+};
+use bitflags::bitflags;
 use compact_str::CompactString;
 use glow::HasContext;
 
 use crate::{
-    CursorPosition,
+    CursorPosition, Matcher, PatternMatch, SearchMatch, SearchQuery, UrlMatch,
     error::Error,
     gl::{
         CellIterator, CellQuery, Drawable, GlState, RenderContext, ShaderProgram,
-        atlas::{self, FontAtlas, GlyphSlot},
+        atlas::FontAtlas,
         buffer_upload_array,
-        dirty_regions::DirtyRegions,
+        cell_query::CellSpan,
+        grid_model::GridModel,
+        highlight_layer::{Highlight, HighlightId, HighlightLayer},
+        hyperlink::HyperlinkId,
+        image_layer::{ImageFrame, ImageId, ImageLayer, ImagePlacement, PlacementId},
         selection::SelectionTracker,
         ubo::UniformBufferObject,
     },
     mat4::Mat4,
+    palette::{CellColor, Palette},
 };
 
 /// A high-performance terminal grid renderer using instanced rendering.
@@ -29,24 +38,40 @@ use crate::{
 pub struct TerminalGrid {
     /// GPU resources (shader, buffers, UBOs) - recreated on context loss
     gpu: GpuResources,
-    /// Terminal cell instance data
-    cells: Vec<CellDynamic>,
-    /// Terminal size in cells
-    terminal_size: TerminalSize,
+    /// CPU-side cell model, mirrored to the instance buffers
+    model: GridModel,
+    /// Terminal size and ring length of the model when the instance buffers were created
+    synced_layout: (TerminalSize, usize),
+    /// Viewport offset of the model when the uniform data was last uploaded
+    synced_viewport_offset: usize,
     /// Size of the canvas in pixels (physical)
     canvas_size_px: (i32, i32),
     /// Current device pixel ratio
     pixel_ratio: f32,
-    /// Font atlas for rendering text.
-    atlas: FontAtlas,
-    /// Fallback glyph for missing symbols.
-    fallback_glyph: u16,
-    /// Selection tracker for managing cell selections.
-    selection: SelectionTracker,
-    /// Indicates whether there are cells pending flush to the GPU.
-    dirty_regions: DirtyRegions,
     /// Background cell opacity (0.0 = fully transparent, 1.0 = fully opaque).
     bg_alpha: f32,
+    /// Minimum WCAG contrast ratio of the foreground against the background.
+    minimum_contrast: f32,
+    /// Cursor state, drawn by the fragment shader.
+    cursor: Cursor,
+    /// Animation clock in milliseconds, wrapping at `u32::MAX`.
+    time_ms: u32,
+    /// Clock value at which the cursor blink cycle last restarted.
+    cursor_epoch_ms: u32,
+    /// Duration of each on/off phase of blinking text; `0` disables blinking.
+    text_blink_ms: u32,
+    /// Colors referenced by [`CellColor`] palette colors.
+    palette: Palette,
+    /// Sub-cell scroll offset of the content, in cells.
+    scroll_offset: (f32, f32),
+    /// Inline images placed over or under the cells.
+    images: ImageLayer,
+    /// Highlighted cell ranges drawn over the cell backgrounds.
+    highlights: HighlightLayer,
+    /// Colors of selected cells, applied by the fragment shader.
+    selection_style: SelectionStyle,
+    /// Selected cells when the uniform data was last uploaded
+    synced_selection: Option<CellSpan>,
 }
 
 /// GPU resources that need to be recreated after a context loss.
@@ -65,8 +90,12 @@ struct GpuResources {
     ubo_vertex: UniformBufferObject,
     /// Shared state for the fragment shader
     ubo_fragment: UniformBufferObject,
+    /// Palette colors, resolved by the vertex shader
+    ubo_palette: UniformBufferObject,
     /// Uniform location for the texture sampler.
     sampler_loc: glow::UniformLocation,
+    /// Uniform location selecting the combined, background or text pass.
+    pass_loc: glow::UniformLocation,
 }
 
 impl GpuResources {
@@ -78,6 +107,7 @@ impl GpuResources {
         self.buffers.delete(gl);
         self.ubo_vertex.delete(gl);
         self.ubo_fragment.delete(gl);
+        self.ubo_palette.delete(gl);
     }
 
     /// Creates all GPU resources for the terminal grid.
@@ -91,7 +121,7 @@ impl GpuResources {
     fn new(
         gl: &glow::Context,
         cell_pos: &[CellStatic],
-        cell_data: &[CellDynamic],
+        model: &GridModel,
         cell_size: CellSize,
         glsl_version: crate::GlslVersion,
     ) -> Result<Self, Error> {
@@ -101,7 +131,7 @@ impl GpuResources {
         unsafe { gl.bind_vertex_array(Some(vao)) };
 
         // Create all buffers
-        let buffers = setup_buffers(gl, vao, cell_pos, cell_data, cell_size)?;
+        let buffers = setup_buffers(gl, vao, cell_pos, model, cell_size)?;
 
         // Unbind VAO to prevent accidental modification
         unsafe { gl.bind_vertex_array(None) };
@@ -120,27 +150,50 @@ impl GpuResources {
         ubo_vertex.bind_to_shader(gl, &shader, "VertUbo")?;
         let ubo_fragment = UniformBufferObject::new(gl, CellFragmentUbo::BINDING_POINT)?;
         ubo_fragment.bind_to_shader(gl, &shader, "FragUbo")?;
+        let ubo_palette = UniformBufferObject::new(gl, PaletteUbo::BINDING_POINT)?;
+        ubo_palette.bind_to_shader(gl, &shader, "PaletteUbo")?;
 
         let sampler_loc = unsafe { gl.get_uniform_location(shader.program, "u_sampler") }
             .ok_or(Error::uniform_location_failed("u_sampler"))?;
+        let pass_loc = unsafe { gl.get_uniform_location(shader.program, "u_pass") }
+            .ok_or(Error::uniform_location_failed("u_pass"))?;
 
         Ok(Self {
             shader,
             buffers,
             ubo_vertex,
             ubo_fragment,
+            ubo_palette,
             sampler_loc,
+            pass_loc,
         })
     }
 }
 
+/// Selects what the cell shader draws; see `u_pass` in `cell.frag`.
+#[derive(Debug, Clone, Copy)]
+enum CellPass {
+    /// Backgrounds and text in a single opaque pass.
+    Combined = 0,
+    /// Cell backgrounds, including a block cursor.
+    Backgrounds = 1,
+    /// Text, decorations and cursor overlays, blended over the framebuffer.
+    Text = 2,
+}
+
 #[derive(Debug)]
 struct TerminalBuffers {
     vao: glow::VertexArray,
     vertices: glow::Buffer,
     instance_pos: glow::Buffer,
     instance_cell: glow::Buffer,
+    /// Line attribute of each line of the cell ring, advancing once per row of instances.
+    instance_line: glow::Buffer,
     indices: glow::Buffer,
+    /// Staging buffer for copies between overlapping ranges of the instance cell buffer.
+    copy_scratch: glow::Buffer,
+    /// Size of `copy_scratch` in bytes; grown on demand.
+    copy_scratch_size: usize,
 }
 
 impl TerminalBuffers {
@@ -150,7 +203,9 @@ impl TerminalBuffers {
             gl.delete_buffer(self.vertices);
             gl.delete_buffer(self.instance_pos);
             gl.delete_buffer(self.instance_cell);
+            gl.delete_buffer(self.instance_line);
             gl.delete_buffer(self.indices);
+            gl.delete_buffer(self.copy_scratch);
         }
     }
 
@@ -196,6 +251,107 @@ impl TerminalBuffers {
         }
     }
 
+    /// Copies `count` cells within the instance cell buffer, from `src` to `dst`.
+    ///
+    /// GL forbids copies between overlapping ranges of a buffer, so those are
+    /// staged through the scratch buffer.
+    fn copy_instance_data(&mut self, gl: &glow::Context, src: usize, dst: usize, count: usize) {
+        let size = CellDynamic::SIZE;
+        let (src, dst, len) = (
+            (src * size) as i32,
+            (dst * size) as i32,
+            (count * size) as i32,
+        );
+        let copy = |read: glow::Buffer, write: glow::Buffer, src: i32, dst: i32| unsafe {
+            gl.bind_buffer(glow::COPY_READ_BUFFER, Some(read));
+            gl.bind_buffer(glow::COPY_WRITE_BUFFER, Some(write));
+            gl.copy_buffer_sub_data(
+                glow::COPY_READ_BUFFER,
+                glow::COPY_WRITE_BUFFER,
+                src,
+                dst,
+                len,
+            );
+        };
+
+        if src.abs_diff(dst) >= len as u32 {
+            copy(self.instance_cell, self.instance_cell, src, dst);
+        } else {
+            if self.copy_scratch_size < len as usize {
+                unsafe {
+                    gl.bind_buffer(glow::COPY_WRITE_BUFFER, Some(self.copy_scratch));
+                    gl.buffer_data_size(glow::COPY_WRITE_BUFFER, len, glow::DYNAMIC_COPY);
+                }
+                self.copy_scratch_size = len as usize;
+            }
+
+            copy(self.instance_cell, self.copy_scratch, src, 0);
+            copy(self.copy_scratch, self.instance_cell, 0, dst);
+        }
+
+        unsafe {
+            gl.bind_buffer(glow::COPY_READ_BUFFER, None);
+            gl.bind_buffer(glow::COPY_WRITE_BUFFER, None);
+        }
+    }
+
+    /// Replaces the line attributes of the cell ring.
+    fn upload_line_attributes(&self, gl: &glow::Context, line_attributes: &[LineAttribute]) {
+        let data = shader_line_attributes(line_attributes);
+
+        unsafe {
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.instance_line));
+            gl.buffer_sub_data_u8_slice(glow::ARRAY_BUFFER, 0, &data);
+            gl.bind_buffer(glow::ARRAY_BUFFER, None);
+        }
+    }
+
+    /// Points the instance attributes at `first_line` of the cell ring and at
+    /// `pos_row` in the grid position buffer, so that the next instanced draw
+    /// starts at the first cell of those rows.
+    ///
+    /// The VAO must already be bound.
+    fn set_instance_offsets(
+        &self,
+        gl: &glow::Context,
+        first_line: usize,
+        pos_row: usize,
+        cols: usize,
+    ) {
+        let cell_stride = CellDynamic::SIZE;
+        let pos_stride = size_of::<CellStatic>();
+        let (first_cell, first_pos) = (first_line * cols, pos_row * cols);
+
+        unsafe {
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.instance_cell));
+            gl.vertex_attrib_pointer_i32(
+                attrib::PACKED_DEPTH_FG_BG,
+                3,
+                glow::UNSIGNED_INT,
+                cell_stride as i32,
+                (first_cell * cell_stride) as i32,
+            );
+
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.instance_pos));
+            gl.vertex_attrib_pointer_i32(
+                attrib::GRID_XY,
+                2,
+                glow::UNSIGNED_SHORT,
+                pos_stride as i32,
+                (first_pos * pos_stride) as i32,
+            );
+
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.instance_line));
+            gl.vertex_attrib_pointer_i32(
+                attrib::LINE_ATTRIBUTE,
+                1,
+                glow::UNSIGNED_BYTE,
+                1,
+                first_line as i32,
+            );
+        }
+    }
+
     /// Updates the vertex buffer with new cell dimensions.
     fn update_vertex_buffer(&self, gl: &glow::Context, cell_size: CellSize) {
         let (w, h) = (cell_size.width as f32, cell_size.height as f32);
@@ -223,6 +379,9 @@ impl TerminalBuffers {
 }
 
 impl TerminalGrid {
+    /// Default duration of each on/off phase of blinking text.
+    pub const DEFAULT_TEXT_BLINK_MS: u32 = 500;
+
     /// Creates a new terminal grid with the given atlas and screen dimensions.
     ///
     /// # Errors
@@ -230,7 +389,7 @@ impl TerminalGrid {
     /// GPU resource allocation fails.
     pub fn new(
         gl: &glow::Context,
-        mut atlas: FontAtlas,
+        atlas: FontAtlas,
         screen_size: (i32, i32),
         pixel_ratio: f32,
         glsl_version: &crate::GlslVersion,
@@ -240,24 +399,34 @@ impl TerminalGrid {
         let cols = screen_size.0 / cell_size.width;
         let rows = screen_size.1 / cell_size.height;
 
-        let space_glyph = atlas.space_glyph_id();
-        let cell_data = create_terminal_cell_data(cols, rows, space_glyph);
-        let cell_pos = CellStatic::create_grid(cols, rows);
+        let model = GridModel::new(atlas, TerminalSize::new(cols as u16, rows as u16));
+        let size = model.terminal_size();
+        let cell_pos = CellStatic::create_grid(size.cols as i32, size.rows as i32);
 
-        let grid = Self {
-            gpu: GpuResources::new(gl, &cell_pos, &cell_data, cell_size, *glsl_version)?,
-            terminal_size: TerminalSize::new(cols as u16, rows as u16),
+        let mut grid = Self {
+            gpu: GpuResources::new(gl, &cell_pos, &model, cell_size, *glsl_version)?,
+            synced_layout: (size, model.cells().len()),
+            synced_viewport_offset: 0,
+            model,
             canvas_size_px: screen_size,
             pixel_ratio,
-            cells: cell_data,
-            atlas,
-            fallback_glyph: space_glyph,
-            selection: SelectionTracker::new(),
-            dirty_regions: DirtyRegions::new((cols * rows) as usize),
             bg_alpha: 1.0,
+            minimum_contrast: 1.0,
+            // hidden until the application opts in via `set_cursor`
+            cursor: Cursor { visible: false, ..Cursor::default() },
+            time_ms: 0,
+            cursor_epoch_ms: 0,
+            text_blink_ms: Self::DEFAULT_TEXT_BLINK_MS,
+            palette: Palette::default(),
+            scroll_offset: (0.0, 0.0),
+            images: ImageLayer::new(gl, *glsl_version)?,
+            highlights: HighlightLayer::new(gl, *glsl_version)?,
+            selection_style: SelectionStyle::default(),
+            synced_selection: None,
         };
 
         grid.upload_ubo_data(gl);
+        grid.upload_palette(gl);
 
         Ok(grid)
     }
@@ -269,21 +438,45 @@ impl TerminalGrid {
     /// handles cleanup automatically, but explicit deletion is still recommended.
     pub fn delete(self, gl: &glow::Context) {
         self.gpu.delete(gl);
-        self.atlas.delete(gl);
+        self.images.delete(gl);
+        self.highlights.delete(gl);
+        self.model.atlas().delete(gl);
     }
 
     /// Returns the effective cell size for layout (base cell size * cell scale).
     fn effective_cell_size(&self) -> CellSize {
-        let cell_scale = self.atlas.cell_scale_for_dpr(self.pixel_ratio);
-        self.atlas.cell_size().scale(cell_scale)
+        let atlas = self.model.atlas();
+        let cell_scale = atlas.cell_scale_for_dpr(self.pixel_ratio);
+        atlas.cell_size().scale(cell_scale)
+    }
+
+    /// Returns the cell model mirrored by this grid.
+    #[must_use]
+    pub fn model(&self) -> &GridModel {
+        &self.model
+    }
+
+    /// Returns a mutable reference to the cell model.
+    ///
+    /// Changes to the model are mirrored to the GPU on the next call to
+    /// [`flush_cells`](Self::flush_cells), including changes to its size or
+    /// scrollback capacity. Use [`replace_atlas`](Self::replace_atlas) rather
+    /// than [`GridModel::replace_atlas`] to swap the font atlas, as the old
+    /// atlas texture must be deleted.
+    pub fn model_mut(&mut self) -> &mut GridModel {
+        &mut self.model
+    }
+
+    /// Returns the grid model for mutation, along with the palette, e.g. to
+    /// resolve colors while writing cells.
+    #[cfg(feature = "ratatui")]
+    pub(crate) fn model_and_palette_mut(&mut self) -> (&mut GridModel, &Palette) {
+        (&mut self.model, &self.palette)
     }
 
     /// Sets the fallback glyph for missing characters.
     pub fn set_fallback_glyph(&mut self, fallback: &str) {
-        self.fallback_glyph = self
-            .atlas
-            .resolve_glyph_slot(fallback, FontStyle::Normal as u16)
-            .map_or(' ' as u16, |slot| slot.slot_id());
+        self.model.set_fallback_glyph(fallback);
     }
 
     /// Replaces the current font atlas with a new one, translating all existing
@@ -294,64 +487,9 @@ impl TerminalGrid {
     /// 2. Resolving the corresponding glyph slot in the new atlas
     /// 3. Updating double-width glyphs (emoji, wide chars) across both cells
     /// 4. Resizing the grid if cell dimensions changed
-    pub fn replace_atlas(&mut self, gl: &glow::Context, mut atlas: FontAtlas) {
-        let glyph_mask = atlas::GLYPH_SLOT_MASK as u16;
-        let style_mask = !glyph_mask;
-
-        // compute space glyph before mutable borrows
-        let space_glyph = atlas.space_glyph_id();
-
-        // update fallback glyph to new atlas, before translating existing cells
-        self.fallback_glyph = self
-            .atlas
-            .get_symbol(self.fallback_glyph & glyph_mask)
-            .and_then(|symbol| {
-                let style_bits = self.fallback_glyph & style_mask;
-                atlas.resolve_glyph_slot(symbol.as_str(), style_bits)
-            })
-            .map_or(space_glyph, |slot| slot.slot_id());
-
-        // translate existing glyph ids to new atlas
-        let mut skip_next = false;
-        for idx in 0..self.cells.len() {
-            if skip_next {
-                skip_next = false;
-                continue;
-            }
-
-            let old_glyph_id = self.cells[idx].glyph_id();
-            let style_bits = old_glyph_id & style_mask;
-
-            let slot = self
-                .atlas
-                .get_symbol(old_glyph_id & glyph_mask)
-                .and_then(|symbol| atlas.resolve_glyph_slot(symbol.as_str(), style_bits));
-
-            match slot {
-                Some(GlyphSlot::Normal(id)) => {
-                    self.cells[idx].set_glyph_id(id);
-                },
-                Some(GlyphSlot::Wide(id)) | Some(GlyphSlot::Emoji(id)) => {
-                    self.cells[idx].set_glyph_id(id);
-                    // update right-half in next cell if within bounds
-                    if let Some(next_cell) = self.cells.get_mut(idx + 1) {
-                        next_cell.set_glyph_id(id + 1);
-                        skip_next = true;
-                    }
-                },
-                None => {
-                    self.cells[idx].set_glyph_id(self.fallback_glyph);
-                },
-            }
-        }
-
-        // clear any active selection, just to keep it simple
-        self.selection.clear();
-
-        // replace atlas and resize grid accordingly
-        let old_atlas = std::mem::replace(&mut self.atlas, atlas);
+    pub fn replace_atlas(&mut self, gl: &glow::Context, atlas: FontAtlas) {
+        let old_atlas = self.model.replace_atlas(atlas);
         old_atlas.delete(gl);
-        self.dirty_regions.mark_all();
 
         // update vertex buffer with new cell dimensions
         self.gpu
@@ -364,12 +502,12 @@ impl TerminalGrid {
     /// Returns the [`FontAtlas`] used by this terminal grid.
     #[must_use]
     pub fn atlas(&self) -> &FontAtlas {
-        &self.atlas
+        self.model.atlas()
     }
 
     /// Returns a mutable reference to the font atlas.
     pub fn atlas_mut(&mut self) -> &mut FontAtlas {
-        &mut self.atlas
+        self.model.atlas_mut()
     }
 
     /// Sets the background opacity for terminal cells.
@@ -385,6 +523,138 @@ impl TerminalGrid {
         self.upload_ubo_data(gl);
     }
 
+    /// Returns the minimum contrast ratio; `1.0` when disabled.
+    #[must_use]
+    pub fn minimum_contrast(&self) -> f32 {
+        self.minimum_contrast
+    }
+
+    /// Sets the minimum WCAG contrast ratio of text against its background,
+    /// clamped to `1.0..=21.0`; `1.0` disables the adjustment.
+    ///
+    /// Foreground colors with too little contrast are brightened or darkened
+    /// by the shader, e.g. with a ratio of `4.5`, dark grey text on a black
+    /// background is drawn lighter. Emoji keep their texture colors, and the
+    /// cell data is left untouched.
+    pub fn set_minimum_contrast(&mut self, gl: &glow::Context, ratio: f32) {
+        self.minimum_contrast = ratio.clamp(1.0, 21.0);
+        self.upload_ubo_data(gl);
+    }
+
+    /// Returns the colors of selected cells.
+    #[must_use]
+    pub fn selection_style(&self) -> SelectionStyle {
+        self.selection_style
+    }
+
+    /// Sets the colors of selected cells.
+    ///
+    /// The selection is highlighted by the shader, so neither changing the
+    /// style nor the selection modifies or re-uploads cell data.
+    pub fn set_selection_style(&mut self, gl: &glow::Context, style: SelectionStyle) {
+        self.selection_style = match style {
+            SelectionStyle::Solid { background, foreground, alpha } => SelectionStyle::Solid {
+                background,
+                foreground,
+                alpha: alpha.clamp(0.0, 1.0),
+            },
+            style => style,
+        };
+        self.upload_ubo_data(gl);
+    }
+
+    /// Returns the current cursor state.
+    #[must_use]
+    pub fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
+    /// Sets the cursor state.
+    ///
+    /// The cursor is drawn by the shader from uniform data; changing it never
+    /// modifies or re-uploads cell data. Moving the cursor restarts the blink
+    /// cycle, so the cursor stays visible while it is being moved.
+    pub fn set_cursor(&mut self, gl: &glow::Context, cursor: Cursor) {
+        if cursor == self.cursor {
+            return;
+        }
+
+        if cursor.position != self.cursor.position {
+            self.cursor_epoch_ms = self.time_ms;
+        }
+
+        self.cursor = cursor;
+        self.upload_ubo_data(gl);
+    }
+
+    /// Moves the cursor to the given grid position, keeping its style.
+    pub fn set_cursor_position(&mut self, gl: &glow::Context, position: CursorPosition) {
+        self.set_cursor(gl, Cursor { position, ..self.cursor });
+    }
+
+    /// Advances the animation clock used for cursor and text blinking.
+    ///
+    /// `time` is a monotonic timestamp, e.g. the time since application
+    /// start. Call this once per frame; the uniform data is only re-uploaded
+    /// when something on screen is animated.
+    pub fn set_time(&mut self, gl: &glow::Context, time: Duration) {
+        let previous_phase = self.text_blink_phase();
+
+        // wrapping is fine: the shader only looks at differences and phases
+        self.time_ms = time.as_millis() as u32;
+
+        if self.cursor.is_blinking() || self.text_blink_phase() != previous_phase {
+            self.upload_ubo_data(gl);
+        }
+    }
+
+    /// Returns the duration of each on/off phase of cells with
+    /// [`CellAttributes::BLINK`], in milliseconds.
+    #[must_use]
+    pub fn text_blink_interval_ms(&self) -> u32 {
+        self.text_blink_ms
+    }
+
+    /// Sets the duration of each on/off phase of cells with
+    /// [`CellAttributes::BLINK`]; `0` keeps blinking text steadily visible.
+    pub fn set_text_blink_interval_ms(&mut self, gl: &glow::Context, interval_ms: u32) {
+        self.text_blink_ms = interval_ms;
+        self.upload_ubo_data(gl);
+    }
+
+    /// Returns the palette used to resolve [`CellColor`] palette colors.
+    #[must_use]
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Replaces the palette used to resolve [`CellColor`] palette colors.
+    ///
+    /// Cells referencing the palette are recolored on the GPU; this is a
+    /// single small upload and never touches the cell data.
+    pub fn set_palette(&mut self, gl: &glow::Context, palette: Palette) {
+        if palette == self.palette {
+            return;
+        }
+
+        self.palette = palette;
+        self.upload_palette(gl);
+    }
+
+    /// Uploads the palette colors to the palette UBO.
+    fn upload_palette(&self, gl: &glow::Context) {
+        self.gpu
+            .ubo_palette
+            .upload_data(gl, &PaletteUbo::new(&self.palette));
+    }
+
+    /// Current on/off phase of blinking text, derived from the animation clock.
+    fn text_blink_phase(&self) -> u32 {
+        self.time_ms
+            .checked_div(self.text_blink_ms)
+            .map_or(0, |phase| phase & 1)
+    }
+
     /// Returns the canvas size in pixels.
     #[must_use]
     pub fn canvas_size(&self) -> (i32, i32) {
@@ -416,7 +686,7 @@ impl TerminalGrid {
     /// Returns the size of the terminal grid in cells.
     #[must_use]
     pub fn terminal_size(&self) -> TerminalSize {
-        self.terminal_size
+        self.model.terminal_size()
     }
 
     /// Renders the terminal grid in a single call.
@@ -437,98 +707,270 @@ impl TerminalGrid {
     }
 
     /// Returns a mutable reference to the cell data at the specified cell coordinates.
+    ///
+    /// Coordinates refer to the live screen, regardless of the scrollback viewport.
     pub fn cell_data_mut(&mut self, x: u16, y: u16) -> Option<&mut CellDynamic> {
-        let cols = self.terminal_size.cols;
-        let idx = y as usize * cols as usize + x as usize;
-        self.dirty_regions.mark(idx);
-        self.cells.get_mut(idx)
+        self.model.cell_data_mut(x, y)
     }
 
     /// Returns the active selection state of the terminal grid.
     #[must_use]
     pub fn selection_tracker(&self) -> SelectionTracker {
-        self.selection.clone()
+        self.model.selection_tracker()
     }
 
-    /// Returns the symbols in the specified block range as a `CompactString`.
-    pub(super) fn get_symbols(&self, selection: CellIterator) -> CompactString {
-        let mut text = CompactString::new("");
-
-        for (idx, require_newline_after) in selection {
-            let cell_symbol = self.get_cell_symbol(idx);
-            if cell_symbol.is_some() {
-                text.push_str(&cell_symbol.unwrap_or_default());
-            }
+    /// Internal method — not covered by semver guarantees.
+    #[doc(hidden)]
+    #[must_use]
+    pub fn hash_cells(&self, selection: CellQuery) -> u64 {
+        self.model.hash_cells(selection)
+    }
 
-            if require_newline_after {
-                text.push('\n'); // add newline after each row
-            }
+    /// Returns a copy of the cells currently shown in the viewport, in row-major
+    /// order. Selected cells carry their highlighted colors, as configured by
+    /// [`set_selection_style`](Self::set_selection_style).
+    ///
+    /// Together with a [`SoftwareRenderer`](crate::SoftwareRenderer), this
+    /// allows rendering snapshots of the grid without a GL context.
+    #[must_use]
+    pub fn viewport_cells(&self) -> Vec<CellDynamic> {
+        let mut cells = self.model.viewport_cells();
+        if let Some(query) = self.model.selection_tracker().get_query() {
+            self.cell_iter(query).for_each(|(idx, _)| {
+                self.selection_style
+                    .highlight(&mut cells[idx], &self.palette);
+            });
         }
 
-        text
+        cells
     }
 
-    /// Returns the ASCII character at the given position, if it's an ASCII char.
+    /// Zero-allocation iterator over cell indices for a given selection range and mode.
     ///
-    /// Returns `None` for non-ASCII characters or out-of-bounds positions.
-    /// This is an optimized path for URL detection that avoids string allocation.
-    pub(crate) fn get_ascii_char_at(&self, cursor: CursorPosition) -> Option<char> {
-        let idx = cursor.row as usize * self.terminal_size.cols as usize + cursor.col as usize;
-        if idx < self.cells.len() {
-            let glyph_id = self.cells[idx].glyph_id();
-            self.atlas.get_ascii_char(glyph_id)
-        } else {
-            None
-        }
+    /// See [`GridModel::cell_iter`].
+    #[must_use]
+    pub fn cell_iter(&self, selection: CellQuery) -> CellIterator {
+        self.model.cell_iter(selection)
     }
 
-    /// Internal method — not covered by semver guarantees.
-    #[doc(hidden)]
+    /// Extracts text content from the terminal based on the selection query.
+    ///
+    /// See [`GridModel::get_text`].
     #[must_use]
-    pub fn hash_cells(&self, selection: CellQuery) -> u64 {
-        use std::hash::{Hash, Hasher};
+    pub fn get_text(&self, selection: CellQuery) -> CompactString {
+        self.model.get_text(selection)
+    }
+
+    /// Exports the selected cells as text with ANSI SGR escape sequences.
+    ///
+    /// See [`export_ansi`](crate::export_ansi).
+    #[must_use]
+    pub fn get_ansi(&self, selection: CellQuery) -> String {
+        crate::export_ansi(&self.model, selection, &self.palette)
+    }
 
-        use rustc_hash::FxHasher;
+    /// Exports the selected cells as an HTML fragment with inline styles,
+    /// resolving palette colors against the grid's palette.
+    ///
+    /// See [`export_html`](crate::export_html).
+    #[must_use]
+    pub fn get_html(&self, selection: CellQuery) -> String {
+        crate::export_html(&self.model, selection, &self.palette)
+    }
 
-        let mut hasher = FxHasher::default();
-        for (idx, _) in self.cell_iter(selection) {
-            self.cells[idx].hash(&mut hasher);
+    /// Returns SVG export options matching the grid: the effective cell size
+    /// and the underline and strikethrough metrics of the font atlas.
+    #[must_use]
+    pub fn svg_options(&self) -> crate::SvgOptions {
+        let atlas = self.model.atlas();
+        crate::SvgOptions {
+            underline: atlas.underline(),
+            strikethrough: atlas.strikethrough(),
+            ..crate::SvgOptions::new(self.cell_size())
         }
+    }
 
-        hasher.finish()
+    /// Exports the viewport as an SVG image, resolving palette colors
+    /// against the grid's palette.
+    ///
+    /// See [`export_svg`](crate::export_svg) and [`svg_options`](Self::svg_options).
+    #[must_use]
+    pub fn get_svg(&self, options: &crate::SvgOptions) -> String {
+        crate::export_svg(&self.model, &self.palette, options)
     }
 
-    fn get_cell_symbol(&self, idx: usize) -> Option<CompactString> {
-        if idx < self.cells.len() {
-            let glyph_id = self.cells[idx].glyph_id();
-            let cell_symbol = self.atlas.get_symbol(glyph_id);
-            if cell_symbol.is_some() {
-                return cell_symbol;
-            }
+    /// Uploads an image of `width` x `height` RGBA pixels, in row-major order
+    /// with non-premultiplied alpha, for placement with [`place_image`](Self::place_image).
+    ///
+    /// # Errors
+    /// Returns an error if `pixels` does not hold exactly `width * height * 4`
+    /// bytes, or if the texture cannot be created.
+    pub fn upload_image(
+        &mut self,
+        gl: &glow::Context,
+        width: u32,
+        height: u32,
+        pixels: Vec<u8>,
+    ) -> Result<ImageId, Error> {
+        self.images.upload(gl, width, height, pixels)
+    }
+
+    /// Deletes an uploaded image along with all its placements.
+    ///
+    /// Returns `false` if no such image exists.
+    pub fn delete_image(&mut self, gl: &glow::Context, image: ImageId) -> bool {
+        self.images.delete_image(gl, image)
+    }
+
+    /// Places an uploaded image over a rectangle of viewport cells.
+    ///
+    /// Placements are anchored to the content lines they cover: they scroll
+    /// along with the content, both in the live screen and through the
+    /// scrollback history, and are removed once scrolled out of their scroll
+    /// region or the retained history. They follow the cell size when the grid
+    /// is resized and are cropped at the grid edges.
+    ///
+    /// # Errors
+    /// Returns an error if the placed image has not been uploaded.
+    pub fn place_image(&mut self, placement: ImagePlacement) -> Result<PlacementId, Error> {
+        self.images
+            .place(placement, self.model.scrollback_offset())
+    }
+
+    /// Removes an image placement, keeping the image for later placements.
+    ///
+    /// Returns `false` if no such placement exists.
+    pub fn remove_image_placement(&mut self, placement: PlacementId) -> bool {
+        self.images.remove_placement(placement)
+    }
+
+    /// Returns the image placements in drawing order.
+    pub fn image_placements(&self) -> impl Iterator<Item = (PlacementId, &ImagePlacement)> {
+        self.images.placements()
+    }
+
+    /// Deletes all uploaded images and their placements.
+    pub fn clear_images(&mut self, gl: &glow::Context) {
+        self.images.clear(gl);
+    }
+
+    /// Highlights a range of cells, e.g. a search hit or a diagnostic span.
+    ///
+    /// Highlights tint the cell backgrounds without modifying the cell data,
+    /// so removing a highlight restores the original look exactly. Like the
+    /// selection, they are anchored in viewport cells.
+    pub fn add_highlight(&mut self, highlight: Highlight) -> HighlightId {
+        self.highlights.add(highlight)
+    }
+
+    /// Removes a highlight.
+    ///
+    /// Returns `false` if no such highlight exists.
+    pub fn remove_highlight(&mut self, highlight: HighlightId) -> bool {
+        self.highlights.remove(highlight)
+    }
+
+    /// Returns the highlights in ascending priority.
+    pub fn highlights(&self) -> impl Iterator<Item = (HighlightId, &Highlight)> {
+        self.highlights.highlights()
+    }
+
+    /// Removes all highlights.
+    pub fn clear_highlights(&mut self) {
+        self.highlights.clear();
+    }
+
+    /// Returns the layout image placements are drawn against.
+    fn image_frame(&self) -> ImageFrame {
+        ImageFrame {
+            projection: Mat4::orthographic_from_size(
+                self.canvas_size_px.0 as f32,
+                self.canvas_size_px.1 as f32,
+            )
+            .data,
+            grid_size: self.model.terminal_size(),
+            cell_size: self.effective_cell_size(),
+            scroll_offset: self.scroll_offset,
+            viewport_offset: self.model.scrollback_offset(),
         }
+    }
 
-        self.fallback_symbol()
+    /// Rebinds the cell program and vertex array after drawing images.
+    fn restore_cell_pipeline(&self, gl: &glow::Context) {
+        self.gpu.shader.use_program(gl);
+        unsafe { gl.bind_vertex_array(Some(self.gpu.buffers.vao)) };
     }
 
     /// Uploads uniform buffer data for screen and cell dimensions.
-    fn upload_ubo_data(&self, gl: &glow::Context) {
-        let vertex_ubo = CellVertexUbo::new(self.canvas_size_px, self.effective_cell_size());
+    fn upload_ubo_data(&mut self, gl: &glow::Context) {
+        let vertex_ubo = CellVertexUbo::new(
+            self.canvas_size_px,
+            self.effective_cell_size(),
+            self.scroll_offset,
+            self.minimum_contrast,
+            self.model.terminal_size().cols,
+        );
         self.gpu.ubo_vertex.upload_data(gl, &vertex_ubo);
 
-        let fragment_ubo = CellFragmentUbo::new(&self.atlas, self.bg_alpha);
+        let viewport_offset = self.model.scrollback_offset();
+        let selection = self.selected_span();
+        let fragment_ubo = CellFragmentUbo::new(self.model.atlas(), self.bg_alpha)
+            .with_cursor(&self.cursor, self.cursor_epoch_ms, viewport_offset)
+            .with_time(self.time_ms, self.text_blink_ms)
+            .with_selection(&self.selection_style, selection);
         self.gpu
             .ubo_fragment
             .upload_data(gl, &fragment_ubo);
+
+        self.synced_viewport_offset = viewport_offset;
+        self.synced_selection = selection;
+    }
+
+    /// Returns the cells covered by the active selection.
+    fn selected_span(&self) -> Option<CellSpan> {
+        self.model
+            .selection_tracker()
+            .get_query()
+            .map(|query| self.model.cell_span(query))
+    }
+
+    /// Re-uploads the uniform data if the selection has changed since the last
+    /// upload, e.g. by a mouse handler sharing the [`SelectionTracker`].
+    fn sync_selection(&mut self, gl: &glow::Context) {
+        if self.selected_span() != self.synced_selection {
+            self.upload_ubo_data(gl);
+        }
+    }
+
+    /// Re-resolves the highlight runs if the highlights, the selection or the
+    /// layout changed since they were last uploaded.
+    fn sync_highlights(&mut self, gl: &glow::Context, content_changed: bool) {
+        let selection = self.model.selection_tracker().get_query();
+        self.highlights
+            .sync(gl, &self.model, selection, content_changed);
+    }
+
+    /// Re-uploads the uniform data if the cursor row is stale, as the cursor
+    /// moves along with the live screen when the viewport offset changes.
+    fn sync_viewport_offset(&mut self, gl: &glow::Context) {
+        if self.model.scrollback_offset() != self.synced_viewport_offset {
+            self.upload_ubo_data(gl);
+        }
     }
 
     /// Returns the total number of cells in the terminal grid.
+    ///
+    /// Scrollback history lines are not included.
     #[must_use]
     pub fn cell_count(&self) -> usize {
-        self.cells.len()
+        self.model.cell_count()
     }
 
     /// Updates the content of terminal cells with new data.
     ///
+    /// Cells are written to the live screen in row-major order, regardless of
+    /// the scrollback viewport.
+    ///
     /// # Errors
     /// This method is infallible in the current implementation but returns
     /// `Result` for API consistency with other update methods.
@@ -536,42 +978,10 @@ impl TerminalGrid {
         &mut self,
         cells: impl Iterator<Item = CellData<'a>>,
     ) -> Result<(), Error> {
-        let fallback_glyph = GlyphSlot::Normal(self.fallback_glyph);
-
-        // split borrows: atlas needs &mut, cells needs &mut, dirty_regions needs &mut
-        let atlas = &mut self.atlas;
-        let cell_buf = &mut self.cells;
-
-        // handle double-width emoji that span two cells
-        let mut pending_cell: Option<CellDynamic> = None;
-        cell_buf
-            .iter_mut()
-            .zip(cells)
-            .for_each(|(cell, data)| {
-                let glyph = atlas
-                    .resolve_glyph_slot(data.symbol, data.style_bits)
-                    .unwrap_or(fallback_glyph);
-
-                *cell = if let Some(second_cell) = pending_cell.take() {
-                    second_cell
-                } else {
-                    match glyph {
-                        GlyphSlot::Normal(id) => CellDynamic::new(id, data.fg, data.bg),
-
-                        GlyphSlot::Wide(id) | GlyphSlot::Emoji(id) => {
-                            // storing a double-width glyph, reserve next cell with right-half id
-                            pending_cell = Some(CellDynamic::new(id + 1, data.fg, data.bg));
-                            CellDynamic::new(id, data.fg, data.bg)
-                        },
-                    }
-                }
-            });
-
-        self.dirty_regions.mark_all();
-        Ok(())
+        self.model.update_cells(cells)
     }
 
-    /// Updates cells at specific grid coordinates.
+    /// Updates cells at specific grid coordinates on the live screen.
     ///
     /// # Errors
     /// This method is infallible in the current implementation but returns
@@ -580,13 +990,10 @@ impl TerminalGrid {
         &mut self,
         cells: impl Iterator<Item = (u16, u16, CellData<'a>)>,
     ) -> Result<(), Error> {
-        let cols = self.terminal_size.cols as usize;
-        let cells_by_index = cells.map(|(x, y, data)| (y as usize * cols + x as usize, data));
-
-        self.update_cells_by_index(cells_by_index)
+        self.model.update_cells_by_position(cells)
     }
 
-    /// Updates cells at specific flat indices.
+    /// Updates cells at specific flat indices on the live screen.
     ///
     /// # Errors
     /// This method is infallible in the current implementation but returns
@@ -595,54 +1002,7 @@ impl TerminalGrid {
         &mut self,
         cells: impl Iterator<Item = (usize, CellData<'a>)>,
     ) -> Result<(), Error> {
-        let fallback_glyph = GlyphSlot::Normal(self.fallback_glyph);
-
-        let atlas = &mut self.atlas;
-        let cell_buf = &mut self.cells;
-        let dirty_regions = &mut self.dirty_regions;
-
-        let cell_count = cell_buf.len();
-
-        // ratatui and beamterm can disagree on which emoji
-        // are double-width (beamterm assumes double-width for all emoji),
-        // so for ratatui and similar clients we need to skip the next cell
-        // if we just wrote a double-width emoji in the current cell.
-        let mut skip_idx = None;
-
-        cells
-            .filter(|(idx, _)| *idx < cell_count)
-            .for_each(|(idx, cell)| {
-                if skip_idx.take() == Some(idx) {
-                    // skip this cell, already handled as part of previous double-width emoji
-                    return;
-                }
-
-                let glyph = atlas
-                    .resolve_glyph_slot(cell.symbol, cell.style_bits)
-                    .unwrap_or(fallback_glyph);
-
-                match glyph {
-                    GlyphSlot::Normal(id) => {
-                        cell_buf[idx] = CellDynamic::new(id, cell.fg, cell.bg);
-                        dirty_regions.mark(idx);
-                    },
-
-                    GlyphSlot::Wide(id) | GlyphSlot::Emoji(id) => {
-                        // render left half in current cell
-                        cell_buf[idx] = CellDynamic::new(id, cell.fg, cell.bg);
-                        dirty_regions.mark(idx);
-
-                        // render right half in next cell, if within bounds
-                        if let Some(c) = cell_buf.get_mut(idx + 1) {
-                            *c = CellDynamic::new(id + 1, cell.fg, cell.bg);
-                            dirty_regions.mark(idx + 1);
-                            skip_idx = Some(idx + 1);
-                        }
-                    },
-                }
-            });
-
-        Ok(())
+        self.model.update_cells_by_index(cells)
     }
 
     /// Updates a single cell at the given grid coordinates.
@@ -651,9 +1011,7 @@ impl TerminalGrid {
     /// This method is infallible in the current implementation but returns
     /// `Result` for API consistency with batch update methods.
     pub fn update_cell(&mut self, x: u16, y: u16, cell_data: CellData) -> Result<(), Error> {
-        let cols = self.terminal_size.cols;
-        let idx = y as usize * cols as usize + x as usize;
-        self.update_cell_by_index(idx, cell_data)
+        self.model.update_cell(x, y, cell_data)
     }
 
     /// Updates a single cell at the given flat index.
@@ -662,75 +1020,189 @@ impl TerminalGrid {
     /// This method is infallible in the current implementation but returns
     /// `Result` for API consistency with batch update methods.
     pub fn update_cell_by_index(&mut self, idx: usize, cell_data: CellData) -> Result<(), Error> {
-        self.update_cells_by_index(std::iter::once((idx, cell_data)))
+        self.model.update_cell_by_index(idx, cell_data)
     }
 
-    /// Flushes pending cell updates to the GPU.
+    /// Sets the DEC line attribute of a row on the live screen.
     ///
-    /// This also flushes any pending glyph data in the atlas texture
-    /// (e.g., newly rasterized glyphs in a dynamic atlas).
+    /// See [`GridModel::set_line_attribute`].
+    pub fn set_line_attribute(&mut self, row: u16, line_attribute: LineAttribute) {
+        self.model.set_line_attribute(row, line_attribute);
+    }
+
+    /// Returns the DEC line attribute of a row on the live screen.
     ///
-    /// # Errors
-    /// Returns an error if the atlas texture flush fails (e.g., glyph
-    /// rasterization or texture upload failure in a dynamic atlas).
-    pub fn flush_cells(&mut self, gl: &glow::Context) -> Result<(), Error> {
-        // flush any pending atlas glyph uploads before uploading cell data
-        self.atlas.bind(gl);
-        self.atlas.flush(gl)?;
+    /// See [`GridModel::line_attribute`].
+    #[must_use]
+    pub fn line_attribute(&self, row: u16) -> LineAttribute {
+        self.model.line_attribute(row)
+    }
 
-        if self.dirty_regions.is_clean() {
-            return Ok(()); // no pending updates to flush
-        }
+    /// Returns the DEC line attribute of a viewport row.
+    ///
+    /// See [`GridModel::viewport_line_attribute`].
+    #[must_use]
+    pub fn viewport_line_attribute(&self, row: u16) -> LineAttribute {
+        self.model.viewport_line_attribute(row)
+    }
 
-        // if there is an active selected region with a content hash,
-        // check if the underlying content has changed; if so, clear the selection
-        self.clear_stale_selection();
+    /// Marks whether a row on the live screen continues on the next row.
+    ///
+    /// See [`GridModel::set_line_wrapped`].
+    pub fn set_line_wrapped(&mut self, row: u16, wrapped: bool) {
+        self.model.set_line_wrapped(row, wrapped);
+    }
 
-        // If there's an active selection, flip the colors of the selected cells.
-        // This ensures that the selected cells are rendered with inverted colors
-        // during the GPU upload process.
-        self.flip_selected_cell_colors();
+    /// Returns `true` if a row on the live screen continues on the next row.
+    #[must_use]
+    pub fn is_line_wrapped(&self, row: u16) -> bool {
+        self.model.is_line_wrapped(row)
+    }
 
-        self.gpu.buffers.bind_instance_buffer(gl);
-        if self.dirty_regions.is_all_active_dirty() {
-            // all active chunks dirty — single full upload via buffer orphaning
-            self.gpu
-                .buffers
-                .upload_instance_data(gl, &self.cells);
+    /// Captures the live screen, selection and cursor in a [`GridSnapshot`],
+    /// e.g. for restoring a session with [`restore`](Self::restore).
+    ///
+    /// See [`snapshot_grid`](crate::snapshot_grid).
+    #[must_use]
+    pub fn snapshot(&self) -> GridSnapshot {
+        GridSnapshot {
+            cursor: Some(crate::snapshot::cursor_snapshot(&self.cursor)),
+            ..crate::snapshot_grid(&self.model)
+        }
+    }
 
-            self.dirty_regions.clear();
-        } else {
-            // merge adjacent dirty chunks into contiguous uploads
-            for (start, end) in self.dirty_regions.drain() {
-                self.gpu.buffers.upload_instance_data_range(
-                    gl,
-                    &self.cells[start..end],
-                    start * CellDynamic::SIZE,
-                );
-            }
+    /// Restores the live screen, selection and cursor of a [`GridSnapshot`],
+    /// which may have been taken with a different font atlas or grid size.
+    ///
+    /// See [`restore_grid`](crate::restore_grid).
+    pub fn restore(&mut self, gl: &glow::Context, snapshot: &GridSnapshot) {
+        crate::restore_grid(&mut self.model, snapshot);
+        if let Some(cursor) = &snapshot.cursor {
+            self.set_cursor(gl, crate::snapshot::cursor_from_snapshot(cursor));
         }
-        self.gpu.buffers.unbind_instance_buffer(gl);
+    }
 
-        // Restore the original colors of the selected cells after the upload.
-        // This ensures that the internal state of the cells remains consistent.
-        self.flip_selected_cell_colors();
+    /// Applies the operations of a [`FrameDiff`], e.g. received from a server
+    /// rendering a TUI, to the live screen.
+    ///
+    /// Like [`apply_frame_diff`](crate::apply_frame_diff), but scrolls are
+    /// copied within the GPU instance buffer; see [`scroll_region`](Self::scroll_region).
+    ///
+    /// # Errors
+    /// Returns an error if flushing the pending cell updates before a scroll fails.
+    pub fn apply_frame_diff(&mut self, gl: &glow::Context, diff: &FrameDiff) -> Result<(), Error> {
+        for op in &diff.ops {
+            match op {
+                FrameOp::Resize(size) => {
+                    self.model.resize(*size);
+                },
+                FrameOp::Scroll { top, bottom, lines, fill } => {
+                    let fill = crate::snapshot::cell_data(fill);
+                    self.scroll_region(gl, *top, *bottom, *lines, fill)?;
+                },
+                op => crate::frame::apply_cell_op(&mut self.model, op),
+            }
+        }
 
         Ok(())
     }
 
-    fn flip_selected_cell_colors(&mut self) {
-        if let Some(iter) = self.selected_cells_iter() {
-            iter.for_each(|(idx, _)| {
-                self.cells[idx].flip_colors();
-                self.dirty_regions.mark(idx);
-            });
-        }
+    /// Registers a hyperlink target for linking cells.
+    ///
+    /// See [`GridModel::register_hyperlink`].
+    pub fn register_hyperlink(&mut self, uri: &str, id: Option<&str>) -> HyperlinkId {
+        self.model.register_hyperlink(uri, id)
     }
 
-    fn selected_cells_iter(&self) -> Option<CellIterator> {
-        self.selection
-            .get_query()
-            .map(|query| self.cell_iter(query))
+    /// Returns the hyperlink at a viewport position, along with its extent.
+    ///
+    /// See [`GridModel::hyperlink_at`].
+    #[must_use]
+    pub fn hyperlink_at(&self, cursor: CursorPosition) -> Option<UrlMatch> {
+        self.model.hyperlink_at(cursor)
+    }
+
+    /// Returns every match of the `matchers` in the viewport.
+    ///
+    /// See [`find_all_matches`](crate::find_all_matches).
+    #[must_use]
+    pub fn find_all_matches(&self, matchers: &[Matcher]) -> Vec<PatternMatch> {
+        crate::find_all_matches(&self.model, matchers)
+    }
+
+    /// Returns every match of a text search in the viewport, in reading order.
+    ///
+    /// See [`search`](crate::search()).
+    #[must_use]
+    pub fn search(&self, query: &SearchQuery) -> Vec<SearchMatch> {
+        crate::search(&self.model, query)
+    }
+
+    /// Flushes pending cell updates to the GPU.
+    ///
+    /// This also flushes any pending glyph data in the atlas texture
+    /// (e.g., newly rasterized glyphs in a dynamic atlas), and recreates the
+    /// instance buffers if the model was resized through [`model_mut`](Self::model_mut).
+    ///
+    /// # Errors
+    /// Returns an error if the atlas texture flush fails (e.g., glyph
+    /// rasterization or texture upload failure in a dynamic atlas), or if
+    /// GPU buffer recreation fails.
+    pub fn flush_cells(&mut self, gl: &glow::Context) -> Result<(), Error> {
+        // flush any pending atlas glyph uploads before uploading cell data
+        let atlas = self.model.atlas_mut();
+        atlas.bind(gl);
+        atlas.flush(gl)?;
+
+        if self.model_layout() != self.synced_layout {
+            self.recreate_instance_buffers(gl)?;
+        }
+        self.sync_viewport_offset(gl);
+        let line_attributes_changed = match self.model.take_line_attribute_update() {
+            Some(line_attributes) => {
+                self.gpu
+                    .buffers
+                    .upload_line_attributes(gl, line_attributes);
+                true
+            },
+            None => false,
+        };
+
+        if self.model.cells_and_dirty_regions().1.is_clean() {
+            // the selection may have changed without any cell updates
+            self.sync_selection(gl);
+            self.sync_highlights(gl, line_attributes_changed);
+            return Ok(()); // no pending updates to flush
+        }
+
+        // if there is an active selected region with a content hash,
+        // check if the underlying content has changed; if so, clear the selection
+        self.model.clear_stale_selection();
+        self.sync_selection(gl);
+        self.sync_highlights(gl, true);
+
+        let buffers = &self.gpu.buffers;
+        let (cells, dirty_regions) = self.model.cells_and_dirty_regions();
+
+        buffers.bind_instance_buffer(gl);
+        if dirty_regions.is_all_active_dirty() {
+            // every cell dirty — single full upload via buffer orphaning
+            buffers.upload_instance_data(gl, cells);
+
+            dirty_regions.clear();
+        } else {
+            // upload the coalesced dirty cell ranges
+            for (start, end) in dirty_regions.drain() {
+                buffers.upload_instance_data_range(
+                    gl,
+                    &cells[start..end],
+                    start * CellDynamic::SIZE,
+                );
+            }
+        }
+        buffers.unbind_instance_buffer(gl);
+
+        Ok(())
     }
 
     /// Resizes the terminal grid to fit the new canvas dimensions.
@@ -759,42 +1231,169 @@ impl TerminalGrid {
 
         let cols = (canvas_size.0 / cell_size.width).max(1);
         let rows = (canvas_size.1 / cell_size.height).max(1);
-        if self.terminal_size == TerminalSize::new(cols as u16, rows as u16) {
+        if !self
+            .model
+            .resize(TerminalSize::new(cols as u16, rows as u16))
+        {
             return Ok(()); // no change in terminal size
         }
 
-        // update buffers; bind VAO to ensure correct state
-        unsafe {
-            gl.bind_vertex_array(Some(self.gpu.buffers.vao));
+        self.recreate_instance_buffers(gl)?;
 
-            // delete old cell instance buffers
-            gl.delete_buffer(self.gpu.buffers.instance_cell);
-            gl.delete_buffer(self.gpu.buffers.instance_pos);
+        // resizing resets the viewport to the live screen
+        self.sync_viewport_offset(gl);
+
+        Ok(())
+    }
+
+    /// Sets the maximum number of lines kept in the scrollback history.
+    ///
+    /// The most recent history lines are kept when the capacity shrinks, and
+    /// the viewport is reset to the live screen. A capacity of `0` disables
+    /// the scrollback history.
+    ///
+    /// # Errors
+    /// Returns an error if GPU buffer recreation fails.
+    pub fn set_scrollback_capacity(
+        &mut self,
+        gl: &glow::Context,
+        lines: usize,
+    ) -> Result<(), Error> {
+        if lines == self.model.scrollback_capacity() {
+            return Ok(());
         }
 
-        // resize cell data vector
-        let current_size = (
-            self.terminal_size.cols as i32,
-            self.terminal_size.rows as i32,
-        );
-        let cell_data = self.resize_cell_grid(current_size, (cols, rows));
-        self.cells = cell_data;
+        self.model.set_scrollback_capacity(lines);
+        self.recreate_instance_buffers(gl)?;
 
-        let cell_pos = CellStatic::create_grid(cols, rows);
+        // the cursor row depends on the viewport offset
+        self.sync_viewport_offset(gl);
 
-        // re-create buffers with new data
-        self.gpu.buffers.instance_cell = create_dynamic_instance_buffer(gl, &self.cells)?;
-        self.gpu.buffers.instance_pos = create_static_instance_buffer(gl, &cell_pos)?;
+        Ok(())
+    }
 
-        // unbind VAO
-        unsafe { gl.bind_vertex_array(None) };
+    /// Returns the maximum number of lines kept in the scrollback history.
+    #[must_use]
+    pub fn scrollback_capacity(&self) -> usize {
+        self.model.scrollback_capacity()
+    }
+
+    /// Returns the number of lines currently stored in the scrollback history.
+    #[must_use]
+    pub fn scrollback_len(&self) -> usize {
+        self.model.scrollback_len()
+    }
+
+    /// Returns how many lines the viewport is scrolled back into the history.
+    #[must_use]
+    pub fn scrollback_offset(&self) -> usize {
+        self.model.scrollback_offset()
+    }
+
+    /// Scrolls the viewport `lines` back into the scrollback history, where
+    /// `0` shows the live screen. The offset is clamped to the stored history.
+    ///
+    /// Scrolling only changes the draw offsets into the instance buffer; no
+    /// cell data is re-uploaded. Any active selection is cleared.
+    ///
+    /// Returns the effective offset.
+    pub fn set_scrollback_offset(&mut self, gl: &glow::Context, lines: usize) -> usize {
+        let offset = self.model.set_scrollback_offset(lines);
+
+        // the cursor moves along with the live screen
+        self.sync_viewport_offset(gl);
+
+        offset
+    }
+
+    /// Returns the sub-cell scroll offset, in cells.
+    #[must_use]
+    pub fn scroll_offset(&self) -> (f32, f32) {
+        self.scroll_offset
+    }
+
+    /// Shifts the rendered content by a fraction of a cell, for smooth
+    /// touchpad and kinetic scrolling. Positive values move the content right
+    /// and down; both components are clamped to `-1.0..=1.0`.
+    ///
+    /// The row revealed at the top or bottom edge is drawn from the scrollback
+    /// history, so combining this with [`set_scrollback_offset`](Self::set_scrollback_offset)
+    /// scrolls through the history pixel by pixel.
+    ///
+    /// Horizontal offsets are limited to shifting the viewport: the grid has no
+    /// columns beyond its edges, so no partial columns are rendered. The column
+    /// revealed at the left or right edge shows the cleared canvas, and the
+    /// column at the opposite edge is clipped by it.
+    ///
+    /// Only the vertex uniform data is re-uploaded.
+    pub fn set_scroll_offset(&mut self, gl: &glow::Context, x: f32, y: f32) {
+        let offset = (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0));
+        if offset == self.scroll_offset {
+            return;
+        }
+
+        self.scroll_offset = offset;
+        self.upload_ubo_data(gl);
+    }
+
+    /// Scrolls the rows `top..bottom` of the live screen by `lines`, like a
+    /// terminal scroll region (DECSTBM). Positive values move the content up,
+    /// negative values move it down. Rows scrolled out of the region are
+    /// discarded, and the vacated rows are filled with `fill`.
+    ///
+    /// Moved rows are copied within the GPU instance buffer instead of being
+    /// re-uploaded; only the vacated rows are uploaded on the next flush. To
+    /// make the copy consistent, pending cell updates are flushed first and
+    /// any active selection is cleared.
+    ///
+    /// For full-screen scrolling, [`scroll_into_history`](Self::scroll_into_history)
+    /// is cheaper still, as it moves no cells at all.
+    ///
+    /// # Errors
+    /// Returns an error if flushing the pending cell updates fails.
+    pub fn scroll_region(
+        &mut self,
+        gl: &glow::Context,
+        top: u16,
+        bottom: u16,
+        lines: i32,
+        fill: CellData,
+    ) -> Result<(), Error> {
+        if self
+            .model
+            .is_noop_region_scroll(top, bottom, lines)
+        {
+            return Ok(());
+        }
+
+        // bring the instance buffer in sync with the cells before copying within it
+        self.model.clear_selection();
+        self.flush_cells(gl)?;
 
-        self.terminal_size = TerminalSize::new(cols as u16, rows as u16);
-        self.dirty_regions = DirtyRegions::new(self.cells.len());
+        for (src, dst, count) in self.model.move_region(top, bottom, lines, fill) {
+            self.gpu
+                .buffers
+                .copy_instance_data(gl, src, dst, count);
+        }
+        self.images.scroll_region(top, bottom, lines);
 
         Ok(())
     }
 
+    /// Scrolls the live screen up by `lines`, moving its top rows into the
+    /// scrollback history. The vacated rows at the bottom are filled with `fill`.
+    ///
+    /// Existing cell data is never moved; only the vacated rows are re-uploaded
+    /// on the next flush. With a scrollback capacity of `0`, this is a cheap
+    /// full-screen scroll. A scrolled-back viewport stays anchored to the
+    /// lines it shows.
+    pub fn scroll_into_history(&mut self, gl: &glow::Context, lines: u16, fill: CellData) {
+        self.model.scroll_into_history(lines, fill);
+        self.images
+            .scroll_into_history(lines, self.model.scrollback_len());
+        self.sync_viewport_offset(gl);
+    }
+
     /// Recreates all GPU resources after a context loss.
     ///
     /// Note: After a context loss, old GL resources are already invalid,
@@ -809,20 +1408,21 @@ impl TerminalGrid {
         glsl_version: &crate::GlslVersion,
     ) -> Result<(), Error> {
         let cell_size = self.effective_cell_size();
-        let (cols, rows) = (
-            self.terminal_size.cols as i32,
-            self.terminal_size.rows as i32,
-        );
-        let cell_pos = CellStatic::create_grid(cols, rows);
+        let size = self.model.terminal_size();
+        let cell_pos = CellStatic::create_grid(size.cols as i32, size.rows as i32);
 
         // Recreate all GPU resources (old ones are invalid after context loss)
-        self.gpu = GpuResources::new(gl, &cell_pos, &self.cells, cell_size, *glsl_version)?;
+        self.gpu = GpuResources::new(gl, &cell_pos, &self.model, cell_size, *glsl_version)?;
+        self.images.recreate(gl, *glsl_version)?;
+        self.highlights.recreate(gl, *glsl_version)?;
+        self.synced_layout = self.model_layout();
 
         // Upload UBO data
         self.upload_ubo_data(gl);
+        self.upload_palette(gl);
 
         // Mark cells as needing flush to upload to new buffers
-        self.dirty_regions.mark_all();
+        self.model.cells_and_dirty_regions().1.mark_all();
 
         Ok(())
     }
@@ -832,46 +1432,52 @@ impl TerminalGrid {
     /// # Errors
     /// Returns an error if GPU texture creation fails.
     pub fn recreate_atlas_texture(&mut self, gl: &glow::Context) -> Result<(), Error> {
-        self.atlas.recreate_texture(gl)
+        self.model.atlas_mut().recreate_texture(gl)
     }
 
     /// Returns the base glyph identifier for a given symbol.
     pub fn base_glyph_id(&mut self, symbol: &str) -> Option<u16> {
-        self.atlas.get_base_glyph_id(symbol)
+        self.model.atlas_mut().get_base_glyph_id(symbol)
     }
 
-    fn fallback_symbol(&self) -> Option<CompactString> {
-        self.atlas.get_symbol(self.fallback_glyph)
+    /// Returns the model's terminal size and ring length, which determine the
+    /// layout of the instance buffers.
+    fn model_layout(&self) -> (TerminalSize, usize) {
+        (self.model.terminal_size(), self.model.cells().len())
     }
 
-    fn clear_stale_selection(&self) {
-        if let Some(query) = self.selection_tracker().get_query()
-            && let Some(hash) = query.content_hash
-            && hash != self.hash_cells(query)
-        {
-            self.selection.clear();
+    /// Re-creates the instance buffers from the current cell ring.
+    fn recreate_instance_buffers(&mut self, gl: &glow::Context) -> Result<(), Error> {
+        // update buffers; bind VAO to ensure correct state
+        unsafe {
+            gl.bind_vertex_array(Some(self.gpu.buffers.vao));
+
+            // delete old cell instance buffers
+            gl.delete_buffer(self.gpu.buffers.instance_cell);
+            gl.delete_buffer(self.gpu.buffers.instance_pos);
+            gl.delete_buffer(self.gpu.buffers.instance_line);
         }
-    }
 
-    fn resize_cell_grid(&mut self, old_size: (i32, i32), new_size: (i32, i32)) -> Vec<CellDynamic> {
-        let empty_cell = CellDynamic::new(self.atlas.space_glyph_id(), 0xFFFFFF, 0x000000);
+        let size = self.model.terminal_size();
+        let cell_pos = CellStatic::create_grid(size.cols as i32, size.rows as i32);
 
-        let new_len = new_size.0 * new_size.1;
-        let mut new_cells = Vec::with_capacity(new_len as usize);
-        for _ in 0..new_len {
-            new_cells.push(empty_cell);
-        }
+        // re-create buffers with new data
+        self.gpu.buffers.instance_cell = create_dynamic_instance_buffer(gl, self.model.cells())?;
+        self.gpu.buffers.instance_pos = create_static_instance_buffer(gl, &cell_pos)?;
+        self.gpu.buffers.instance_line =
+            create_line_attribute_buffer(gl, self.model.line_attributes(), size.cols)?;
+        self.model.take_line_attribute_update();
 
-        let cells = &self.cells;
-        for y in 0..min(old_size.1, new_size.1) {
-            for x in 0..min(old_size.0, new_size.0) {
-                let new_idx = (y * new_size.0 + x) as usize;
-                let old_idx = (y * old_size.0 + x) as usize;
-                new_cells[new_idx] = cells[old_idx];
-            }
-        }
+        // unbind VAO
+        unsafe { gl.bind_vertex_array(None) };
+
+        self.model.cells_and_dirty_regions().1.clear();
+        self.synced_layout = self.model_layout();
+
+        // the vertex uniforms include the number of grid columns
+        self.upload_ubo_data(gl);
 
-        new_cells
+        Ok(())
     }
 }
 
@@ -879,7 +1485,7 @@ fn setup_buffers(
     gl: &glow::Context,
     vao: glow::VertexArray,
     cell_pos: &[CellStatic],
-    cell_data: &[CellDynamic],
+    model: &GridModel,
     cell_size: CellSize,
 ) -> Result<TerminalBuffers, Error> {
     let (w, h) = (cell_size.width as f32, cell_size.height as f32);
@@ -898,8 +1504,16 @@ fn setup_buffers(
         vao,
         vertices: create_buffer_f32(gl, glow::ARRAY_BUFFER, &vertices, glow::STATIC_DRAW)?,
         instance_pos: create_static_instance_buffer(gl, cell_pos)?,
-        instance_cell: create_dynamic_instance_buffer(gl, cell_data)?,
+        instance_cell: create_dynamic_instance_buffer(gl, model.cells())?,
+        instance_line: create_line_attribute_buffer(
+            gl,
+            model.line_attributes(),
+            model.terminal_size().cols,
+        )?,
         indices: create_buffer_u8(gl, glow::ELEMENT_ARRAY_BUFFER, &indices, glow::STATIC_DRAW)?,
+        copy_scratch: unsafe { gl.create_buffer() }
+            .map_err(|e| Error::buffer_creation_failed("copy-scratch-buffer", e))?,
+        copy_scratch_size: 0,
     })
 }
 
@@ -978,7 +1592,7 @@ fn create_dynamic_instance_buffer(
     enable_vertex_attrib_array(
         gl,
         attrib::PACKED_DEPTH_FG_BG,
-        2,
+        3,
         glow::UNSIGNED_INT,
         0,
         stride,
@@ -987,6 +1601,35 @@ fn create_dynamic_instance_buffer(
     Ok(buffer)
 }
 
+/// Creates the buffer of line attributes, advancing once per row of `cols` instances.
+fn create_line_attribute_buffer(
+    gl: &glow::Context,
+    line_attributes: &[LineAttribute],
+    cols: u16,
+) -> Result<glow::Buffer, Error> {
+    let buffer = unsafe { gl.create_buffer() }
+        .map_err(|e| Error::buffer_creation_failed("line-attribute-buffer", e))?;
+
+    let data = shader_line_attributes(line_attributes);
+    unsafe {
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(buffer));
+        gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, &data, glow::DYNAMIC_DRAW);
+    }
+
+    enable_vertex_attrib(gl, attrib::LINE_ATTRIBUTE, 1, glow::UNSIGNED_BYTE, 0, 1);
+    unsafe { gl.vertex_attrib_divisor(attrib::LINE_ATTRIBUTE, cols as u32) };
+
+    Ok(buffer)
+}
+
+/// Converts line attributes to the identifiers consumed by the cell shaders.
+fn shader_line_attributes(line_attributes: &[LineAttribute]) -> Vec<u8> {
+    line_attributes
+        .iter()
+        .map(|line_attribute| line_attribute.shader_line_attribute())
+        .collect()
+}
+
 fn enable_vertex_attrib_array(
     gl: &glow::Context,
     index: u32,
@@ -1026,20 +1669,92 @@ impl Drawable for TerminalGrid {
         unsafe { gl.bind_vertex_array(Some(self.gpu.buffers.vao)) };
 
         context.state.active_texture(gl, glow::TEXTURE0);
-        self.atlas.bind(gl);
+        self.model.atlas().bind(gl);
         self.gpu.ubo_vertex.bind(context.gl);
         self.gpu.ubo_fragment.bind(context.gl);
-        unsafe { gl.uniform_1_i32(Some(&self.gpu.sampler_loc), 0) };
+        self.gpu.ubo_palette.bind(context.gl);
+        unsafe {
+            gl.uniform_1_i32(Some(&self.gpu.sampler_loc), 0);
+            gl.uniform_1_u32(Some(&self.gpu.pass_loc), CellPass::Combined as u32);
+        }
 
         Ok(())
     }
 
     fn draw(&self, context: &mut RenderContext) {
         let gl = context.gl;
-        let cell_count = self.cells.len() as i32;
+        let cols = self.model.terminal_size().cols as usize;
+        let scrollback = self.model.scrollback();
 
-        unsafe {
-            gl.draw_elements_instanced(glow::TRIANGLES, 6, glow::UNSIGNED_BYTE, 0, cell_count);
+        let draw_lines = |first_line: usize, pos_row: usize, count: usize| {
+            self.gpu
+                .buffers
+                .set_instance_offsets(gl, first_line, pos_row, cols);
+
+            let instance_count = (count * cols) as i32;
+            unsafe {
+                gl.draw_elements_instanced(
+                    glow::TRIANGLES,
+                    6,
+                    glow::UNSIGNED_BYTE,
+                    0,
+                    instance_count,
+                );
+            }
+        };
+
+        // partially visible row revealed by a sub-cell scroll offset
+        let partial_line = scrollback.partial_line(self.scroll_offset);
+
+        let draw_cells = || {
+            // the viewport covers at most two contiguous runs of ring lines;
+            // grid positions are offset by the margin row above the grid
+            for (line, row, count) in scrollback.viewport_segments() {
+                draw_lines(line, row + 1, count);
+            }
+            if let Some((line, pos_row)) = partial_line {
+                draw_lines(line, pos_row, 1);
+            }
+        };
+
+        if !self.images.has_below_text() && self.highlights.is_empty() {
+            draw_cells();
+        } else {
+            // images below the text and highlights are drawn between the cell
+            // backgrounds and the blended text, so translucent backgrounds still
+            // reveal the canvas and highlighted text keeps its colors
+            let set_pass = |pass: CellPass| unsafe {
+                gl.uniform_1_u32(Some(&self.gpu.pass_loc), pass as u32);
+            };
+
+            set_pass(CellPass::Backgrounds);
+            draw_cells();
+
+            let frame = self.image_frame();
+            self.images.draw(gl, context.state, &frame, true);
+            self.highlights.draw(gl, context.state, &frame);
+            self.restore_cell_pipeline(gl);
+
+            set_pass(CellPass::Text);
+            context.state.blend(gl, true).blend_func_separate(
+                gl,
+                glow::SRC_ALPHA,
+                glow::ONE_MINUS_SRC_ALPHA,
+                glow::ONE,
+                glow::ONE_MINUS_SRC_ALPHA,
+            );
+            draw_cells();
+            set_pass(CellPass::Combined);
+        }
+
+        if self
+            .images
+            .placements()
+            .any(|(_, placement)| !placement.is_below_text())
+        {
+            self.images
+                .draw(gl, context.state, &self.image_frame(), false);
+            self.restore_cell_pipeline(gl);
         }
     }
 
@@ -1050,9 +1765,11 @@ impl Drawable for TerminalGrid {
             gl.bind_texture(glow::TEXTURE_2D_ARRAY, None);
             gl.use_program(None);
         }
+        context.state.blend(gl, false);
 
         self.gpu.ubo_vertex.unbind(gl);
         self.gpu.ubo_fragment.unbind(gl);
+        self.gpu.ubo_palette.unbind(gl);
     }
 }
 
@@ -1069,10 +1786,14 @@ impl Drawable for TerminalGrid {
 /// - BB: Blue component
 #[derive(Debug, Copy, Clone)]
 pub struct CellData<'a> {
-    symbol: &'a str,
-    style_bits: u16,
-    fg: u32,
-    bg: u32,
+    pub(super) symbol: &'a str,
+    pub(super) style_bits: u16,
+    pub(super) fg: CellColor,
+    pub(super) bg: CellColor,
+    pub(super) underline_style: UnderlineStyle,
+    pub(super) underline_color: Option<u32>,
+    pub(super) attributes: CellAttributes,
+    pub(super) hyperlink: Option<HyperlinkId>,
 }
 
 impl<'a> CellData<'a> {
@@ -1093,7 +1814,350 @@ impl<'a> CellData<'a> {
     /// Creates new cell data with pre-encoded style bits.
     #[must_use]
     pub const fn new_with_style_bits(symbol: &'a str, style_bits: u16, fg: u32, bg: u32) -> Self {
-        Self { symbol, style_bits, fg, bg }
+        Self {
+            symbol,
+            style_bits,
+            fg: CellColor::Rgb(fg),
+            bg: CellColor::Rgb(bg),
+            underline_style: UnderlineStyle::Single,
+            underline_color: None,
+            attributes: CellAttributes::empty(),
+            hyperlink: None,
+        }
+    }
+
+    /// Replaces the foreground color, e.g. with a [`CellColor::Indexed`]
+    /// palette color.
+    #[must_use]
+    pub const fn with_fg(mut self, color: CellColor) -> Self {
+        self.fg = color;
+        self
+    }
+
+    /// Replaces the background color, e.g. with a [`CellColor::Indexed`]
+    /// palette color.
+    #[must_use]
+    pub const fn with_bg(mut self, color: CellColor) -> Self {
+        self.bg = color;
+        self
+    }
+
+    /// Underlines the cell using the given style.
+    ///
+    /// Sets the underline effect bit, so this also underlines cells created
+    /// with [`GlyphEffect::None`].
+    #[must_use]
+    pub const fn with_underline(mut self, style: UnderlineStyle) -> Self {
+        self.style_bits |= Glyph::UNDERLINE_FLAG;
+        self.underline_style = style;
+        self
+    }
+
+    /// Draws the underline in `color` (0xRRGGBB) instead of the foreground color.
+    #[must_use]
+    pub const fn with_underline_color(mut self, color: u32) -> Self {
+        self.underline_color = Some(color);
+        self
+    }
+
+    /// Adds the given rendering attributes, e.g. [`CellAttributes::DIM`].
+    #[must_use]
+    pub const fn with_attributes(mut self, attributes: CellAttributes) -> Self {
+        self.attributes = self.attributes.union(attributes);
+        self
+    }
+
+    /// Links the cell to a hyperlink target registered with
+    /// [`GridModel::register_hyperlink`].
+    ///
+    /// The link is kept alongside the cell data and does not affect rendering;
+    /// style linked cells, e.g. with an underline, to make them discoverable.
+    #[must_use]
+    pub const fn with_hyperlink(mut self, link: HyperlinkId) -> Self {
+        self.hyperlink = Some(link);
+        self
+    }
+
+    /// Packs the cell colors, underline and rendering attributes with a
+    /// resolved glyph id.
+    pub(super) fn to_cell_dynamic(self, glyph_id: u16) -> CellDynamic {
+        let mut cell = CellDynamic::new(glyph_id, 0, 0);
+        cell.fg(self.fg);
+        cell.bg(self.bg);
+        cell.underline_style(self.underline_style);
+        cell.underline_color(self.underline_color);
+        cell.attributes(self.attributes);
+        cell
+    }
+}
+
+bitflags! {
+    /// Rendering attributes applied by the cell shader.
+    ///
+    /// Unlike baking the effect into the cell colors, the original colors are
+    /// kept, so toggling an attribute never requires recomputing them:
+    ///
+    /// ```rust
+    /// use beamterm_core::{CellAttributes, CellData};
+    ///
+    /// let cell = CellData::new_with_style_bits("!", 0, 0xFFFFFF, 0x000000)
+    ///     .with_attributes(CellAttributes::BLINK | CellAttributes::REVERSE);
+    /// ```
+    #[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    pub struct CellAttributes : u8 {
+        /// Faint text; the foreground color is blended halfway towards the background.
+        const DIM     = 0b0000_0001;
+        /// Text and decorations blink with the grid's text blink interval.
+        const BLINK   = 0b0000_0010;
+        /// Concealed text; only the background is drawn.
+        const HIDDEN  = 0b0000_0100;
+        /// Reverse video; the foreground and background colors are swapped.
+        const REVERSE = 0b0000_1000;
+    }
+}
+
+/// Shape of the line drawn for underlined cells.
+///
+/// Corresponds to the `SGR 4:x` subparameters; the underline itself is
+/// enabled by the [`GlyphEffect::Underline`] bit of the glyph id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum UnderlineStyle {
+    /// A single straight line.
+    #[default]
+    Single,
+    /// Two thin parallel lines.
+    Double,
+    /// A wavy line, commonly used for spelling and diagnostic markers.
+    Curly,
+    /// A dotted line.
+    Dotted,
+    /// A dashed line.
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// Style identifier consumed by the fragment shader.
+    pub(crate) const fn shader_style(self) -> u8 {
+        match self {
+            UnderlineStyle::Single => 0,
+            UnderlineStyle::Double => 1,
+            UnderlineStyle::Curly => 2,
+            UnderlineStyle::Dotted => 3,
+            UnderlineStyle::Dashed => 4,
+        }
+    }
+
+    pub(crate) const fn from_shader_style(style: u8) -> Self {
+        match style {
+            1 => UnderlineStyle::Double,
+            2 => UnderlineStyle::Curly,
+            3 => UnderlineStyle::Dotted,
+            4 => UnderlineStyle::Dashed,
+            _ => UnderlineStyle::Single,
+        }
+    }
+}
+
+/// DEC line attribute of a terminal row (DECSWL, DECDWL and DECDHL).
+///
+/// Double-width and double-height rows draw each cell twice as wide, so only
+/// the cells in the left half of the row are visible. Double-height text spans
+/// two rows: the upper row shows the top half of the glyphs and the lower row
+/// the bottom half, so both rows must hold the same text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineAttribute {
+    /// Regular single-width, single-height row.
+    #[default]
+    Single,
+    /// Double-width row (DECDWL).
+    DoubleWidth,
+    /// Top half of a double-height, double-width row (DECDHL).
+    DoubleHeightTop,
+    /// Bottom half of a double-height, double-width row (DECDHL).
+    DoubleHeightBottom,
+}
+
+impl LineAttribute {
+    /// Line attribute identifier consumed by the cell shaders.
+    pub(crate) const fn shader_line_attribute(self) -> u8 {
+        match self {
+            LineAttribute::Single => 0,
+            LineAttribute::DoubleWidth => 1,
+            LineAttribute::DoubleHeightTop => 2,
+            LineAttribute::DoubleHeightBottom => 3,
+        }
+    }
+
+    pub(crate) const fn from_shader_line_attribute(line_attribute: u8) -> Self {
+        match line_attribute {
+            1 => LineAttribute::DoubleWidth,
+            2 => LineAttribute::DoubleHeightTop,
+            3 => LineAttribute::DoubleHeightBottom,
+            _ => LineAttribute::Single,
+        }
+    }
+
+    /// Returns `true` for rows drawing cells at twice their width.
+    #[must_use]
+    pub const fn is_double_width(self) -> bool {
+        !matches!(self, LineAttribute::Single)
+    }
+
+    /// Returns `true` for the top and bottom halves of double-height rows.
+    #[must_use]
+    pub const fn is_double_height(self) -> bool {
+        matches!(
+            self,
+            LineAttribute::DoubleHeightTop | LineAttribute::DoubleHeightBottom
+        )
+    }
+}
+
+/// Shape of the terminal cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum CursorShape {
+    /// Fills the cell with the cursor color; the glyph is drawn using the
+    /// cell's background color.
+    #[default]
+    Block,
+    /// Thin vertical bar along the left edge of the cell.
+    Bar,
+    /// Horizontal bar along the bottom edge of the cell.
+    Underline,
+    /// Outline around the cell, commonly used for unfocused terminals.
+    HollowBlock,
+}
+
+/// Cursor state of a [`TerminalGrid`].
+///
+/// The cursor is rendered as an overlay by the cell shader, so the colors of
+/// the cell underneath are left untouched.
+///
+/// # Example
+/// ```
+/// use beamterm_core::{Cursor, CursorPosition, CursorShape};
+///
+/// let cursor = Cursor {
+///     position: CursorPosition::new(4, 2),
+///     shape: CursorShape::Bar,
+///     blink_interval_ms: 500,
+///     ..Cursor::default()
+/// };
+/// assert!(cursor.visible);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    /// Position of the cursor in grid coordinates.
+    pub position: CursorPosition,
+    /// Shape of the cursor.
+    pub shape: CursorShape,
+    /// Cursor color as 0xRRGGBB.
+    pub color: u32,
+    /// Duration of each on/off blink phase in milliseconds; `0` disables blinking.
+    pub blink_interval_ms: u32,
+    /// Whether the cursor is drawn at all.
+    pub visible: bool,
+}
+
+impl Cursor {
+    /// Returns `true` if the cursor is visible and blinking.
+    #[must_use]
+    pub fn is_blinking(&self) -> bool {
+        self.visible && self.blink_interval_ms > 0
+    }
+
+    /// Shape identifier consumed by the fragment shader; `0` hides the cursor.
+    fn shader_shape(&self) -> u32 {
+        if !self.visible {
+            return 0;
+        }
+
+        match self.shape {
+            CursorShape::Block => 1,
+            CursorShape::Bar => 2,
+            CursorShape::Underline => 3,
+            CursorShape::HollowBlock => 4,
+        }
+    }
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self {
+            position: CursorPosition::new(0, 0),
+            shape: CursorShape::Block,
+            color: 0xFFFFFF,
+            blink_interval_ms: 0,
+            visible: true,
+        }
+    }
+}
+
+/// Colors of selected cells in a [`TerminalGrid`].
+///
+/// The selection is highlighted by the cell shader from the
+/// [`SelectionTracker`] range, so the cell data is left untouched and cells
+/// with reverse video are highlighted like any other.
+///
+/// # Example
+/// ```
+/// use beamterm_core::SelectionStyle;
+///
+/// // selected cells are drawn in inverted colors by default
+/// assert_eq!(SelectionStyle::default(), SelectionStyle::Inverted);
+///
+/// // translucent blue highlight that keeps the text colors
+/// let style = SelectionStyle::Solid { background: 0x264F78, foreground: None, alpha: 0.6 };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SelectionStyle {
+    /// Swaps the foreground and background colors of selected cells.
+    #[default]
+    Inverted,
+    /// Blends a solid highlight color over the background of selected cells.
+    Solid {
+        /// Highlight background color as 0xRRGGBB.
+        background: u32,
+        /// Text color of selected cells as 0xRRGGBB; `None` keeps the cell's
+        /// foreground color.
+        foreground: Option<u32>,
+        /// Opacity of the highlight over the cell background, from `0.0` to `1.0`.
+        alpha: f32,
+    },
+}
+
+impl SelectionStyle {
+    /// Applies the highlight to a copy of a selected cell, resolving its colors
+    /// against `palette`.
+    fn highlight(&self, cell: &mut CellDynamic, palette: &Palette) {
+        let attributes = cell.get_attributes();
+        let (mut fg, mut bg) = (
+            palette.resolve(cell.get_fg()),
+            palette.resolve(cell.get_bg()),
+        );
+        if attributes.contains(CellAttributes::REVERSE) {
+            std::mem::swap(&mut fg, &mut bg);
+            cell.attributes(attributes - CellAttributes::REVERSE);
+        }
+
+        match *self {
+            SelectionStyle::Inverted => {
+                cell.fg_color(bg);
+                cell.bg_color(fg);
+            },
+            SelectionStyle::Solid { background, foreground, alpha } => {
+                let blend = |shift: u32| {
+                    let channel = |rgb: u32| ((rgb >> shift) & 0xFF) as f32;
+                    let mixed = channel(bg) + (channel(background) - channel(bg)) * alpha;
+                    (mixed.round() as u32) << shift
+                };
+
+                cell.fg_color(foreground.unwrap_or(fg));
+                cell.bg_color(blend(16) | blend(8) | blend(0));
+            },
+        }
     }
 }
 
@@ -1108,13 +2172,29 @@ struct CellStatic {
 /// Dynamic instance data for terminal cell appearance.
 ///
 /// `CellDynamic` contains the frequently-changing visual data for each terminal
-/// cell, including the character glyph and colors.
+/// cell, including the character glyph, colors and underline attributes.
 ///
 /// # Memory Layout
-/// The 8-byte data array is packed as follows:
+/// The 12-byte data array is packed as follows:
 /// - Bytes 0-1: Glyph depth/layer index (u16, little-endian)
 /// - Bytes 2-4: Foreground color RGB (3 bytes)
 /// - Bytes 5-7: Background color RGB (3 bytes)
+/// - Bytes 8-10: Underline color RGB (3 bytes), or the palette flags
+/// - Byte 11: Underline code (bits 0-3) and [`CellAttributes`] (bits 4-7)
+///
+/// A palette color stores its 9-bit [`Palette`](crate::Palette) slot in the
+/// red and green bytes of the color, little-endian. The palette flags mark
+/// the foreground (bit 0) and background (bit 1) as palette colors.
+///
+/// The underline code is the underline style plus five times the layout of
+/// the remaining bytes:
+/// - `0`: the underline is drawn in the foreground color, and byte 8 holds
+///   the palette flags.
+/// - `1`: bytes 8-10 hold the underline color, and both colors are RGB.
+/// - `2`: bytes 8-10 hold the underline color, and byte 7 holds the palette
+///   flags. The palette slot of the background, or of the foreground if only
+///   the foreground is a palette color, is stored in bytes 5-6; bytes 2-4
+///   hold the other color.
 #[derive(Debug, Clone, Copy, Hash)]
 #[repr(C, align(4))]
 pub struct CellDynamic {
@@ -1129,15 +2209,23 @@ pub struct CellDynamic {
     /// - `data[5]`: Background red component (0-255)
     /// - `data[6]`: Background green component (0-255)
     /// - `data[7]`: Background blue component (0-255)
-    data: [u8; 8], // 2b layer, fg:rgb, bg:rgb
+    /// - `data[8]`: Underline red component (0-255), or palette flags
+    /// - `data[9]`: Underline green component (0-255)
+    /// - `data[10]`: Underline blue component (0-255)
+    /// - `data[11]`: Underline code and cell attributes
+    data: [u8; 12], // 2b layer, fg:rgb, bg:rgb, underline:rgb, flags
 }
 
 impl CellStatic {
+    /// Creates grid positions for `rows` rows plus one margin row above and
+    /// below, which are used for the partially visible rows of a sub-cell
+    /// scroll offset. The shader subtracts one from every row, so the grid
+    /// proper starts at instance `cols`.
     fn create_grid(cols: i32, rows: i32) -> Vec<Self> {
         debug_assert!(cols > 0 && cols < u16::MAX as i32, "cols: {cols}");
-        debug_assert!(rows > 0 && rows < u16::MAX as i32, "rows: {rows}");
+        debug_assert!(rows > 0 && rows < u16::MAX as i32 - 2, "rows: {rows}");
 
-        (0..rows)
+        (0..rows + 2)
             .flat_map(|row| (0..cols).map(move |col| (col, row)))
             .map(|(col, row)| Self { grid_xy: [col as u16, row as u16] })
             .collect()
@@ -1150,11 +2238,25 @@ impl CellDynamic {
     const GLYPH_STYLE_MASK: u16 =
         Glyph::BOLD_FLAG | Glyph::ITALIC_FLAG | Glyph::UNDERLINE_FLAG | Glyph::STRIKETHROUGH_FLAG;
 
+    const UNDERLINE_CODE_MASK: u8 = 0x0F;
+    /// Number of underline styles; the underline code is the style plus this
+    /// many times the layout of the color bytes.
+    const UNDERLINE_STYLES: u8 = 5;
+    /// The underline is drawn in the foreground color; byte 8 holds the palette flags.
+    const FG_UNDERLINE_LAYOUT: u8 = 0;
+    /// Bytes 8-10 hold the underline color; both colors are RGB.
+    const RGB_UNDERLINE_LAYOUT: u8 = 1;
+    /// Bytes 8-10 hold the underline color; byte 7 holds the palette flags.
+    const PALETTE_UNDERLINE_LAYOUT: u8 = 2;
+    const ATTRIBUTES_SHIFT: u8 = 4;
+    const FG_INDEXED_FLAG: u8 = 0x01;
+    const BG_INDEXED_FLAG: u8 = 0x02;
+
     /// Creates a new packed cell from a glyph ID and foreground/background colors.
     #[inline]
     #[must_use]
     pub fn new(glyph_id: u16, fg: u32, bg: u32) -> Self {
-        let mut data = [0; 8];
+        let mut data = [0; 12];
 
         // pack glyph ID into the first two bytes
         let glyph_id = glyph_id.to_le_bytes();
@@ -1182,44 +2284,198 @@ impl CellDynamic {
 
     /// Swaps foreground and background colors.
     pub fn flip_colors(&mut self) {
-        // swap foreground and background colors
-        let fg = [self.data[2], self.data[3], self.data[4]];
-        self.data[2] = self.data[5]; // R
-        self.data[3] = self.data[6]; // G
-        self.data[4] = self.data[7]; // B
-        self.data[5] = fg[0]; // R
-        self.data[6] = fg[1]; // G
-        self.data[7] = fg[2]; // B
+        self.pack_colors(self.get_bg(), self.get_fg(), self.get_underline_color());
     }
 
     /// Sets the foreground color of the cell.
     pub fn fg_color(&mut self, fg: u32) {
-        let fg = fg.to_le_bytes();
-        self.data[2] = fg[2]; // R
-        self.data[3] = fg[1]; // G
-        self.data[4] = fg[0]; // B
+        self.fg(CellColor::Rgb(fg));
     }
 
     /// Sets the background color of the cell.
     pub fn bg_color(&mut self, bg: u32) {
-        let bg = bg.to_le_bytes();
-        self.data[5] = bg[2]; // R
-        self.data[6] = bg[1]; // G
-        self.data[7] = bg[0]; // B
+        self.bg(CellColor::Rgb(bg));
+    }
+
+    /// Sets the foreground color of the cell, which may reference the palette.
+    pub fn fg(&mut self, color: CellColor) {
+        self.pack_colors(color, self.get_bg(), self.get_underline_color());
+    }
+
+    /// Sets the background color of the cell, which may reference the palette.
+    pub fn bg(&mut self, color: CellColor) {
+        self.pack_colors(self.get_fg(), color, self.get_underline_color());
+    }
+
+    /// Returns the foreground color of the cell.
+    #[must_use]
+    pub fn get_fg(&self) -> CellColor {
+        self.colors().0
+    }
+
+    /// Returns the background color of the cell.
+    #[must_use]
+    pub fn get_bg(&self) -> CellColor {
+        self.colors().1
     }
 
     /// Returns foreground color as a packed RGB value.
+    ///
+    /// For palette colors, this is the raw palette slot; use
+    /// [`get_fg`](Self::get_fg) to tell them apart.
     #[must_use]
     pub fn get_fg_color(&self) -> u32 {
-        // unpack foreground color from data
-        ((self.data[2] as u32) << 16) | ((self.data[3] as u32) << 8) | (self.data[4] as u32)
+        Self::raw_color(self.get_fg())
     }
 
     /// Returns background color as a packed RGB value.
+    ///
+    /// For palette colors, this is the raw palette slot; use
+    /// [`get_bg`](Self::get_bg) to tell them apart.
     #[must_use]
     pub fn get_bg_color(&self) -> u32 {
-        // unpack background color from data
-        ((self.data[5] as u32) << 16) | ((self.data[6] as u32) << 8) | (self.data[7] as u32)
+        Self::raw_color(self.get_bg())
+    }
+
+    /// Sets the shape of the underline; only drawn if the underline bit is set.
+    pub fn underline_style(&mut self, style: UnderlineStyle) {
+        self.set_underline_code(style.shader_style(), self.color_layout());
+    }
+
+    /// Sets the underline color, or `None` to draw it in the foreground color.
+    pub fn underline_color(&mut self, color: Option<u32>) {
+        self.pack_colors(self.get_fg(), self.get_bg(), color);
+    }
+
+    /// Returns the shape of the underline.
+    #[must_use]
+    pub fn get_underline_style(&self) -> UnderlineStyle {
+        UnderlineStyle::from_shader_style(self.underline_code() % Self::UNDERLINE_STYLES)
+    }
+
+    /// Returns the underline color as a packed RGB value, or `None` if the
+    /// underline is drawn in the foreground color.
+    #[must_use]
+    pub fn get_underline_color(&self) -> Option<u32> {
+        (self.color_layout() != Self::FG_UNDERLINE_LAYOUT).then(|| self.rgb_at(8))
+    }
+
+    fn underline_code(&self) -> u8 {
+        self.data[11] & Self::UNDERLINE_CODE_MASK
+    }
+
+    fn set_underline_code(&mut self, style: u8, layout: u8) {
+        let code = style + Self::UNDERLINE_STYLES * layout;
+        self.data[11] = (self.data[11] & !Self::UNDERLINE_CODE_MASK) | code;
+    }
+
+    /// Returns the layout of the color bytes, as encoded in the underline code.
+    fn color_layout(&self) -> u8 {
+        self.underline_code() / Self::UNDERLINE_STYLES
+    }
+
+    /// Returns the foreground and background colors.
+    fn colors(&self) -> (CellColor, CellColor) {
+        let (flags, swapped) = match self.color_layout() {
+            Self::FG_UNDERLINE_LAYOUT => (self.data[8], false),
+            Self::RGB_UNDERLINE_LAYOUT => (0, false),
+            // the palette slot of the foreground takes the place of an RGB background
+            _ => (self.data[7], self.data[7] & Self::BG_INDEXED_FLAG == 0),
+        };
+
+        let color_at = |offset: usize, indexed: bool| {
+            if indexed {
+                CellColor::from_palette_index(u16::from_le_bytes([
+                    self.data[offset],
+                    self.data[offset + 1],
+                ]))
+            } else {
+                CellColor::Rgb(self.rgb_at(offset))
+            }
+        };
+
+        if swapped {
+            (color_at(5, true), color_at(2, false))
+        } else {
+            (
+                color_at(2, flags & Self::FG_INDEXED_FLAG != 0),
+                color_at(5, flags & Self::BG_INDEXED_FLAG != 0),
+            )
+        }
+    }
+
+    /// Packs the colors of the cell, choosing the layout of the color bytes
+    /// that fits them; see the [type-level documentation](Self).
+    fn pack_colors(&mut self, fg: CellColor, bg: CellColor, underline_color: Option<u32>) {
+        let flag =
+            |color: CellColor, flag: u8| if color.palette_index().is_some() { flag } else { 0 };
+        let flags = flag(fg, Self::FG_INDEXED_FLAG) | flag(bg, Self::BG_INDEXED_FLAG);
+
+        let layout = match underline_color {
+            None => Self::FG_UNDERLINE_LAYOUT,
+            Some(_) if flags == 0 => Self::RGB_UNDERLINE_LAYOUT,
+            Some(_) => Self::PALETTE_UNDERLINE_LAYOUT,
+        };
+
+        let swapped = layout == Self::PALETTE_UNDERLINE_LAYOUT && flags == Self::FG_INDEXED_FLAG;
+        let (first, second) = if swapped { (bg, fg) } else { (fg, bg) };
+        self.set_color_at(2, first);
+        self.set_color_at(5, second);
+
+        match underline_color {
+            Some(rgb) => self.set_rgb_at(8, rgb),
+            None => self.data[8..11].copy_from_slice(&[flags, 0, 0]),
+        }
+        if layout == Self::PALETTE_UNDERLINE_LAYOUT {
+            self.data[7] = flags;
+        }
+
+        let style = self.underline_code() % Self::UNDERLINE_STYLES;
+        self.set_underline_code(style, layout);
+    }
+
+    /// Stores a color in the three bytes starting at `offset`.
+    fn set_color_at(&mut self, offset: usize, color: CellColor) {
+        match color.palette_index() {
+            Some(slot) => {
+                let slot = slot.to_le_bytes();
+                self.data[offset..offset + 3].copy_from_slice(&[slot[0], slot[1], 0]);
+            },
+            None => self.set_rgb_at(offset, Self::raw_color(color)),
+        }
+    }
+
+    fn set_rgb_at(&mut self, offset: usize, rgb: u32) {
+        let rgb = rgb.to_le_bytes();
+        self.data[offset] = rgb[2]; // R
+        self.data[offset + 1] = rgb[1]; // G
+        self.data[offset + 2] = rgb[0]; // B
+    }
+
+    fn rgb_at(&self, offset: usize) -> u32 {
+        ((self.data[offset] as u32) << 16)
+            | ((self.data[offset + 1] as u32) << 8)
+            | (self.data[offset + 2] as u32)
+    }
+
+    /// Returns an RGB color, or the palette slot of a palette color.
+    fn raw_color(color: CellColor) -> u32 {
+        match color {
+            CellColor::Rgb(rgb) => rgb,
+            color => color.palette_index().unwrap_or_default() as u32,
+        }
+    }
+
+    /// Replaces the rendering attributes of the cell.
+    pub fn attributes(&mut self, attributes: CellAttributes) {
+        let flags = self.data[11] & !(0xF << Self::ATTRIBUTES_SHIFT);
+        self.data[11] = flags | (attributes.bits() << Self::ATTRIBUTES_SHIFT);
+    }
+
+    /// Returns the rendering attributes of the cell.
+    #[must_use]
+    pub fn get_attributes(&self) -> CellAttributes {
+        CellAttributes::from_bits_truncate(self.data[11] >> Self::ATTRIBUTES_SHIFT)
     }
 
     /// Returns the style bits for this cell, excluding id and emoji bits.
@@ -1229,11 +2485,11 @@ impl CellDynamic {
     }
 
     #[inline]
-    fn glyph_id(self) -> u16 {
+    pub(crate) fn glyph_id(self) -> u16 {
         u16::from_le_bytes([self.data[0], self.data[1]])
     }
 
-    fn set_glyph_id(&mut self, glyph_id: u16) {
+    pub(crate) fn set_glyph_id(&mut self, glyph_id: u16) {
         let bytes = glyph_id.to_le_bytes();
         self.data[0] = bytes[0];
         self.data[1] = bytes[1];
@@ -1243,9 +2499,11 @@ impl CellDynamic {
 #[derive(Clone, Copy)]
 #[repr(C, align(16))] // std140 layout requires proper alignment
 struct CellVertexUbo {
-    pub projection: [f32; 16], // mat4
-    pub cell_size: [f32; 2],   // vec2 - screen cell size
-    pub _padding: [f32; 2],
+    pub projection: [f32; 16],   // mat4
+    pub cell_size: [f32; 2],     // vec2 - screen cell size
+    pub scroll_offset: [f32; 2], // vec2 - sub-cell scroll offset in cells
+    pub minimum_contrast: f32,   // minimum WCAG contrast ratio; 1.0 = disabled
+    pub grid_cols: u32,          // number of columns of the terminal grid
 }
 
 #[derive(Clone, Copy)]
@@ -1258,18 +2516,46 @@ struct CellFragmentUbo {
     pub strikethrough_thickness: f32, // strikethrough thickness as fraction of cell height
     pub emoji_bit: u32,               // static atlas: 12, dynamic atlas: 15
     pub bg_alpha: f32,                // background cell opacity (0.0 = transparent, 1.0 = opaque)
+    pub cursor_color: [f32; 3],       // vec3 - cursor color
+    pub cursor_shape: u32,            // 0 = hidden, 1 = block, 2 = bar, 3 = underline, 4 = hollow
+    pub cursor_pos: [u32; 2],         // uvec2 - cursor position in grid coordinates
+    pub cursor_blink_ms: u32,         // duration of each blink phase; 0 = steady
+    pub cursor_epoch_ms: u32,         // clock value at which the blink cycle started
+    pub time_ms: u32,                 // animation clock in milliseconds
+    pub text_blink_ms: u32,           // duration of each blink phase of text; 0 = steady
+    pub selection_start: [u32; 2],    // uvec2 - first selected cell
+    pub selection_bg: [f32; 3],       // vec3 - selection highlight color
+    pub selection_alpha: f32,         // opacity of the selection highlight
+    pub selection_fg: [f32; 3],       // vec3 - text color of selected cells
+    pub selection_colors: u32,        // 0 = keep text color, 1 = use `selection_fg`, 2 = invert
+    pub selection_end: [u32; 2],      // uvec2 - last selected cell
+    pub selection_mode: u32,          // 0 = none, 1 = linear, 2 = block
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, align(16))] // std140 layout requires proper alignment
+struct PaletteUbo {
+    pub colors: [[f32; 4]; Palette::SLOTS], // vec4[258] - 256 indexed colors, default fg, default bg
 }
 
 impl CellVertexUbo {
     pub const BINDING_POINT: u32 = 0;
 
-    fn new(canvas_size: (i32, i32), cell_size: CellSize) -> Self {
+    fn new(
+        canvas_size: (i32, i32),
+        cell_size: CellSize,
+        scroll_offset: (f32, f32),
+        minimum_contrast: f32,
+        grid_cols: u16,
+    ) -> Self {
         let projection =
             Mat4::orthographic_from_size(canvas_size.0 as f32, canvas_size.1 as f32).data;
         Self {
             projection,
             cell_size: [cell_size.width as f32, cell_size.height as f32],
-            _padding: [0.0; 2], // padding to ensure proper alignment
+            scroll_offset: [scroll_offset.0, scroll_offset.1],
+            minimum_contrast,
+            grid_cols: grid_cols as u32,
         }
     }
 }
@@ -1294,14 +2580,91 @@ impl CellFragmentUbo {
             strikethrough_thickness: strikethrough.thickness(),
             emoji_bit: atlas.emoji_bit(),
             bg_alpha,
+            cursor_color: [0.0; 3],
+            cursor_shape: 0,
+            cursor_pos: [0; 2],
+            cursor_blink_ms: 0,
+            cursor_epoch_ms: 0,
+            time_ms: 0,
+            text_blink_ms: 0,
+            selection_start: [0; 2],
+            selection_bg: [0.0; 3],
+            selection_alpha: 0.0,
+            selection_fg: [0.0; 3],
+            selection_colors: 0,
+            selection_end: [0; 2],
+            selection_mode: 0,
         }
     }
+
+    /// Sets the cursor uniforms; `viewport_offset` shifts the cursor down along
+    /// with the live screen while the viewport is scrolled back.
+    fn with_cursor(mut self, cursor: &Cursor, epoch_ms: u32, viewport_offset: usize) -> Self {
+        let row = cursor.position.row as u32 + viewport_offset as u32;
+
+        self.cursor_color = rgb_to_vec3(cursor.color);
+        self.cursor_shape = cursor.shader_shape();
+        self.cursor_pos = [cursor.position.col as u32, row];
+        self.cursor_blink_ms = cursor.blink_interval_ms;
+        self.cursor_epoch_ms = epoch_ms;
+        self
+    }
+
+    fn with_time(mut self, time_ms: u32, text_blink_ms: u32) -> Self {
+        self.time_ms = time_ms;
+        self.text_blink_ms = text_blink_ms;
+        self
+    }
+
+    /// Sets the selection uniforms; `None` disables the highlight.
+    fn with_selection(mut self, style: &SelectionStyle, selection: Option<CellSpan>) -> Self {
+        let (mode, start, end) = match selection {
+            None => (0, (0, 0), (0, 0)),
+            Some(CellSpan::Linear { start, end }) => (1, start, end),
+            Some(CellSpan::Block { start, end }) => (2, start, end),
+        };
+
+        self.selection_mode = mode;
+        self.selection_start = [start.0 as u32, start.1 as u32];
+        self.selection_end = [end.0 as u32, end.1 as u32];
+        match *style {
+            // the inverted background keeps the opacity of the cell background
+            SelectionStyle::Inverted => {
+                self.selection_bg = [0.0; 3];
+                self.selection_alpha = 0.0;
+                self.selection_fg = [0.0; 3];
+                self.selection_colors = 2;
+            },
+            SelectionStyle::Solid { background, foreground, alpha } => {
+                self.selection_bg = rgb_to_vec3(background);
+                self.selection_alpha = alpha;
+                self.selection_fg = rgb_to_vec3(foreground.unwrap_or(0));
+                self.selection_colors = foreground.is_some() as u32;
+            },
+        }
+        self
+    }
 }
 
-fn create_terminal_cell_data(cols: i32, rows: i32, fill_glyph: u16) -> Vec<CellDynamic> {
-    (0..cols * rows)
-        .map(|_i| CellDynamic::new(fill_glyph, 0x00ff_ffff, 0x0000_0000))
-        .collect()
+impl PaletteUbo {
+    pub const BINDING_POINT: u32 = 2;
+
+    fn new(palette: &Palette) -> Self {
+        Self {
+            colors: std::array::from_fn(|slot| {
+                let [r, g, b] = rgb_to_vec3(palette.slot(slot));
+                [r, g, b, 1.0]
+            }),
+        }
+    }
+}
+
+fn rgb_to_vec3(rgb: u32) -> [f32; 3] {
+    [
+        ((rgb >> 16) & 0xFF) as f32 / 255.0,
+        ((rgb >> 8) & 0xFF) as f32 / 255.0,
+        (rgb & 0xFF) as f32 / 255.0,
+    ]
 }
 
 mod attrib {
@@ -1310,4 +2673,5 @@ mod attrib {
 
     pub const GRID_XY: u32 = 2;
     pub const PACKED_DEPTH_FG_BG: u32 = 3;
+    pub const LINE_ATTRIBUTE: u32 = 4;
 }
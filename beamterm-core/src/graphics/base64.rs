@@ -0,0 +1,60 @@
+use crate::error::Error;
+
+/// Decodes standard base64, with or without padding.
+pub(super) fn decode(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let input = input
+        .strip_suffix(b"==")
+        .or_else(|| input.strip_suffix(b"="))
+        .unwrap_or(input);
+
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+
+    for &byte in input {
+        acc = (acc << 6) | sextet(byte)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((acc >> bits) as u8);
+        }
+    }
+
+    // a single trailing sextet can't encode a whole byte
+    if input.len() % 4 == 1 {
+        return Err(Error::invalid_image_data("truncated base64 payload"));
+    }
+
+    Ok(output)
+}
+
+fn sextet(byte: u8) -> Result<u8, Error> {
+    match byte {
+        b'A'..=b'Z' => Ok(byte - b'A'),
+        b'a'..=b'z' => Ok(byte - b'a' + 26),
+        b'0'..=b'9' => Ok(byte - b'0' + 52),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(Error::invalid_image_data(format_args!(
+            "invalid base64 byte 0x{byte:02x}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_padded_and_unpadded_input() {
+        assert_eq!(decode(b"").unwrap(), b"");
+        assert_eq!(decode(b"Zg==").unwrap(), b"f");
+        assert_eq!(decode(b"Zm8=").unwrap(), b"fo");
+        assert_eq!(decode(b"Zm9v").unwrap(), b"foo");
+        assert_eq!(decode(b"Zm9vYg").unwrap(), b"foob");
+        assert_eq!(decode(b"/+8=").unwrap(), [0xFF, 0xEF]);
+
+        assert!(decode(b"Zm9v!").is_err());
+        assert!(decode(b"Zm9vY").is_err());
+    }
+}
@@ -0,0 +1,539 @@
+use std::io::Cursor;
+
+use beamterm_data::CellSize;
+use miniz_oxide::inflate::TINFLStatus;
+
+use super::{GraphicsImage, base64, check_dimensions};
+use crate::{ImageId, ImagePlacement, error::Error};
+
+/// Maximum size of zlib-compressed PNG data after inflating it.
+const MAX_INFLATED_PNG_LEN: usize = 32 * 1024 * 1024;
+
+/// Action of a kitty graphics command, from the `a` key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KittyAction {
+    /// Transmit image data without displaying it (`a=t`, the default).
+    Transmit,
+    /// Transmit image data and display it at the cursor (`a=T`).
+    TransmitAndDisplay,
+    /// Display a previously transmitted image (`a=p`).
+    Put,
+    /// Delete images or placements (`a=d`).
+    Delete,
+    /// Check whether image data can be decoded, without storing it (`a=q`).
+    Query,
+}
+
+/// A decoded kitty graphics command.
+///
+/// Keys not listed here, such as source rectangles and pixel offsets, are
+/// ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KittyCommand {
+    /// The requested action.
+    pub action: KittyAction,
+    /// Response suppression level from the `q` key.
+    pub quiet: u8,
+    /// Client-assigned image id (`i`).
+    pub image_id: Option<u32>,
+    /// Client-assigned image number (`I`).
+    pub image_number: Option<u32>,
+    /// Client-assigned placement id (`p`).
+    pub placement_id: Option<u32>,
+    /// Number of columns to display the image over (`c`).
+    pub cols: Option<u16>,
+    /// Number of rows to display the image over (`r`).
+    pub rows: Option<u16>,
+    /// Drawing order (`z`); negative values are drawn below the text.
+    pub z_index: i32,
+    /// What to delete (`d`), for [`KittyAction::Delete`].
+    pub delete: Option<char>,
+    /// The transmitted image, decoded to RGBA.
+    pub image: Option<GraphicsImage>,
+}
+
+impl KittyCommand {
+    /// Places the uploaded `image` at `col` and `row`, covering the requested
+    /// columns and rows, or the [`cell_extent`](GraphicsImage::cell_extent)
+    /// of `source` where not given.
+    #[must_use]
+    pub fn placement(
+        &self,
+        image: ImageId,
+        source: &GraphicsImage,
+        col: u16,
+        row: u16,
+        cell_size: CellSize,
+    ) -> ImagePlacement {
+        let (cols, rows) = source.cell_extent(cell_size);
+        ImagePlacement::new(
+            image,
+            col,
+            row,
+            self.cols.unwrap_or(cols),
+            self.rows.unwrap_or(rows),
+        )
+        .with_z_index(self.z_index)
+    }
+}
+
+/// Decodes kitty graphics protocol commands, reassembling chunked transmissions.
+///
+/// Only direct transmission (`t=d`) is supported; file and shared memory
+/// transmission are left to the terminal emulator.
+///
+/// # Example
+/// ```
+/// use beamterm_core::graphics::{KittyAction, KittyDecoder};
+///
+/// let mut decoder = KittyDecoder::new();
+///
+/// // a 1x1 RGB image sent in two chunks
+/// assert_eq!(decoder.feed(b"a=T,f=24,s=1,v=1,i=7,m=1;/w").unwrap(), None);
+/// let command = decoder.feed(b"m=0;AA").unwrap().unwrap();
+///
+/// assert_eq!(command.action, KittyAction::TransmitAndDisplay);
+/// assert_eq!(command.image_id, Some(7));
+/// assert_eq!(command.image.unwrap().pixels, [0xFF, 0, 0, 0xFF]);
+/// ```
+#[derive(Debug, Default)]
+pub struct KittyDecoder {
+    /// Transmission awaiting further chunks.
+    pending: Option<Pending>,
+}
+
+#[derive(Debug)]
+struct Pending {
+    command: KittyCommand,
+    transmission: Transmission,
+    /// Base64 data of all chunks received so far.
+    data: Vec<u8>,
+}
+
+/// Keys describing how the image data is encoded.
+#[derive(Debug, Clone, Copy)]
+struct Transmission {
+    format: u32,
+    medium: u8,
+    compressed: bool,
+    width: u32,
+    height: u32,
+}
+
+impl KittyDecoder {
+    /// Upper bound on the base64 data of a single transmission, in bytes.
+    const MAX_PAYLOAD: usize = 64 * 1024 * 1024;
+
+    /// Creates a decoder with no pending transmission.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the payload of one graphics escape: everything between the
+    /// `ESC _ G` introducer and the `ESC \` terminator.
+    ///
+    /// Returns the decoded command once complete, or `None` while a chunked
+    /// transmission (`m=1`) awaits further chunks.
+    ///
+    /// # Errors
+    /// Returns an error for malformed keys, unsupported formats or media, and
+    /// image data that fails to decode. A failed transmission is discarded.
+    pub fn feed(&mut self, payload: &[u8]) -> Result<Option<KittyCommand>, Error> {
+        let result = self.feed_chunk(payload);
+        if result.is_err() {
+            self.pending = None;
+        }
+
+        result
+    }
+
+    /// Returns `true` if a chunked transmission awaits further chunks.
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    fn feed_chunk(&mut self, payload: &[u8]) -> Result<Option<KittyCommand>, Error> {
+        let (control, data) = match payload.iter().position(|&b| b == b';') {
+            Some(idx) => (&payload[..idx], &payload[idx + 1..]),
+            None => (payload, &[][..]),
+        };
+
+        let mut more = false;
+        let mut pending = match self.pending.take() {
+            // continuation chunks only carry the `m` and `q` keys
+            Some(mut pending) => {
+                for (key, value) in parse_keys(control)? {
+                    match key {
+                        b'm' => more = number(key, value)? == 1,
+                        b'q' => pending.command.quiet = number(key, value)? as u8,
+                        _ => {},
+                    }
+                }
+                pending
+            },
+            None => {
+                let (command, transmission) = parse_command(control, &mut more)?;
+                Pending { command, transmission, data: Vec::new() }
+            },
+        };
+
+        if pending.data.len() + data.len() > Self::MAX_PAYLOAD {
+            return Err(Error::invalid_image_data(
+                "kitty graphics payload exceeds the maximum size",
+            ));
+        }
+        pending.data.extend_from_slice(data);
+
+        if more {
+            self.pending = Some(pending);
+            return Ok(None);
+        }
+
+        let Pending { mut command, transmission, data } = pending;
+        let transmits = matches!(
+            command.action,
+            KittyAction::Transmit | KittyAction::TransmitAndDisplay | KittyAction::Query
+        );
+        if transmits && !data.is_empty() {
+            command.image = Some(transmission.decode(&data)?);
+        }
+
+        Ok(Some(command))
+    }
+}
+
+impl Transmission {
+    fn decode(self, data: &[u8]) -> Result<GraphicsImage, Error> {
+        if self.medium != b'd' {
+            return Err(Error::invalid_image_data(format_args!(
+                "unsupported kitty transmission medium '{}'",
+                self.medium as char
+            )));
+        }
+
+        let mut data = base64::decode(data)?;
+        if self.compressed {
+            data = inflate(&data, self.inflated_len_limit()?)?;
+        }
+
+        match self.format {
+            24 => GraphicsImage::from_channels(self.width, self.height, 3, &data),
+            32 => GraphicsImage::from_channels(self.width, self.height, 4, &data),
+            100 => decode_png(&data),
+            format => Err(Error::invalid_image_data(format_args!(
+                "unsupported kitty image format {format}"
+            ))),
+        }
+    }
+
+    /// Returns the maximum size of the inflated image data: the exact size
+    /// of raw pixels, as declared by `s` and `v`.
+    fn inflated_len_limit(&self) -> Result<usize, Error> {
+        let channels = match self.format {
+            24 => 3,
+            32 => 4,
+            _ => return Ok(MAX_INFLATED_PNG_LEN),
+        };
+
+        check_dimensions(self.width, self.height)?;
+        Ok(self.width as usize * self.height as usize * channels)
+    }
+}
+
+/// Inflates zlib-compressed image data, rejecting data that inflates to more
+/// than `limit` bytes.
+fn inflate(data: &[u8], limit: usize) -> Result<Vec<u8>, Error> {
+    miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, limit).map_err(|err| {
+        match err.status {
+            TINFLStatus::HasMoreOutput => Error::invalid_image_data(format_args!(
+                "zlib data inflates to more than {limit} bytes"
+            )),
+            _ => Error::invalid_image_data(format_args!("zlib decompression failed: {err}")),
+        }
+    })
+}
+
+/// Parses the control keys of the first chunk of a command.
+fn parse_command(control: &[u8], more: &mut bool) -> Result<(KittyCommand, Transmission), Error> {
+    let mut command = KittyCommand {
+        action: KittyAction::Transmit,
+        quiet: 0,
+        image_id: None,
+        image_number: None,
+        placement_id: None,
+        cols: None,
+        rows: None,
+        z_index: 0,
+        delete: None,
+        image: None,
+    };
+    let mut transmission = Transmission {
+        format: 32,
+        medium: b'd',
+        compressed: false,
+        width: 0,
+        height: 0,
+    };
+
+    for (key, value) in parse_keys(control)? {
+        match key {
+            b'a' => {
+                command.action = match value {
+                    b"t" => KittyAction::Transmit,
+                    b"T" => KittyAction::TransmitAndDisplay,
+                    b"p" => KittyAction::Put,
+                    b"d" => KittyAction::Delete,
+                    b"q" => KittyAction::Query,
+                    _ => return Err(invalid_value(key, value)),
+                };
+            },
+            b'q' => command.quiet = number(key, value)? as u8,
+            b'i' => command.image_id = Some(number(key, value)?),
+            b'I' => command.image_number = Some(number(key, value)?),
+            b'p' => command.placement_id = Some(number(key, value)?),
+            b'c' => command.cols = Some(number(key, value)?.min(u16::MAX as u32) as u16),
+            b'r' => command.rows = Some(number(key, value)?.min(u16::MAX as u32) as u16),
+            b'z' => command.z_index = signed_number(key, value)?,
+            b'd' => command.delete = Some(single_char(key, value)? as char),
+            b'f' => transmission.format = number(key, value)?,
+            b't' => transmission.medium = single_char(key, value)?,
+            b'o' => transmission.compressed = single_char(key, value)? == b'z',
+            b's' => transmission.width = number(key, value)?,
+            b'v' => transmission.height = number(key, value)?,
+            b'm' => *more = number(key, value)? == 1,
+            _ => {},
+        }
+    }
+
+    Ok((command, transmission))
+}
+
+/// Splits `k=v,k=v` control data into single-byte keys and their values.
+fn parse_keys(control: &[u8]) -> Result<Vec<(u8, &[u8])>, Error> {
+    control
+        .split(|&b| b == b',')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair {
+            [key, b'=', value @ ..] => Ok((*key, value)),
+            _ => Err(Error::invalid_image_data(format_args!(
+                "malformed kitty graphics key '{}'",
+                String::from_utf8_lossy(pair)
+            ))),
+        })
+        .collect()
+}
+
+fn number(key: u8, value: &[u8]) -> Result<u32, Error> {
+    std::str::from_utf8(value)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| invalid_value(key, value))
+}
+
+fn signed_number(key: u8, value: &[u8]) -> Result<i32, Error> {
+    std::str::from_utf8(value)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| invalid_value(key, value))
+}
+
+fn single_char(key: u8, value: &[u8]) -> Result<u8, Error> {
+    match value {
+        [c] => Ok(*c),
+        _ => Err(invalid_value(key, value)),
+    }
+}
+
+fn invalid_value(key: u8, value: &[u8]) -> Error {
+    Error::invalid_image_data(format_args!(
+        "invalid value '{}' for kitty graphics key '{}'",
+        String::from_utf8_lossy(value),
+        key as char
+    ))
+}
+
+/// Decodes a PNG image to RGBA, expanding palette, grayscale and 16-bit images.
+fn decode_png(data: &[u8]) -> Result<GraphicsImage, Error> {
+    let png_error = |err: png::DecodingError| {
+        Error::invalid_image_data(format_args!("PNG decoding failed: {err}"))
+    };
+
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(png_error)?;
+
+    let (width, height) = reader.info().size();
+    check_dimensions(width, height)?;
+
+    let buffer_size = reader
+        .output_buffer_size()
+        .ok_or_else(|| Error::invalid_image_data("PNG image is too large"))?;
+    let mut buf = vec![0; buffer_size];
+    let info = reader.next_frame(&mut buf).map_err(png_error)?;
+    buf.truncate(info.buffer_size());
+
+    let channels = match info.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        png::ColorType::Indexed => {
+            return Err(Error::invalid_image_data("unexpanded PNG palette"));
+        },
+    };
+
+    GraphicsImage::from_channels(info.width, info.height, channels, &buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(data: &[u8]) -> String {
+        const ALPHABET: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let mut out = String::new();
+        for chunk in data.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+            for i in 0..=chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3F] as char);
+            }
+            (chunk.len()..3).for_each(|_| out.push('='));
+        }
+        out
+    }
+
+    #[test]
+    fn decodes_chunked_rgba_transmission() {
+        let pixels: Vec<u8> = (0..16).collect();
+        let encoded = encode(&pixels);
+        let (first, second) = encoded.split_at(8);
+
+        let mut decoder = KittyDecoder::new();
+        let head = format!("a=T,f=32,s=2,v=2,i=1,p=3,c=4,r=2,z=-1,m=1;{first}");
+        assert_eq!(decoder.feed(head.as_bytes()).unwrap(), None);
+        assert!(decoder.is_pending());
+
+        let command = decoder
+            .feed(format!("m=0;{second}").as_bytes())
+            .unwrap()
+            .unwrap();
+        assert!(!decoder.is_pending());
+
+        assert_eq!(command.placement_id, Some(3));
+        assert_eq!(
+            (command.cols, command.rows, command.z_index),
+            (Some(4), Some(2), -1)
+        );
+        let image = command.image.clone().unwrap();
+        assert_eq!((image.width, image.height), (2, 2));
+        assert_eq!(image.pixels, pixels);
+
+        let placement = command.placement(ImageId(9), &image, 1, 2, CellSize::new(8, 16));
+        assert_eq!(
+            placement,
+            ImagePlacement::new(ImageId(9), 1, 2, 4, 2).with_z_index(-1)
+        );
+    }
+
+    #[test]
+    fn decodes_compressed_rgb() {
+        let rgb = [0x10, 0x20, 0x30, 0x40, 0x50, 0x60];
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&rgb, 6);
+        let payload = format!("f=24,o=z,s=2,v=1;{}", encode(&compressed));
+
+        let command = KittyDecoder::new()
+            .feed(payload.as_bytes())
+            .unwrap()
+            .unwrap();
+        assert_eq!(command.action, KittyAction::Transmit);
+        assert_eq!(command.image.unwrap().pixels, [
+            0x10, 0x20, 0x30, 0xFF, 0x40, 0x50, 0x60, 0xFF
+        ]);
+    }
+
+    #[test]
+    fn compressed_data_must_inflate_to_the_declared_size() {
+        let bomb = miniz_oxide::deflate::compress_to_vec_zlib(&vec![0; 1024 * 1024], 6);
+        let payload = format!("f=32,o=z,s=2,v=2;{}", encode(&bomb));
+        assert!(
+            KittyDecoder::new()
+                .feed(payload.as_bytes())
+                .is_err()
+        );
+
+        let short = miniz_oxide::deflate::compress_to_vec_zlib(&[0; 12], 6);
+        let payload = format!("f=32,o=z,s=2,v=2;{}", encode(&short));
+        assert!(
+            KittyDecoder::new()
+                .feed(payload.as_bytes())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn decodes_png() {
+        let mut png_data = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut png_data, 3, 1);
+            encoder.set_color(png::ColorType::GrayscaleAlpha);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer
+                .write_image_data(&[0, 0xFF, 0x80, 0x80, 0xFF, 0])
+                .unwrap();
+        }
+
+        let payload = format!("a=t,f=100,i=2;{}", encode(&png_data));
+        let image = KittyDecoder::new()
+            .feed(payload.as_bytes())
+            .unwrap()
+            .unwrap()
+            .image
+            .unwrap();
+
+        assert_eq!((image.width, image.height), (3, 1));
+        assert_eq!(image.pixels, [
+            0, 0, 0, 0xFF, 0x80, 0x80, 0x80, 0x80, 0xFF, 0xFF, 0xFF, 0
+        ]);
+        assert_eq!(image.cell_extent(CellSize::new(2, 16)), (2, 1));
+    }
+
+    #[test]
+    fn commands_without_data() {
+        let mut decoder = KittyDecoder::new();
+
+        let put = decoder.feed(b"a=p,i=5,p=2,z=3").unwrap().unwrap();
+        assert_eq!(put.action, KittyAction::Put);
+        assert_eq!(
+            (put.image_id, put.placement_id, put.z_index),
+            (Some(5), Some(2), 3)
+        );
+        assert_eq!(put.image, None);
+
+        let delete = decoder.feed(b"a=d,d=I,I=4,q=2").unwrap().unwrap();
+        assert_eq!(delete.action, KittyAction::Delete);
+        assert_eq!(
+            (delete.delete, delete.image_number, delete.quiet),
+            (Some('I'), Some(4), 2)
+        );
+    }
+
+    #[test]
+    fn errors_discard_pending_transmission() {
+        let mut decoder = KittyDecoder::new();
+        assert_eq!(decoder.feed(b"f=32,s=1,v=1,m=1;AAAA").unwrap(), None);
+        assert!(decoder.feed(b"m=0;AAAA").is_err()); // 4 bytes expected, got 6
+        assert!(!decoder.is_pending());
+
+        assert!(decoder.feed(b"a=x").is_err());
+        assert!(decoder.feed(b"t=f,s=1,v=1;AAAAAA==").is_err());
+        assert!(decoder.feed(b"f=24,s=2,v=2;AAAA").is_err());
+    }
+}
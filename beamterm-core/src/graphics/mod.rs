@@ -0,0 +1,105 @@
+//! Decoders for the sixel and kitty terminal graphics protocols.
+//!
+//! The decoders turn escape sequence payloads into RGBA [`GraphicsImage`]s,
+//! ready for [`TerminalGrid::upload_image`](crate::TerminalGrid::upload_image)
+//! and placement over the grid cells. Parsing the escape sequences themselves
+//! is left to the terminal emulator.
+
+mod base64;
+mod kitty;
+mod sixel;
+
+use beamterm_data::CellSize;
+pub use kitty::{KittyAction, KittyCommand, KittyDecoder};
+pub use sixel::decode_sixel;
+
+use crate::{ImageId, ImagePlacement, error::Error};
+
+/// Upper bound on the width and height of decoded images, in pixels.
+pub const MAX_IMAGE_DIMENSION: u32 = 10_000;
+
+/// An image decoded from a graphics protocol payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphicsImage {
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+    /// RGBA pixels in row-major order, with non-premultiplied alpha.
+    pub pixels: Vec<u8>,
+}
+
+impl GraphicsImage {
+    /// Returns the number of columns and rows covered by the image when drawn
+    /// at its native size; partially covered cells are included.
+    #[must_use]
+    pub fn cell_extent(&self, cell_size: CellSize) -> (u16, u16) {
+        let cells = |px: u32, cell_px: i32| {
+            px.div_ceil(cell_px.max(1) as u32)
+                .min(u16::MAX as u32) as u16
+        };
+
+        (
+            cells(self.width, cell_size.width),
+            cells(self.height, cell_size.height),
+        )
+    }
+
+    /// Places the uploaded `image` at `col` and `row`, covering the cells of
+    /// its [`cell_extent`](Self::cell_extent).
+    #[must_use]
+    pub fn placement(
+        &self,
+        image: ImageId,
+        col: u16,
+        row: u16,
+        cell_size: CellSize,
+    ) -> ImagePlacement {
+        let (cols, rows) = self.cell_extent(cell_size);
+        ImagePlacement::new(image, col, row, cols, rows)
+    }
+
+    /// Creates an image from pixels with `channels` bytes per pixel, expanding
+    /// grayscale and RGB data to RGBA.
+    fn from_channels(width: u32, height: u32, channels: usize, data: &[u8]) -> Result<Self, Error> {
+        check_dimensions(width, height)?;
+
+        let expected_len = width as usize * height as usize * channels;
+        if data.len() != expected_len {
+            return Err(Error::invalid_image_data(format_args!(
+                "expected {expected_len} bytes for a {width}x{height} image, got {}",
+                data.len()
+            )));
+        }
+
+        let pixels = match channels {
+            4 => data.to_vec(),
+            3 => data
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 0xFF])
+                .collect(),
+            2 => data
+                .chunks_exact(2)
+                .flat_map(|la| [la[0], la[0], la[0], la[1]])
+                .collect(),
+            1 => data
+                .iter()
+                .flat_map(|&l| [l, l, l, 0xFF])
+                .collect(),
+            _ => unreachable!("unsupported channel count: {channels}"),
+        };
+
+        Ok(Self { width, height, pixels })
+    }
+}
+
+/// Rejects empty images and images larger than [`MAX_IMAGE_DIMENSION`].
+fn check_dimensions(width: u32, height: u32) -> Result<(), Error> {
+    if width == 0 || height == 0 || width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+        return Err(Error::invalid_image_data(format_args!(
+            "unsupported image size {width}x{height}"
+        )));
+    }
+
+    Ok(())
+}
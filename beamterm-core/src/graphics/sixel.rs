@@ -0,0 +1,313 @@
+use super::{GraphicsImage, MAX_IMAGE_DIMENSION, check_dimensions};
+use crate::error::Error;
+
+/// Number of color registers.
+const REGISTERS: usize = 256;
+
+/// Default colors of the first 16 registers, as RGB percentages (VT340).
+const VT340_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+/// Decodes a sixel image from the body of a DCS sequence.
+///
+/// `payload` holds everything between the `ESC P` introducer and the string
+/// terminator, i.e. the optional `P1;P2;P3` parameters, the final `q` and the
+/// sixel data. Pixels not covered by any sixel are transparent if `P2` is 1,
+/// and filled with color register 0 otherwise.
+///
+/// # Example
+/// ```
+/// use beamterm_core::graphics::decode_sixel;
+///
+/// // a 2x6 red image: define register 1 as red, then two full sixels
+/// let image = decode_sixel(b"0;1q#1;2;100;0;0#1~~").unwrap();
+/// assert_eq!((image.width, image.height), (2, 6));
+/// assert_eq!(image.pixels[..4], [0xFF, 0, 0, 0xFF]);
+/// ```
+///
+/// # Errors
+/// Returns an error if the payload lacks the `q` introducer, or if the image
+/// is empty or exceeds [`MAX_IMAGE_DIMENSION`].
+pub fn decode_sixel(payload: &[u8]) -> Result<GraphicsImage, Error> {
+    let q = payload
+        .iter()
+        .position(|&b| b == b'q')
+        .ok_or_else(|| Error::invalid_image_data("missing sixel introducer 'q'"))?;
+
+    let (params, _) = parse_params(&payload[..q]);
+    let transparent = params.get(1) == Some(&1);
+
+    let mut decoder = SixelDecoder::new();
+    decoder.decode(&payload[q + 1..])?;
+    decoder.finish(transparent)
+}
+
+/// Sixel drawing state; pixels are kept as rows that grow as they are drawn.
+struct SixelDecoder {
+    palette: [[u8; 4]; REGISTERS],
+    color: [u8; 4],
+    rows: Vec<Vec<[u8; 4]>>,
+    /// Position of the next sixel; `y` is the top pixel row of the band.
+    x: usize,
+    y: usize,
+    /// Extent of the image, including blank sixels.
+    width: usize,
+    /// Size from the raster attributes, if given.
+    raster_size: (usize, usize),
+}
+
+impl SixelDecoder {
+    fn new() -> Self {
+        let mut palette = [[0, 0, 0, 0xFF]; REGISTERS];
+        for (color, rgb) in palette.iter_mut().zip(VT340_PALETTE) {
+            *color = rgb_from_percent(rgb.map(u32::from));
+        }
+
+        Self {
+            palette,
+            color: palette[0],
+            rows: Vec::new(),
+            x: 0,
+            y: 0,
+            width: 0,
+            raster_size: (0, 0),
+        }
+    }
+
+    fn decode(&mut self, data: &[u8]) -> Result<(), Error> {
+        let mut pos = 0;
+        while pos < data.len() {
+            let byte = data[pos];
+            pos += 1;
+
+            match byte {
+                b'?'..=b'~' => self.draw(byte - b'?', 1)?,
+                b'!' => {
+                    let (params, len) = parse_params(&data[pos..]);
+                    pos += len;
+                    if let Some(&sixel @ b'?'..=b'~') = data.get(pos) {
+                        pos += 1;
+                        let count = params.first().copied().unwrap_or(1).max(1);
+                        self.draw(sixel - b'?', count as usize)?;
+                    }
+                },
+                b'#' => {
+                    let (params, len) = parse_params(&data[pos..]);
+                    pos += len;
+                    self.select_color(&params);
+                },
+                b'"' => {
+                    let (params, len) = parse_params(&data[pos..]);
+                    pos += len;
+                    if let [_, _, width, height, ..] = params[..] {
+                        self.raster_size = (width as usize, height as usize);
+                    }
+                },
+                b'$' => self.x = 0,
+                b'-' => {
+                    self.x = 0;
+                    self.y += 6;
+                },
+                // ignore whitespace and unknown bytes
+                _ => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles `#Pc` (select) and `#Pc;Pu;Px;Py;Pz` (define and select).
+    fn select_color(&mut self, params: &[u32]) {
+        let Some(&register) = params.first() else {
+            return;
+        };
+        let register = register as usize % REGISTERS;
+
+        if let [_, space, a, b, c, ..] = params[..] {
+            self.palette[register] = match space {
+                1 => rgb_from_hls(a, b, c),
+                _ => rgb_from_percent([a, b, c]),
+            };
+        }
+
+        self.color = self.palette[register];
+    }
+
+    /// Draws the sixel `bits` `count` times at the current position.
+    fn draw(&mut self, bits: u8, count: usize) -> Result<(), Error> {
+        let max = MAX_IMAGE_DIMENSION as usize;
+        if count > max.saturating_sub(self.x) || self.y + 6 > max {
+            return Err(Error::invalid_image_data(
+                "sixel image exceeds the maximum dimensions",
+            ));
+        }
+        let end = self.x + count;
+
+        for bit in (0..6).filter(|bit| bits & (1 << bit) != 0) {
+            let y = self.y + bit;
+            if self.rows.len() <= y {
+                self.rows.resize_with(y + 1, Vec::new);
+            }
+
+            let row = &mut self.rows[y];
+            if row.len() < end {
+                row.resize(end, [0; 4]);
+            }
+            row[self.x..end].fill(self.color);
+        }
+
+        self.x = end;
+        self.width = self.width.max(end);
+        Ok(())
+    }
+
+    fn finish(self, transparent: bool) -> Result<GraphicsImage, Error> {
+        let width = self.width.max(self.raster_size.0);
+        let height = self.rows.len().max(self.raster_size.1);
+        check_dimensions(width as u32, height as u32)?;
+
+        let background = if transparent { [0; 4] } else { self.palette[0] };
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            let row = self.rows.get(y).map_or(&[][..], Vec::as_slice);
+            pixels.extend((0..width).flat_map(|x| match row.get(x) {
+                Some(&color) if color[3] != 0 => color,
+                _ => background,
+            }));
+        }
+
+        Ok(GraphicsImage { width: width as u32, height: height as u32, pixels })
+    }
+}
+
+/// Parses `;`-separated decimal parameters, returning them along with the
+/// number of bytes consumed. Empty parameters are read as 0.
+fn parse_params(data: &[u8]) -> (Vec<u32>, usize) {
+    let len = data
+        .iter()
+        .position(|b| !b.is_ascii_digit() && *b != b';')
+        .unwrap_or(data.len());
+
+    let params = data[..len]
+        .split(|&b| b == b';')
+        .map(|digits| {
+            digits.iter().fold(0u32, |acc, d| {
+                acc.saturating_mul(10)
+                    .saturating_add((d - b'0') as u32)
+            })
+        })
+        .collect();
+
+    (params, len)
+}
+
+/// Converts RGB percentages (0-100) to an opaque RGBA color.
+fn rgb_from_percent(rgb: [u32; 3]) -> [u8; 4] {
+    let [r, g, b] = rgb.map(|c| ((c.min(100) * 255 + 50) / 100) as u8);
+    [r, g, b, 0xFF]
+}
+
+/// Converts a sixel HLS color to an opaque RGBA color. Sixel hues start at
+/// blue, with red at 120 and green at 240 degrees.
+fn rgb_from_hls(hue: u32, lightness: u32, saturation: u32) -> [u8; 4] {
+    let h = ((hue % 360 + 240) % 360) as f32 / 60.0;
+    let l = lightness.min(100) as f32 / 100.0;
+    let s = saturation.min(100) as f32 / 100.0;
+
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let m = l - chroma / 2.0;
+    let [r, g, b] = [r, g, b].map(|c| ((c + m) * 255.0).round() as u8);
+    [r, g, b, 0xFF]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(image: &GraphicsImage, x: u32, y: u32) -> [u8; 4] {
+        let idx = ((y * image.width + x) * 4) as usize;
+        image.pixels[idx..idx + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn decodes_bands_colors_and_repeats() {
+        // red 3x1 line at the top, then a green 1x6 column in the second band
+        let image = decode_sixel(b"q#1;2;100;0;0!3@-#2;2;0;100;0~").unwrap();
+        assert_eq!((image.width, image.height), (3, 12));
+
+        assert_eq!(pixel(&image, 2, 0), [0xFF, 0, 0, 0xFF]);
+        assert_eq!(pixel(&image, 0, 6), [0, 0xFF, 0, 0xFF]);
+        assert_eq!(pixel(&image, 0, 11), [0, 0xFF, 0, 0xFF]);
+        // uncovered pixels take register 0 (black) without P2=1
+        assert_eq!(pixel(&image, 1, 6), [0, 0, 0, 0xFF]);
+    }
+
+    #[test]
+    fn transparent_background_and_raster_size() {
+        let image = decode_sixel(b"0;1;0q\"1;1;4;8#3$@").unwrap();
+        assert_eq!((image.width, image.height), (4, 8));
+
+        // register 3 is the VT340 green
+        assert_eq!(pixel(&image, 0, 0), [51, 204, 51, 0xFF]);
+        assert_eq!(pixel(&image, 3, 7), [0; 4]);
+    }
+
+    #[test]
+    fn carriage_return_overdraws_the_band() {
+        let image = decode_sixel(b"q#1;2;100;0;0~~$#2;2;0;0;100@").unwrap();
+        assert_eq!(pixel(&image, 0, 0), [0, 0, 0xFF, 0xFF]);
+        assert_eq!(pixel(&image, 0, 1), [0xFF, 0, 0, 0xFF]);
+        assert_eq!(pixel(&image, 1, 0), [0xFF, 0, 0, 0xFF]);
+    }
+
+    #[test]
+    fn hls_colors_start_at_blue() {
+        assert_eq!(rgb_from_hls(0, 50, 100), [0, 0, 0xFF, 0xFF]);
+        assert_eq!(rgb_from_hls(120, 50, 100), [0xFF, 0, 0, 0xFF]);
+        assert_eq!(rgb_from_hls(240, 50, 100), [0, 0xFF, 0, 0xFF]);
+        assert_eq!(rgb_from_hls(0, 100, 0), [0xFF, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn saturated_color_parameters_are_clamped() {
+        // parameters saturate at u32::MAX while parsing
+        let image = decode_sixel(b"q#1;1;4294967295;50;50~#2;2;4294967295;99999999999;0~").unwrap();
+        assert_eq!(pixel(&image, 0, 0)[3], 0xFF);
+        assert_eq!(pixel(&image, 1, 0), [0xFF, 0xFF, 0, 0xFF]);
+    }
+
+    #[test]
+    fn rejects_invalid_payloads() {
+        assert!(decode_sixel(b"#1~").is_err());
+        assert!(decode_sixel(b"q").is_err());
+        assert!(decode_sixel(b"q!20000~").is_err());
+        assert!(decode_sixel(b"q!4294967295~").is_err());
+        assert!(decode_sixel(b"q~!4294967295~").is_err());
+    }
+}
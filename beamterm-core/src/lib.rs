@@ -11,16 +11,32 @@
 //! `beamterm_core::glow` and `beamterm_core::compact_str` without adding
 //! separate dependencies or worrying about version mismatches.
 //!
+//! With the `ratatui` feature, [`TerminalGridBackend`] implements the
+//! `Backend` trait of [`ratatui_core`], which is re-exported as
+//! `beamterm_core::ratatui_core`.
+//!
 //! **Semver policy**: A dependency version bump (e.g. glow 0.17 to 0.18) is
 //! only considered a beamterm breaking change if the type signatures used in
 //! beamterm's public API actually change. A version bump that preserves the
 //! same type signatures is a compatible update.
 
+mod cell_text;
 pub(crate) mod error;
+mod export;
+mod frame;
 /// OpenGL rendering engine, atlas management, and terminal grid.
 pub mod gl;
+#[cfg(feature = "graphics-protocols")]
+pub mod graphics;
 mod mat4;
+mod matcher;
+mod palette;
 mod position;
+#[cfg(feature = "ratatui")]
+mod ratatui_backend;
+mod search;
+mod snapshot;
+mod software;
 mod url;
 
 // Re-export third-party crates that appear in beamterm-core's public API.
@@ -28,22 +44,37 @@ mod url;
 // `beamterm_core::compact_str` without adding separate dependencies
 // or worrying about version mismatches.
 pub use ::beamterm_data::{
-    CellSize, DebugSpacePattern, FontAtlasData, GlyphEffect, SerializationError, TerminalSize,
+    CellSize, CellSnapshot, CursorSnapshot, DebugSpacePattern, FontAtlasData, Frame, FrameDiff,
+    FrameOp, GlyphEffect, GridSnapshot, LineDecoration, RowSnapshot, SelectionSnapshot,
+    SerializationError, TerminalSize,
 };
 pub use beamterm_data::FontStyle;
 pub use beamterm_unicode::{is_double_width, is_emoji};
 pub use compact_str;
 pub use error::Error;
+pub use export::{SvgOptions, export_ansi, export_html, export_svg};
+pub use frame::apply_frame_diff;
 pub use gl::{
-    Atlas, CellData, CellDynamic, CellIterator, CellQuery, Drawable, FontAtlas, GlState, GlyphSlot,
-    GlyphTracker, RenderContext, SelectionMode, SelectionTracker, StaticFontAtlas, TerminalGrid,
-    select,
+    Atlas, CellAttributes, CellData, CellDynamic, CellIterator, CellQuery, Cursor, CursorShape,
+    DEFAULT_WORD_SEPARATORS, Drawable, FontAtlas, GlState, GlyphLookup, GlyphSlot, GlyphTracker,
+    GridModel, Highlight, HighlightId, HyperlinkId, ImageId, ImagePlacement, LineAttribute,
+    PlacementId, RenderContext, SelectionMode, SelectionStyle, SelectionTracker, StaticFontAtlas,
+    StaticGlyphLookup, TerminalGrid, UnderlineStyle, select,
 };
 #[cfg(feature = "native-dynamic-atlas")]
 pub use gl::{NativeDynamicAtlas, NativeGlyphRasterizer};
 pub use glow;
+pub use matcher::{Matcher, PatternMatch, find_all_matches};
+pub use palette::{CellColor, Palette};
 pub use position::CursorPosition;
-pub use url::{UrlMatch, find_url_at_cursor};
+#[cfg(feature = "ratatui")]
+pub use ratatui_backend::TerminalGridBackend;
+#[cfg(feature = "ratatui")]
+pub use ratatui_core;
+pub use search::{SearchMatch, SearchOptions, SearchQuery, search};
+pub use snapshot::{restore_grid, snapshot_grid};
+pub use software::{RgbaImage, SoftwareRenderer};
+pub use url::{UrlMatch, find_url_at_cursor, find_url_at_cursor_in_model};
 
 /// GL shader language target for version injection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
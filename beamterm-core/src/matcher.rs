@@ -0,0 +1,584 @@
+use std::{fmt, ops::Range, sync::Arc};
+
+use compact_str::CompactString;
+
+use crate::{
+    GlyphLookup, GridModel,
+    cell_text::CellText,
+    gl::CellQuery,
+    url::{is_trailing_punctuation, is_url_char},
+};
+
+/// A match of a [`Matcher`] on screen, as found by [`find_all_matches`].
+#[derive(Debug, Clone)]
+pub struct PatternMatch {
+    /// Index of the matcher that produced the match.
+    pub matcher: usize,
+    /// A `CellQuery` configured with the match's start and end positions.
+    pub query: CellQuery,
+    /// The matched text.
+    pub text: CompactString,
+}
+
+/// A pattern detected by [`find_all_matches`].
+///
+/// Besides the built-in patterns, [`Matcher::custom`] accepts any function
+/// finding byte ranges in a line of text.
+#[derive(Clone)]
+pub struct Matcher {
+    kind: MatcherKind,
+}
+
+type MatchFn = dyn Fn(&str) -> Vec<Range<usize>> + Send + Sync;
+
+#[derive(Clone)]
+enum MatcherKind {
+    Schemes(Vec<CompactString>),
+    FilePaths,
+    GitShas,
+    IssueKeys,
+    IpAddresses,
+    Custom(Arc<MatchFn>),
+}
+
+impl Matcher {
+    /// Matches `http://` and `https://` URLs, like [`find_url_at_cursor`](crate::find_url_at_cursor).
+    #[must_use]
+    pub fn urls() -> Self {
+        Self::schemes(&["https://", "http://"])
+    }
+
+    /// Matches URIs starting with `prefix`, such as `mailto:` or `ssh://`.
+    ///
+    /// Trailing punctuation and unbalanced closing parentheses are excluded.
+    #[must_use]
+    pub fn scheme(prefix: &str) -> Self {
+        Self::schemes(&[prefix])
+    }
+
+    /// Matches URIs starting with any of the `prefixes`.
+    #[must_use]
+    pub fn schemes(prefixes: &[&str]) -> Self {
+        let prefixes = prefixes
+            .iter()
+            .filter(|prefix| !prefix.is_empty())
+            .map(|&prefix| CompactString::new(prefix))
+            .collect();
+
+        Self { kind: MatcherKind::Schemes(prefixes) }
+    }
+
+    /// Matches file paths containing a `/`, e.g. `src/main.rs`, along with an
+    /// optional `:line` or `:line:col` suffix. File names without a directory
+    /// are only matched with a line suffix, e.g. `main.rs:12`.
+    #[must_use]
+    pub fn file_paths() -> Self {
+        Self { kind: MatcherKind::FilePaths }
+    }
+
+    /// Matches abbreviated and full git commit hashes: 7 to 40 lowercase
+    /// hexadecimal digits, containing both letters and digits.
+    #[must_use]
+    pub fn git_shas() -> Self {
+        Self { kind: MatcherKind::GitShas }
+    }
+
+    /// Matches issue keys in the style of `PROJ-123`.
+    #[must_use]
+    pub fn issue_keys() -> Self {
+        Self { kind: MatcherKind::IssueKeys }
+    }
+
+    /// Matches IPv4 addresses with an optional port, e.g. `10.0.0.1:8080`.
+    #[must_use]
+    pub fn ip_addresses() -> Self {
+        Self { kind: MatcherKind::IpAddresses }
+    }
+
+    /// Matches the byte ranges returned by `find` for each line of text.
+    ///
+    /// # Example
+    /// ```
+    /// use beamterm_core::Matcher;
+    ///
+    /// // matches the word TODO
+    /// let todo = Matcher::custom(|line: &str| {
+    ///     line.match_indices("TODO")
+    ///         .map(|(start, m)| start..start + m.len())
+    ///         .collect()
+    /// });
+    /// ```
+    pub fn custom(find: impl Fn(&str) -> Vec<Range<usize>> + Send + Sync + 'static) -> Self {
+        Self { kind: MatcherKind::Custom(Arc::new(find)) }
+    }
+
+    /// Returns the byte ranges of all matches in `line`, in ascending order.
+    pub(crate) fn find(&self, line: &str) -> Vec<Range<usize>> {
+        match &self.kind {
+            MatcherKind::Schemes(prefixes) => {
+                let mut matches: Vec<_> = prefixes
+                    .iter()
+                    .flat_map(|prefix| find_scheme(line, prefix))
+                    .collect();
+                matches.sort_by_key(|m| m.start);
+                matches
+            },
+            MatcherKind::FilePaths => find_file_paths(line),
+            MatcherKind::GitShas => find_git_shas(line),
+            MatcherKind::IssueKeys => find_issue_keys(line),
+            MatcherKind::IpAddresses => find_ip_addresses(line),
+            MatcherKind::Custom(find) => {
+                let mut matches = find(line);
+                matches.sort_by_key(|m| m.start);
+                matches
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match &self.kind {
+            MatcherKind::Schemes(prefixes) => return write!(f, "Matcher::Schemes({prefixes:?})"),
+            MatcherKind::FilePaths => "FilePaths",
+            MatcherKind::GitShas => "GitShas",
+            MatcherKind::IssueKeys => "IssueKeys",
+            MatcherKind::IpAddresses => "IpAddresses",
+            MatcherKind::Custom(_) => "Custom",
+        };
+
+        write!(f, "Matcher::{kind}")
+    }
+}
+
+/// Finds every match of the `matchers` in the viewport, in row-major order.
+///
+/// Each row is matched separately. Where matches of several matchers
+/// overlap, the matcher listed first wins, so list the most specific
+/// matchers first, e.g. URLs before file paths.
+///
+/// # Example
+/// ```
+/// use beamterm_core::{CellData, FontAtlasData, GridModel, Matcher, StaticGlyphLookup};
+/// use beamterm_core::{TerminalSize, find_all_matches};
+///
+/// let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+/// let mut model = GridModel::new(lookup, TerminalSize::new(24, 1));
+///
+/// let text: Vec<String> = "fixed in a1b2c3d, see #1".chars().map(String::from).collect();
+/// let cells = text.iter().map(|s| CellData::new_with_style_bits(s, 0, 0xffffff, 0));
+/// model.update_cells(cells).unwrap();
+///
+/// let matches = find_all_matches(&model, &[Matcher::git_shas()]);
+/// assert_eq!(matches[0].text, "a1b2c3d");
+/// ```
+#[must_use]
+pub fn find_all_matches<A: GlyphLookup>(
+    grid: &GridModel<A>,
+    matchers: &[Matcher],
+) -> Vec<PatternMatch> {
+    let size = grid.terminal_size();
+    let cols = size.cols as usize;
+    let mut matches = Vec::new();
+
+    for row in 0..size.rows as usize {
+        let line = CellText::new(grid, row * cols..(row + 1) * cols);
+
+        let mut row_matches: Vec<(Range<usize>, usize)> = Vec::new();
+        for (matcher_idx, matcher) in matchers.iter().enumerate() {
+            for range in matcher.find(&line.text) {
+                let overlaps = row_matches
+                    .iter()
+                    .any(|(r, _)| r.start < range.end && range.start < r.end);
+                if !overlaps {
+                    row_matches.push((range, matcher_idx));
+                }
+            }
+        }
+        row_matches.sort_by_key(|(range, _)| range.start);
+
+        matches.extend(
+            row_matches
+                .into_iter()
+                .filter_map(|(range, matcher)| {
+                    let query = line.query(range.clone())?;
+                    let text = CompactString::new(&line.text[range]);
+                    Some(PatternMatch { matcher, query, text })
+                }),
+        );
+    }
+
+    matches
+}
+
+/// Returns `true` if the byte can be part of a word.
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Returns `true` if no word continues across `idx` from the left.
+fn is_word_start(bytes: &[u8], idx: usize) -> bool {
+    idx == 0 || !is_word_byte(bytes[idx - 1])
+}
+
+/// Returns `true` if no word continues across `idx` to the right.
+fn is_word_end(bytes: &[u8], idx: usize) -> bool {
+    bytes.get(idx).is_none_or(|&b| !is_word_byte(b))
+}
+
+fn find_scheme(line: &str, prefix: &str) -> Vec<Range<usize>> {
+    let bytes = line.as_bytes();
+    let mut matches = Vec::new();
+
+    for (start, _) in line.match_indices(prefix) {
+        if !is_word_start(bytes, start)
+            || matches
+                .last()
+                .is_some_and(|m: &Range<usize>| start < m.end)
+        {
+            continue;
+        }
+
+        let body = start + prefix.len();
+        let mut end = body;
+        let mut paren_balance = 0;
+        while let Some(&b) = bytes.get(end)
+            && b.is_ascii()
+            && is_url_char(b as char)
+        {
+            match b {
+                b'(' => paren_balance += 1,
+                b')' => paren_balance -= 1,
+                _ => {},
+            }
+            end += 1;
+        }
+
+        // trim trailing punctuation and unbalanced closing parentheses
+        while end > body {
+            match bytes[end - 1] {
+                b if is_trailing_punctuation(b as char) => end -= 1,
+                b')' if paren_balance < 0 => {
+                    paren_balance += 1;
+                    end -= 1;
+                },
+                _ => break,
+            }
+        }
+
+        if end > body {
+            matches.push(start..end);
+        }
+    }
+
+    matches
+}
+
+fn find_file_paths(line: &str) -> Vec<Range<usize>> {
+    let is_path_byte = |b: u8| b.is_ascii_alphanumeric() || b"._-/~+@".contains(&b);
+    let bytes = line.as_bytes();
+    let mut matches = Vec::new();
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if !is_path_byte(bytes[idx]) {
+            idx += 1;
+            continue;
+        }
+
+        let start = idx;
+        while idx < bytes.len() && is_path_byte(bytes[idx]) {
+            idx += 1;
+        }
+
+        // the remainder of a URL or a `host:path` pair
+        if start > 0 && bytes[start - 1] == b':' {
+            continue;
+        }
+
+        let mut end = idx;
+        while end > start && matches!(bytes[end - 1], b'.' | b'-') {
+            end -= 1;
+        }
+        let path = &bytes[start..end];
+
+        // optional :line and :line:col suffixes
+        let mut suffix_end = end;
+        for _ in 0..2 {
+            if suffix_end != idx || bytes.get(suffix_end) != Some(&b':') {
+                break;
+            }
+            let digits = bytes[suffix_end + 1..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            if digits == 0 {
+                break;
+            }
+            suffix_end += 1 + digits;
+            idx = suffix_end;
+        }
+
+        let has_dir = path.contains(&b'/');
+        let has_extension = path
+            .iter()
+            .rposition(|&b| b == b'.')
+            .is_some_and(|dot| dot > 0 && dot + 1 < path.len());
+        let is_path = path.iter().any(u8::is_ascii_alphabetic)
+            && (has_dir || (has_extension && suffix_end > end));
+
+        if is_path && is_word_end(bytes, suffix_end) {
+            matches.push(start..suffix_end);
+        }
+    }
+
+    matches
+}
+
+fn find_git_shas(line: &str) -> Vec<Range<usize>> {
+    let is_hex = |b: u8| b.is_ascii_digit() || (b'a'..=b'f').contains(&b);
+    let bytes = line.as_bytes();
+    let mut matches = Vec::new();
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if !is_hex(bytes[idx]) || !is_word_start(bytes, idx) {
+            idx += 1;
+            continue;
+        }
+
+        let start = idx;
+        while idx < bytes.len() && is_hex(bytes[idx]) {
+            idx += 1;
+        }
+
+        let sha = &bytes[start..idx];
+        if (7..=40).contains(&sha.len())
+            && is_word_end(bytes, idx)
+            && sha.iter().any(u8::is_ascii_digit)
+            && sha.iter().any(u8::is_ascii_alphabetic)
+        {
+            matches.push(start..idx);
+        }
+    }
+
+    matches
+}
+
+fn find_issue_keys(line: &str) -> Vec<Range<usize>> {
+    let bytes = line.as_bytes();
+    let mut matches = Vec::new();
+
+    for (dash, _) in line.match_indices('-') {
+        let key_len = bytes[..dash]
+            .iter()
+            .rev()
+            .take_while(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            .count();
+        let start = dash - key_len;
+        let number_len = bytes[dash + 1..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        let end = dash + 1 + number_len;
+
+        if (2..=10).contains(&key_len)
+            && bytes[start].is_ascii_uppercase()
+            && number_len > 0
+            && bytes[dash + 1] != b'0'
+            && is_word_start(bytes, start)
+            && is_word_end(bytes, end)
+        {
+            matches.push(start..end);
+        }
+    }
+
+    matches
+}
+
+fn find_ip_addresses(line: &str) -> Vec<Range<usize>> {
+    let bytes = line.as_bytes();
+    let digits_at = |idx: usize| {
+        bytes[idx.min(bytes.len())..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let mut matches = Vec::new();
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if !bytes[idx].is_ascii_digit()
+            || (idx > 0 && (is_word_byte(bytes[idx - 1]) || bytes[idx - 1] == b'.'))
+        {
+            idx += 1;
+            continue;
+        }
+
+        let start = idx;
+        let mut end = idx;
+        let mut octets = 0;
+        while octets < 4 {
+            let len = digits_at(end);
+            let valid = (1..=3).contains(&len)
+                && line[end..end + len]
+                    .parse::<u16>()
+                    .is_ok_and(|octet| octet <= 255);
+            if !valid {
+                break;
+            }
+            end += len;
+            octets += 1;
+            if octets < 4 {
+                if bytes.get(end) != Some(&b'.') {
+                    break;
+                }
+                end += 1;
+            }
+        }
+
+        if octets == 4 && bytes.get(end) != Some(&b'.') && is_word_end(bytes, end) {
+            // optional port
+            let port_len = if bytes.get(end) == Some(&b':') { digits_at(end + 1) } else { 0 };
+            let valid_port = (1..=5).contains(&port_len)
+                && line[end + 1..end + 1 + port_len]
+                    .parse::<u16>()
+                    .is_ok();
+            if valid_port && is_word_end(bytes, end + 1 + port_len) {
+                end += 1 + port_len;
+            }
+
+            matches.push(start..end);
+            idx = end;
+        } else {
+            idx = start + digits_at(start).max(1);
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, TerminalSize};
+
+    use super::*;
+    use crate::{CellData, StaticGlyphLookup};
+
+    fn model_with_text(cols: u16, rows: u16, text: &str) -> GridModel<StaticGlyphLookup> {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        let mut model = GridModel::new(lookup, TerminalSize::new(cols, rows));
+        let symbols: Vec<String> = text.chars().map(String::from).collect();
+        model
+            .update_cells(
+                symbols
+                    .iter()
+                    .map(|s| CellData::new_with_style_bits(s, 0, 0xFFFFFF, 0)),
+            )
+            .unwrap();
+        model
+    }
+
+    fn texts<'a>(line: &'a str, matcher: &Matcher) -> Vec<&'a str> {
+        matcher
+            .find(line)
+            .into_iter()
+            .map(|range| &line[range])
+            .collect()
+    }
+
+    #[test]
+    fn schemes() {
+        let line = "see (https://en.wikipedia.org/wiki/Rust_(language)), mail mailto:a@b.org.";
+        assert_eq!(texts(line, &Matcher::urls()), vec![
+            "https://en.wikipedia.org/wiki/Rust_(language)"
+        ]);
+        assert_eq!(texts(line, &Matcher::scheme("mailto:")), vec![
+            "mailto:a@b.org"
+        ]);
+        assert_eq!(
+            texts(
+                "git clone ssh://git@host:22/repo.git",
+                &Matcher::scheme("ssh://")
+            ),
+            vec!["ssh://git@host:22/repo.git"]
+        );
+        assert!(texts("xhttps://no and https://", &Matcher::urls()).is_empty());
+    }
+
+    #[test]
+    fn file_paths() {
+        let line = "error at src/gl/mod.rs:12:5, see ./README.md and main.rs:7 or ~/.bashrc.";
+        assert_eq!(texts(line, &Matcher::file_paths()), vec![
+            "src/gl/mod.rs:12:5",
+            "./README.md",
+            "main.rs:7",
+            "~/.bashrc"
+        ]);
+        assert!(texts("1/2 main.rs https://x.org/a", &Matcher::file_paths()).is_empty());
+    }
+
+    #[test]
+    fn git_shas() {
+        let line = "a1b2c3d deadbeef 1234567 0123456789abcdef0123456789abcdef01234567 a1b2c3";
+        assert_eq!(texts(line, &Matcher::git_shas()), vec![
+            "a1b2c3d",
+            "0123456789abcdef0123456789abcdef01234567"
+        ]);
+    }
+
+    #[test]
+    fn issue_keys() {
+        let line = "PROJ-123, ab-1 X-1 BEAM2-7 UTF-8x ISO-0 [CORE-42]";
+        assert_eq!(texts(line, &Matcher::issue_keys()), vec![
+            "PROJ-123", "BEAM2-7", "CORE-42"
+        ]);
+    }
+
+    #[test]
+    fn ip_addresses() {
+        let line = "10.0.0.1:8080 192.168.1.256 1.2.3.4.5 v1.2.3.4 127.0.0.1, 8.8.8.8:99999";
+        assert_eq!(texts(line, &Matcher::ip_addresses()), vec![
+            "10.0.0.1:8080",
+            "127.0.0.1",
+            "8.8.8.8"
+        ]);
+    }
+
+    #[test]
+    fn earlier_matchers_win_overlaps() {
+        let model = model_with_text(24, 2, "https://x.org/a.rs:1    see src/lib.rs:9 fine");
+        let matchers = [Matcher::urls(), Matcher::file_paths()];
+
+        let matches: Vec<_> = find_all_matches(&model, &matchers)
+            .into_iter()
+            .map(|m| (m.matcher, m.text, model.get_text(m.query)))
+            .collect();
+
+        assert_eq!(matches, vec![
+            (
+                0,
+                "https://x.org/a.rs:1".into(),
+                "https://x.org/a.rs:1".into()
+            ),
+            (1, "src/lib.rs:9".into(), "src/lib.rs:9".into()),
+        ]);
+    }
+
+    #[test]
+    fn custom_matches_are_mapped_to_cells() {
+        let model = model_with_text(8, 1, "ab   ok.");
+        // empty and out of bounds ranges are ignored
+        let matcher = Matcher::custom(|line: &str| {
+            let mut ranges = vec![1..1, 40..50];
+            ranges.extend(line.find("ok").map(|start| start..start + 2));
+            ranges
+        });
+
+        let matches = find_all_matches(&model, &[matcher]);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].text, "ok");
+        assert_eq!(matches[0].query.range(), Some(((5, 0), (6, 0))));
+    }
+}
@@ -0,0 +1,221 @@
+/// Color of a cell, either a direct RGB value or a reference into the
+/// [`Palette`] of the grid.
+///
+/// Palette colors are resolved on the GPU, so switching themes with
+/// [`TerminalGrid::set_palette`](crate::TerminalGrid::set_palette) recolors
+/// all cells referencing the palette without re-uploading any cell data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CellColor {
+    /// A direct color as 0xRRGGBB.
+    Rgb(u32),
+    /// One of the 256 indexed palette colors.
+    Indexed(u8),
+    /// The default foreground color of the palette.
+    DefaultFg,
+    /// The default background color of the palette.
+    DefaultBg,
+}
+
+impl CellColor {
+    /// Palette slot of [`CellColor::DefaultFg`].
+    pub(crate) const DEFAULT_FG_INDEX: u16 = 256;
+    /// Palette slot of [`CellColor::DefaultBg`].
+    pub(crate) const DEFAULT_BG_INDEX: u16 = 257;
+
+    /// Returns the palette slot referenced by this color, or `None` for RGB colors.
+    pub(crate) const fn palette_index(self) -> Option<u16> {
+        match self {
+            CellColor::Rgb(_) => None,
+            CellColor::Indexed(index) => Some(index as u16),
+            CellColor::DefaultFg => Some(Self::DEFAULT_FG_INDEX),
+            CellColor::DefaultBg => Some(Self::DEFAULT_BG_INDEX),
+        }
+    }
+
+    /// Inverse of [`CellColor::palette_index`].
+    pub(crate) const fn from_palette_index(index: u16) -> Self {
+        match index {
+            Self::DEFAULT_FG_INDEX => CellColor::DefaultFg,
+            Self::DEFAULT_BG_INDEX => CellColor::DefaultBg,
+            index => CellColor::Indexed(index as u8),
+        }
+    }
+}
+
+impl From<u32> for CellColor {
+    fn from(rgb: u32) -> Self {
+        CellColor::Rgb(rgb)
+    }
+}
+
+/// A 256-color terminal palette with default foreground and background colors.
+///
+/// The default palette uses the standard xterm colors: 16 ANSI colors, a
+/// 6x6x6 color cube and a 24-step grayscale ramp.
+///
+/// # Example
+/// ```
+/// use beamterm_core::{CellColor, Palette};
+///
+/// let mut palette = Palette::default();
+/// palette.set_color(1, 0xFF5555);
+/// palette.set_default_bg(0x282A36);
+///
+/// assert_eq!(palette.resolve(CellColor::Indexed(1)), 0xFF5555);
+/// assert_eq!(palette.resolve(CellColor::DefaultBg), 0x282A36);
+/// assert_eq!(palette.resolve(CellColor::Rgb(0x123456)), 0x123456);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Palette {
+    colors: [u32; 256],
+    default_fg: u32,
+    default_bg: u32,
+}
+
+impl Palette {
+    /// Number of palette slots, including the default foreground and background.
+    pub(crate) const SLOTS: usize = 258;
+
+    #[rustfmt::skip]
+    const XTERM_ANSI_COLORS: [u32; 16] = [
+        0x00_00_00, 0xcd_00_00, 0x00_cd_00, 0xcd_cd_00,
+        0x00_00_ee, 0xcd_00_cd, 0x00_cd_cd, 0xe5_e5_e5,
+        0x7f_7f_7f, 0xff_00_00, 0x00_ff_00, 0xff_ff_00,
+        0x5c_5c_ff, 0xff_00_ff, 0x00_ff_ff, 0xff_ff_ff,
+    ];
+
+    const COLOR_CUBE_VALUES: [u32; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+    /// Creates the xterm palette with the given 16 ANSI colors and default
+    /// foreground and background colors.
+    #[must_use]
+    pub fn new(ansi_colors: [u32; 16], default_fg: u32, default_bg: u32) -> Self {
+        let colors = std::array::from_fn(|index| match index {
+            0..=15 => ansi_colors[index] & 0xFFFFFF,
+            16..=231 => {
+                let i = index - 16;
+                let r = Self::COLOR_CUBE_VALUES[i / 36];
+                let g = Self::COLOR_CUBE_VALUES[(i / 6) % 6];
+                let b = Self::COLOR_CUBE_VALUES[i % 6];
+                (r << 16) | (g << 8) | b
+            },
+            _ => {
+                let v = 8 + 10 * (index as u32 - 232);
+                (v << 16) | (v << 8) | v
+            },
+        });
+
+        Self {
+            colors,
+            default_fg: default_fg & 0xFFFFFF,
+            default_bg: default_bg & 0xFFFFFF,
+        }
+    }
+
+    /// Returns the indexed color as 0xRRGGBB.
+    #[must_use]
+    pub fn color(&self, index: u8) -> u32 {
+        self.colors[index as usize]
+    }
+
+    /// Sets the indexed color to `rgb` (0xRRGGBB).
+    pub fn set_color(&mut self, index: u8, rgb: u32) {
+        self.colors[index as usize] = rgb & 0xFFFFFF;
+    }
+
+    /// Returns the default foreground color as 0xRRGGBB.
+    #[must_use]
+    pub fn default_fg(&self) -> u32 {
+        self.default_fg
+    }
+
+    /// Sets the default foreground color to `rgb` (0xRRGGBB).
+    pub fn set_default_fg(&mut self, rgb: u32) {
+        self.default_fg = rgb & 0xFFFFFF;
+    }
+
+    /// Returns the default background color as 0xRRGGBB.
+    #[must_use]
+    pub fn default_bg(&self) -> u32 {
+        self.default_bg
+    }
+
+    /// Sets the default background color to `rgb` (0xRRGGBB).
+    pub fn set_default_bg(&mut self, rgb: u32) {
+        self.default_bg = rgb & 0xFFFFFF;
+    }
+
+    /// Resolves a cell color to 0xRRGGBB.
+    #[must_use]
+    pub fn resolve(&self, color: CellColor) -> u32 {
+        match color {
+            CellColor::Rgb(rgb) => rgb & 0xFFFFFF,
+            CellColor::Indexed(index) => self.color(index),
+            CellColor::DefaultFg => self.default_fg,
+            CellColor::DefaultBg => self.default_bg,
+        }
+    }
+
+    /// Returns the color of a palette slot, as laid out in the palette UBO.
+    pub(crate) fn slot(&self, slot: usize) -> u32 {
+        match slot as u16 {
+            CellColor::DEFAULT_FG_INDEX => self.default_fg,
+            CellColor::DEFAULT_BG_INDEX => self.default_bg,
+            _ => self.colors[slot],
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(Self::XTERM_ANSI_COLORS, 0xFFFFFF, 0x000000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_palette_matches_xterm() {
+        let palette = Palette::default();
+
+        assert_eq!(palette.color(1), 0xcd0000);
+        assert_eq!(palette.color(16), 0x000000);
+        assert_eq!(palette.color(196), 0xff0000);
+        assert_eq!(palette.color(231), 0xffffff);
+        assert_eq!(palette.color(232), 0x080808);
+        assert_eq!(palette.color(255), 0xeeeeee);
+    }
+
+    #[test]
+    fn constructor_masks_colors_to_rgb() {
+        let palette = Palette::new(
+            [0xFF00_0000 | 0x123456; 16],
+            0xFF00_0000 | 0xABCDEF,
+            0x8000_0000,
+        );
+
+        for index in 0..16 {
+            assert_eq!(palette.color(index), 0x123456);
+        }
+        assert_eq!(palette.default_fg(), 0xABCDEF);
+        assert_eq!(palette.default_bg(), 0x000000);
+    }
+
+    #[test]
+    fn palette_indices_round_trip() {
+        for color in [
+            CellColor::Indexed(0),
+            CellColor::Indexed(255),
+            CellColor::DefaultFg,
+            CellColor::DefaultBg,
+        ] {
+            let index = color.palette_index().unwrap();
+            assert!((index as usize) < Palette::SLOTS);
+            assert_eq!(CellColor::from_palette_index(index), color);
+        }
+
+        assert_eq!(CellColor::Rgb(0x123456).palette_index(), None);
+    }
+}
@@ -0,0 +1,634 @@
+use std::{ops::Range, rc::Rc};
+
+use beamterm_data::{Glyph, TerminalSize};
+use ratatui_core::{
+    backend::{Backend, ClearType, WindowSize},
+    buffer::Cell,
+    layout::{Position, Size},
+    style::{Color, Modifier},
+};
+
+use crate::{
+    CellAttributes, CellColor, CellData, Cursor, CursorPosition, Error, Palette, TerminalGrid,
+};
+
+/// A [ratatui](https://ratatui.rs) [`Backend`] drawing to a [`TerminalGrid`].
+///
+/// Ratatui diffs its buffers and only hands changed cells to
+/// [`draw`](Backend::draw), which are written to the grid with
+/// [`TerminalGrid::update_cells_by_position`]; [`flush`](Backend::flush)
+/// uploads them to the GPU. Rendering the grid is left to the application,
+/// through [`grid`](Self::grid) and [`gl`](Self::gl).
+///
+/// `Color::Reset` maps to the default colors of the grid's [`Palette`], and
+/// named and indexed colors to its indexed colors, so switching themes
+/// recolors the UI without redrawing it.
+pub struct TerminalGridBackend {
+    backend: GridBackend<GlSurface>,
+}
+
+/// The [`Backend`] behind [`TerminalGridBackend`], generic over the
+/// [`Surface`] it draws to so that it also runs without GL.
+struct GridBackend<S> {
+    surface: S,
+}
+
+/// A [`TerminalGrid`] along with the GL context its GPU resources live in.
+struct GlSurface {
+    gl: Rc<glow::Context>,
+    grid: TerminalGrid,
+}
+
+/// The grid operations a [`GridBackend`] is built on.
+trait Surface {
+    /// Writes ratatui cells to the live screen.
+    ///
+    /// # Errors
+    /// Returns an error if the cells cannot be written.
+    fn draw_cells<'a>(
+        &mut self,
+        cells: impl Iterator<Item = (u16, u16, &'a Cell)>,
+    ) -> Result<(), Error>;
+
+    /// Fills a range of cells of the live screen with blanks.
+    ///
+    /// # Errors
+    /// Returns an error if the cells cannot be written.
+    fn clear_cells(&mut self, cells: Range<usize>) -> Result<(), Error>;
+
+    /// Scrolls the live rows of `region` by `lines`; positive values move
+    /// the content up.
+    ///
+    /// # Errors
+    /// Returns an error if the scroll cannot be applied.
+    fn scroll_region(&mut self, region: Range<u16>, lines: i32) -> Result<(), Error>;
+
+    /// Returns the size of the grid in cells.
+    fn terminal_size(&self) -> TerminalSize;
+
+    /// Returns the size of the canvas in pixels.
+    fn canvas_size(&self) -> (i32, i32);
+
+    /// Returns the cursor.
+    fn cursor(&self) -> Cursor;
+
+    /// Replaces the cursor.
+    fn set_cursor(&mut self, cursor: Cursor);
+
+    /// Uploads the written cells for rendering.
+    ///
+    /// # Errors
+    /// Returns an error if the cells cannot be uploaded.
+    fn flush(&mut self) -> Result<(), Error>;
+}
+
+impl TerminalGridBackend {
+    /// Creates a backend drawing to `grid` with the GL context `gl`.
+    #[must_use]
+    pub fn new(gl: Rc<glow::Context>, grid: TerminalGrid) -> Self {
+        let surface = GlSurface { gl, grid };
+        Self { backend: GridBackend { surface } }
+    }
+
+    /// Returns the terminal grid.
+    pub fn grid(&self) -> &TerminalGrid {
+        &self.backend.surface.grid
+    }
+
+    /// Returns the terminal grid for mutation, e.g. to resize it.
+    pub fn grid_mut(&mut self) -> &mut TerminalGrid {
+        &mut self.backend.surface.grid
+    }
+
+    /// Returns the GL context of the grid.
+    #[must_use]
+    pub fn gl(&self) -> &glow::Context {
+        &self.backend.surface.gl
+    }
+
+    /// Consumes the backend, returning the terminal grid.
+    pub fn into_grid(self) -> TerminalGrid {
+        self.backend.surface.grid
+    }
+}
+
+impl Backend for TerminalGridBackend {
+    type Error = Error;
+
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), Self::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        self.backend.draw(content)
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), Self::Error> {
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<(), Self::Error> {
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> Result<Position, Self::Error> {
+        self.backend.get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> Result<(), Self::Error> {
+        self.backend.set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> Result<(), Self::Error> {
+        self.backend.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> Result<(), Self::Error> {
+        self.backend.clear_region(clear_type)
+    }
+
+    fn size(&self) -> Result<Size, Self::Error> {
+        self.backend.size()
+    }
+
+    fn window_size(&mut self) -> Result<WindowSize, Self::Error> {
+        self.backend.window_size()
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.backend.flush()
+    }
+
+    fn scroll_region_up(&mut self, region: Range<u16>, line_count: u16) -> Result<(), Self::Error> {
+        self.backend.scroll_region_up(region, line_count)
+    }
+
+    fn scroll_region_down(
+        &mut self,
+        region: Range<u16>,
+        line_count: u16,
+    ) -> Result<(), Self::Error> {
+        self.backend
+            .scroll_region_down(region, line_count)
+    }
+}
+
+impl Surface for GlSurface {
+    fn draw_cells<'a>(
+        &mut self,
+        cells: impl Iterator<Item = (u16, u16, &'a Cell)>,
+    ) -> Result<(), Error> {
+        let (model, palette) = self.grid.model_and_palette_mut();
+        model.update_cells_by_position(cells.map(|(x, y, cell)| (x, y, cell_data(cell, palette))))
+    }
+
+    fn clear_cells(&mut self, cells: Range<usize>) -> Result<(), Error> {
+        self.grid
+            .update_cells_by_index(cells.map(|idx| (idx, blank_cell())))
+    }
+
+    fn scroll_region(&mut self, region: Range<u16>, lines: i32) -> Result<(), Error> {
+        self.grid
+            .scroll_region(&self.gl, region.start, region.end, lines, blank_cell())
+    }
+
+    fn terminal_size(&self) -> TerminalSize {
+        self.grid.terminal_size()
+    }
+
+    fn canvas_size(&self) -> (i32, i32) {
+        self.grid.canvas_size()
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.grid.cursor()
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.grid.set_cursor(&self.gl, cursor);
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.grid.flush_cells(&self.gl)
+    }
+}
+
+impl<S: Surface> Backend for GridBackend<S> {
+    type Error = Error;
+
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), Self::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        self.surface.draw_cells(content)
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), Self::Error> {
+        let cursor = self.surface.cursor();
+        self.surface
+            .set_cursor(Cursor { visible: false, ..cursor });
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> Result<(), Self::Error> {
+        let cursor = self.surface.cursor();
+        self.surface
+            .set_cursor(Cursor { visible: true, ..cursor });
+        Ok(())
+    }
+
+    fn get_cursor_position(&mut self) -> Result<Position, Self::Error> {
+        let position = self.surface.cursor().position;
+        Ok(Position::new(position.col, position.row))
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> Result<(), Self::Error> {
+        let Position { x, y } = position.into();
+        let cursor = self.surface.cursor();
+        self.surface
+            .set_cursor(Cursor { position: CursorPosition::new(x, y), ..cursor });
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), Self::Error> {
+        self.clear_region(ClearType::All)
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> Result<(), Self::Error> {
+        let size = self.surface.terminal_size();
+        let cells = clear_range(
+            clear_type,
+            self.surface.cursor().position,
+            size.cols,
+            size.rows,
+        );
+        self.surface.clear_cells(cells)
+    }
+
+    fn size(&self) -> Result<Size, Self::Error> {
+        let size = self.surface.terminal_size();
+        Ok(Size::new(size.cols, size.rows))
+    }
+
+    fn window_size(&mut self) -> Result<WindowSize, Self::Error> {
+        let (width, height) = self.surface.canvas_size();
+        Ok(WindowSize {
+            columns_rows: self.size()?,
+            pixels: Size::new(
+                width.clamp(0, u16::MAX as i32) as u16,
+                height.clamp(0, u16::MAX as i32) as u16,
+            ),
+        })
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.surface.flush()
+    }
+
+    fn scroll_region_up(&mut self, region: Range<u16>, line_count: u16) -> Result<(), Self::Error> {
+        self.surface
+            .scroll_region(region, line_count.into())
+    }
+
+    fn scroll_region_down(
+        &mut self,
+        region: Range<u16>,
+        line_count: u16,
+    ) -> Result<(), Self::Error> {
+        self.surface
+            .scroll_region(region, -i32::from(line_count))
+    }
+}
+
+/// Returns the cell indices cleared by `clear_type`, for a cursor at `cursor`.
+fn clear_range(
+    clear_type: ClearType,
+    cursor: CursorPosition,
+    cols: u16,
+    rows: u16,
+) -> Range<usize> {
+    let cols = cols as usize;
+    let cell_count = cols * rows as usize;
+    let cursor = (cursor.row as usize * cols + cursor.col as usize).min(cell_count);
+    let line_start = cursor - cursor % cols.max(1);
+    let line_end = (line_start + cols).min(cell_count);
+
+    match clear_type {
+        ClearType::All => 0..cell_count,
+        ClearType::AfterCursor => cursor..cell_count,
+        ClearType::BeforeCursor => 0..(cursor + 1).min(cell_count),
+        ClearType::CurrentLine => line_start..line_end,
+        ClearType::UntilNewLine => cursor..line_end,
+    }
+}
+
+/// Converts a ratatui [`Cell`] to [`CellData`].
+///
+/// Underline colors are resolved against `palette`, since the grid stores
+/// them as RGB.
+fn cell_data<'a>(cell: &'a Cell, palette: &Palette) -> CellData<'a> {
+    let modifier = cell.modifier;
+
+    let mut style_bits = 0;
+    for (flag, bit) in [
+        (Modifier::BOLD, Glyph::BOLD_FLAG),
+        (Modifier::ITALIC, Glyph::ITALIC_FLAG),
+        (Modifier::UNDERLINED, Glyph::UNDERLINE_FLAG),
+        (Modifier::CROSSED_OUT, Glyph::STRIKETHROUGH_FLAG),
+    ] {
+        if modifier.contains(flag) {
+            style_bits |= bit;
+        }
+    }
+
+    let mut attributes = CellAttributes::empty();
+    attributes.set(CellAttributes::DIM, modifier.contains(Modifier::DIM));
+    attributes.set(
+        CellAttributes::BLINK,
+        modifier.intersects(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+    );
+    attributes.set(
+        CellAttributes::REVERSE,
+        modifier.contains(Modifier::REVERSED),
+    );
+    attributes.set(CellAttributes::HIDDEN, modifier.contains(Modifier::HIDDEN));
+
+    let data = CellData::new_with_style_bits(cell.symbol(), style_bits, 0, 0)
+        .with_fg(cell_color(cell.fg).unwrap_or(CellColor::DefaultFg))
+        .with_bg(cell_color(cell.bg).unwrap_or(CellColor::DefaultBg))
+        .with_attributes(attributes);
+
+    match cell_color(cell.underline_color) {
+        Some(color) => data.with_underline_color(palette.resolve(color)),
+        None => data,
+    }
+}
+
+/// Converts a ratatui [`Color`], or returns `None` for [`Color::Reset`].
+fn cell_color(color: Color) -> Option<CellColor> {
+    let color = match color {
+        Color::Reset => return None,
+        Color::Black => CellColor::Indexed(0),
+        Color::Red => CellColor::Indexed(1),
+        Color::Green => CellColor::Indexed(2),
+        Color::Yellow => CellColor::Indexed(3),
+        Color::Blue => CellColor::Indexed(4),
+        Color::Magenta => CellColor::Indexed(5),
+        Color::Cyan => CellColor::Indexed(6),
+        Color::Gray => CellColor::Indexed(7),
+        Color::DarkGray => CellColor::Indexed(8),
+        Color::LightRed => CellColor::Indexed(9),
+        Color::LightGreen => CellColor::Indexed(10),
+        Color::LightYellow => CellColor::Indexed(11),
+        Color::LightBlue => CellColor::Indexed(12),
+        Color::LightMagenta => CellColor::Indexed(13),
+        Color::LightCyan => CellColor::Indexed(14),
+        Color::White => CellColor::Indexed(15),
+        Color::Indexed(index) => CellColor::Indexed(index),
+        Color::Rgb(r, g, b) => CellColor::Rgb(u32::from_be_bytes([0, r, g, b])),
+    };
+    Some(color)
+}
+
+fn blank_cell() -> CellData<'static> {
+    CellData::new_with_style_bits(" ", 0, 0, 0)
+        .with_fg(CellColor::DefaultFg)
+        .with_bg(CellColor::DefaultBg)
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{CellSnapshot, FontAtlasData, TerminalSize};
+    use ratatui_core::style::Style;
+
+    use super::*;
+    use crate::{GridModel, StaticGlyphLookup, UnderlineStyle, snapshot_grid};
+
+    /// A GL-free surface writing to a grid model.
+    struct ModelSurface {
+        model: GridModel<StaticGlyphLookup>,
+        palette: Palette,
+        cursor: Cursor,
+        flushes: usize,
+    }
+
+    impl Surface for ModelSurface {
+        fn draw_cells<'a>(
+            &mut self,
+            cells: impl Iterator<Item = (u16, u16, &'a Cell)>,
+        ) -> Result<(), Error> {
+            let palette = &self.palette;
+            self.model.update_cells_by_position(
+                cells.map(|(x, y, cell)| (x, y, cell_data(cell, palette))),
+            )
+        }
+
+        fn clear_cells(&mut self, cells: Range<usize>) -> Result<(), Error> {
+            self.model
+                .update_cells_by_index(cells.map(|idx| (idx, blank_cell())))
+        }
+
+        fn scroll_region(&mut self, region: Range<u16>, lines: i32) -> Result<(), Error> {
+            self.model
+                .scroll_region(region.start, region.end, lines, blank_cell());
+            Ok(())
+        }
+
+        fn terminal_size(&self) -> TerminalSize {
+            self.model.terminal_size()
+        }
+
+        fn canvas_size(&self) -> (i32, i32) {
+            let size = self.model.terminal_size();
+            (size.cols as i32 * 10, size.rows as i32 * 20)
+        }
+
+        fn cursor(&self) -> Cursor {
+            self.cursor
+        }
+
+        fn set_cursor(&mut self, cursor: Cursor) {
+            self.cursor = cursor;
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    fn backend(cols: u16, rows: u16) -> GridBackend<ModelSurface> {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        GridBackend {
+            surface: ModelSurface {
+                model: GridModel::new(lookup, TerminalSize::new(cols, rows)),
+                palette: Palette::default(),
+                cursor: Cursor::default(),
+                flushes: 0,
+            },
+        }
+    }
+
+    fn rows(backend: &GridBackend<ModelSurface>) -> Vec<String> {
+        let cols = backend.surface.terminal_size().cols as usize;
+        snapshot_grid(&backend.surface.model)
+            .cells
+            .chunks(cols)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.symbol.as_str())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn draw_rows(backend: &mut GridBackend<ModelSurface>, rows: &[&str]) {
+        let cells: Vec<_> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars().enumerate().map(move |(x, ch)| {
+                    let mut cell = Cell::default();
+                    cell.set_char(ch);
+                    (x as u16, y as u16, cell)
+                })
+            })
+            .collect();
+        backend
+            .draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell)))
+            .unwrap();
+    }
+
+    #[test]
+    fn backend_draws_and_flushes_cells() {
+        let mut backend = backend(3, 2);
+        draw_rows(&mut backend, &["abc", "def"]);
+        backend.flush().unwrap();
+
+        assert_eq!(rows(&backend), ["abc", "def"]);
+        assert_eq!(backend.surface.flushes, 1);
+        assert_eq!(backend.size().unwrap(), Size::new(3, 2));
+        assert_eq!(backend.window_size().unwrap(), WindowSize {
+            columns_rows: Size::new(3, 2),
+            pixels: Size::new(30, 40),
+        });
+    }
+
+    #[test]
+    fn backend_clears_around_the_cursor() {
+        let mut backend = backend(3, 2);
+        draw_rows(&mut backend, &["abc", "def"]);
+
+        backend.set_cursor_position((1, 0)).unwrap();
+        backend
+            .clear_region(ClearType::UntilNewLine)
+            .unwrap();
+        assert_eq!(rows(&backend), ["a  ", "def"]);
+
+        backend.clear().unwrap();
+        assert_eq!(rows(&backend), ["   ", "   "]);
+    }
+
+    #[test]
+    fn backend_moves_and_toggles_the_cursor() {
+        let mut backend = backend(4, 3);
+
+        backend.set_cursor_position((2, 1)).unwrap();
+        backend.show_cursor().unwrap();
+        assert_eq!(backend.get_cursor_position().unwrap(), Position::new(2, 1));
+        assert!(backend.surface.cursor.visible);
+
+        backend.hide_cursor().unwrap();
+        assert!(!backend.surface.cursor.visible);
+        assert_eq!(backend.surface.cursor.position, CursorPosition::new(2, 1));
+    }
+
+    #[test]
+    fn backend_scrolls_regions() {
+        let mut backend = backend(1, 4);
+        draw_rows(&mut backend, &["a", "b", "c", "d"]);
+
+        backend.scroll_region_up(1..4, 1).unwrap();
+        assert_eq!(rows(&backend), ["a", "c", "d", " "]);
+
+        backend.scroll_region_down(0..3, 2).unwrap();
+        assert_eq!(rows(&backend), [" ", " ", "a", " "]);
+    }
+
+    #[test]
+    fn cells_map_styles_and_colors() {
+        let mut bold = Cell::new("a");
+        bold.set_style(
+            Style::new()
+                .fg(Color::LightRed)
+                .bg(Color::Rgb(0x12, 0x34, 0x56))
+                .add_modifier(Modifier::BOLD | Modifier::CROSSED_OUT | Modifier::REVERSED),
+        );
+        let mut underlined = Cell::new("b");
+        underlined.set_style(
+            Style::new()
+                .fg(Color::Indexed(200))
+                .underline_color(Color::Blue)
+                .add_modifier(Modifier::UNDERLINED | Modifier::DIM | Modifier::SLOW_BLINK),
+        );
+        let plain = Cell::new("c");
+
+        let palette = Palette::default();
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        let mut grid = GridModel::new(lookup, TerminalSize::new(3, 1));
+        let cells = [(0, 0, &bold), (1, 0, &underlined), (2, 0, &plain)];
+        grid.update_cells_by_position(
+            cells
+                .into_iter()
+                .map(|(x, y, cell)| (x, y, cell_data(cell, &palette))),
+        )
+        .unwrap();
+
+        let snapshot = snapshot_grid(&grid);
+        let palette_color = |index: u32| CellSnapshot::PALETTE_COLOR | index;
+        let [bold, underlined, plain] = &snapshot.cells[..] else {
+            panic!("expected three cells");
+        };
+
+        assert_eq!(bold.symbol, "a");
+        assert_eq!(
+            bold.style_bits,
+            Glyph::BOLD_FLAG | Glyph::STRIKETHROUGH_FLAG
+        );
+        assert_eq!((bold.fg, bold.bg), (palette_color(9), 0x123456));
+        assert_eq!(bold.attributes, CellAttributes::REVERSE.bits());
+
+        assert_eq!(underlined.style_bits, Glyph::UNDERLINE_FLAG);
+        assert_eq!(underlined.fg, palette_color(200));
+        assert_eq!(underlined.underline_color, Some(palette.color(4)));
+        assert_eq!(
+            underlined.underline_style,
+            UnderlineStyle::Single.shader_style()
+        );
+        assert_eq!(
+            underlined.attributes,
+            (CellAttributes::DIM | CellAttributes::BLINK).bits()
+        );
+
+        let defaults = (
+            palette_color(CellColor::DEFAULT_FG_INDEX.into()),
+            palette_color(CellColor::DEFAULT_BG_INDEX.into()),
+        );
+        assert_eq!(
+            (plain.style_bits, plain.fg, plain.bg),
+            (0, defaults.0, defaults.1)
+        );
+        assert_eq!(plain.underline_color, None);
+    }
+
+    #[test]
+    fn clear_types_cover_the_expected_cells() {
+        let cursor = CursorPosition::new(2, 1);
+        let range = |clear_type| clear_range(clear_type, cursor, 4, 3);
+
+        assert_eq!(range(ClearType::All), 0..12);
+        assert_eq!(range(ClearType::AfterCursor), 6..12);
+        assert_eq!(range(ClearType::BeforeCursor), 0..7);
+        assert_eq!(range(ClearType::CurrentLine), 4..8);
+        assert_eq!(range(ClearType::UntilNewLine), 6..8);
+    }
+}
@@ -0,0 +1,308 @@
+use std::ops::Range;
+
+use compact_str::CompactString;
+
+#[cfg(feature = "regex")]
+use crate::Error;
+use crate::{GlyphLookup, GridModel, cell_text::CellText, gl::CellQuery};
+
+/// A match of a [`SearchQuery`], as found by [`search`].
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    /// A `CellQuery` configured with the match's start and end positions.
+    pub query: CellQuery,
+    /// The matched text.
+    pub text: CompactString,
+}
+
+/// Options of a [`SearchQuery`].
+///
+/// The default is a case-insensitive search matching anywhere in words.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Distinguishes upper and lower case letters.
+    pub case_sensitive: bool,
+    /// Only matches that are not directly preceded or followed by a letter,
+    /// digit or underscore are returned.
+    pub whole_word: bool,
+}
+
+/// Text to search for in the cells of a grid with [`search`].
+///
+/// # Example
+/// ```
+/// use beamterm_core::{SearchOptions, SearchQuery};
+///
+/// let options = SearchOptions { whole_word: true, ..SearchOptions::default() };
+/// let query = SearchQuery::plain("error", options);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    pattern: Pattern,
+    options: SearchOptions,
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    Plain(CompactString),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl SearchQuery {
+    /// Searches for `text` literally. An empty `text` matches nothing.
+    #[must_use]
+    pub fn plain(text: &str, options: SearchOptions) -> Self {
+        Self {
+            pattern: Pattern::Plain(CompactString::new(text)),
+            options,
+        }
+    }
+
+    /// Searches for matches of a regular expression, in the syntax of the
+    /// [`regex`] crate. Empty matches are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if the pattern is not a valid regular expression.
+    #[cfg(feature = "regex")]
+    pub fn regex(pattern: &str, options: SearchOptions) -> Result<Self, Error> {
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(Error::invalid_search_pattern)?;
+
+        Ok(Self { pattern: Pattern::Regex(regex), options })
+    }
+
+    /// Returns the options of the search.
+    #[must_use]
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    /// Returns the non-overlapping matches in a line of text, as byte ranges.
+    fn find(&self, line: &str) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        let mut pos = 0;
+
+        while pos < line.len() {
+            let Some(range) = self.find_at(line, pos) else {
+                break;
+            };
+
+            if range.is_empty() || (self.options.whole_word && !is_whole_word(line, &range)) {
+                // retry from the next character
+                pos = range.start + next_char_len(line, range.start);
+                continue;
+            }
+
+            pos = range.end;
+            matches.push(range);
+        }
+
+        matches
+    }
+
+    /// Returns the first match starting at or after the byte offset `pos`.
+    fn find_at(&self, line: &str, pos: usize) -> Option<Range<usize>> {
+        match &self.pattern {
+            Pattern::Plain(needle) if needle.is_empty() => None,
+            Pattern::Plain(needle) => line[pos..]
+                .char_indices()
+                .find_map(|(offset, _)| {
+                    let start = pos + offset;
+                    let len = prefix_len(&line[start..], needle, self.options.case_sensitive)?;
+                    Some(start..start + len)
+                }),
+            #[cfg(feature = "regex")]
+            Pattern::Regex(regex) => regex.find_at(line, pos).map(|m| m.range()),
+        }
+    }
+}
+
+/// Returns every match of `query` in the viewport, in reading order.
+///
+/// Rows marked with [`GridModel::set_line_wrapped`] are joined with the
+/// following row, so matches may span several rows. Double-width glyphs are
+/// matched as a single character covering both of their cells.
+#[must_use]
+pub fn search<A: GlyphLookup>(grid: &GridModel<A>, query: &SearchQuery) -> Vec<SearchMatch> {
+    let size = grid.terminal_size();
+    let cols = size.cols as usize;
+    let mut matches = Vec::new();
+
+    let mut row = 0;
+    while row < size.rows {
+        // a logical line runs up to the first row that is not wrapped
+        let first = row;
+        while row + 1 < size.rows && grid.is_viewport_line_wrapped(row) {
+            row += 1;
+        }
+        row += 1;
+
+        let line = CellText::new(grid, first as usize * cols..row as usize * cols);
+        matches.extend(
+            query
+                .find(&line.text)
+                .into_iter()
+                .filter_map(|range| {
+                    let query = line.query(range.clone())?;
+                    let text = CompactString::new(&line.text[range]);
+                    Some(SearchMatch { query, text })
+                }),
+        );
+    }
+
+    matches
+}
+
+/// Returns the byte length of the prefix of `haystack` equal to `needle`.
+fn prefix_len(haystack: &str, needle: &str, case_sensitive: bool) -> Option<usize> {
+    if case_sensitive {
+        return haystack
+            .starts_with(needle)
+            .then_some(needle.len());
+    }
+
+    let mut haystack_chars = haystack.char_indices();
+    for n in needle.chars() {
+        let (_, h) = haystack_chars.next()?;
+        if h != n && !h.to_lowercase().eq(n.to_lowercase()) {
+            return None;
+        }
+    }
+
+    Some(
+        haystack_chars
+            .next()
+            .map_or(haystack.len(), |(offset, _)| offset),
+    )
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+fn is_whole_word(line: &str, range: &Range<usize>) -> bool {
+    let before = line[..range.start].chars().next_back();
+    let after = line[range.end..].chars().next();
+    !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+}
+
+fn next_char_len(line: &str, pos: usize) -> usize {
+    line[pos..]
+        .chars()
+        .next()
+        .map_or(1, char::len_utf8)
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, TerminalSize};
+
+    use super::*;
+    use crate::{CellData, StaticGlyphLookup};
+
+    fn model_with_cells(cols: u16, rows: u16, cells: &[&str]) -> GridModel<StaticGlyphLookup> {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        let mut model = GridModel::new(lookup, TerminalSize::new(cols, rows));
+        model
+            .update_cells(
+                cells
+                    .iter()
+                    .map(|s| CellData::new_with_style_bits(s, 0, 0xFFFFFF, 0)),
+            )
+            .unwrap();
+        model
+    }
+
+    fn model_with_text(cols: u16, rows: u16, text: &str) -> GridModel<StaticGlyphLookup> {
+        let symbols: Vec<String> = text.chars().map(String::from).collect();
+        let cells: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        model_with_cells(cols, rows, &cells)
+    }
+
+    fn found(model: &GridModel<StaticGlyphLookup>, query: &SearchQuery) -> Vec<String> {
+        search(model, query)
+            .into_iter()
+            .map(|m| m.text.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn plain_search_honors_case() {
+        let model = model_with_text(12, 1, "Foo foo FOO");
+
+        let insensitive = SearchQuery::plain("foo", SearchOptions::default());
+        assert_eq!(found(&model, &insensitive), vec!["Foo", "foo", "FOO"]);
+
+        let options = SearchOptions { case_sensitive: true, ..SearchOptions::default() };
+        let sensitive = SearchQuery::plain("foo", options);
+        let matches = search(&model, &sensitive);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(model.get_text(matches[0].query), "foo");
+
+        assert!(search(&model, &SearchQuery::plain("", SearchOptions::default())).is_empty());
+    }
+
+    #[test]
+    fn whole_word_skips_partial_matches() {
+        let model = model_with_text(20, 1, "cat concat cat_ cat.");
+
+        let options = SearchOptions { whole_word: true, ..SearchOptions::default() };
+        let matches = search(&model, &SearchQuery::plain("cat", options));
+
+        let starts: Vec<_> = matches.iter().map(|m| m.query.start).collect();
+        assert_eq!(starts, vec![Some((0, 0)), Some((16, 0))]);
+    }
+
+    #[test]
+    fn matches_span_wrapped_rows() {
+        let mut model = model_with_text(4, 3, "ab  hello   ");
+        assert!(
+            search(
+                &model,
+                &SearchQuery::plain("hello", SearchOptions::default())
+            )
+            .is_empty()
+        );
+
+        model.set_line_wrapped(1, true);
+        let matches = search(
+            &model,
+            &SearchQuery::plain("hello", SearchOptions::default()),
+        );
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].query.start, Some((0, 1)));
+        assert_eq!(matches[0].query.end, Some((0, 2)));
+        assert_eq!(model.get_text(matches[0].query), "hell\no");
+    }
+
+    #[test]
+    fn double_width_cells_are_covered_by_matches() {
+        let model = model_with_cells(6, 1, &["a", "🚀", " ", "b", "c", "d"]);
+
+        let matches = search(&model, &SearchQuery::plain("🚀b", SearchOptions::default()));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].query.start, Some((1, 0)));
+        assert_eq!(matches[0].query.end, Some((3, 0)));
+
+        let matches = search(&model, &SearchQuery::plain("c", SearchOptions::default()));
+        assert_eq!(matches[0].query.start, Some((4, 0)));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_search() {
+        let model = model_with_text(24, 1, "v1.2 V10.3 build v2");
+
+        let query = SearchQuery::regex(r"v\d+(\.\d+)?", SearchOptions::default()).unwrap();
+        assert_eq!(found(&model, &query), vec!["v1.2", "V10.3", "v2"]);
+
+        let options = SearchOptions { case_sensitive: true, whole_word: true };
+        let query = SearchQuery::regex(r"v\d", options).unwrap();
+        assert_eq!(found(&model, &query), vec!["v1", "v2"]);
+
+        assert!(SearchQuery::regex("(", SearchOptions::default()).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 // uniforms
 uniform mediump sampler2DArray u_sampler;
+uniform uint u_pass; // 0 = combined, 1 = backgrounds only, 2 = text only
 layout(std140) uniform FragUbo {
     vec2 u_padding_frac;             // padding as fraction of cell size
     float u_underline_pos;           // underline position (0.0 = top, 1.0 = bottom)
@@ -8,11 +9,30 @@ layout(std140) uniform FragUbo {
     float u_strikethrough_thickness; // strikethrough thickness as fraction of cell height
     uint u_emoji_bit;        // static atlas: 12, dynamic atlas: 15
     float u_bg_alpha;        // background cell opacity (0.0 = transparent, 1.0 = opaque)
+    vec3 u_cursor_color;     // cursor color
+    uint u_cursor_shape;     // 0 = hidden, 1 = block, 2 = bar, 3 = underline, 4 = hollow block
+    uvec2 u_cursor_pos;      // cursor position in grid coordinates
+    uint u_cursor_blink_ms;  // duration of each blink phase; 0 = steady
+    uint u_cursor_epoch_ms;  // clock value at which the blink cycle started
+    uint u_time_ms;          // animation clock in milliseconds
+    uint u_text_blink_ms;    // duration of each blink phase of text; 0 = steady
+    uvec2 u_selection_start; // first selected cell in grid coordinates
+    vec3 u_selection_bg;     // selection highlight color
+    float u_selection_alpha; // opacity of the selection highlight
+    vec3 u_selection_fg;     // text color of selected cells
+    uint u_selection_colors; // 0 = keep text color, 1 = use u_selection_fg, 2 = invert
+    uvec2 u_selection_end;   // last selected cell in grid coordinates
+    uint u_selection_mode;   // 0 = none, 1 = linear, 2 = block
 };
 
 flat in uint v_glyph_index;
 flat in vec3 v_fg_color;
 flat in vec3 v_bg_color;
+flat in vec3 v_underline_color;
+flat in uint v_cell_flags; // bits 0-2: underline style, bit 3: underline color,
+                           // bit 4: dim, bit 5: blink, bit 6: hidden, bit 7: reverse,
+                           // bits 8-9: line attribute
+flat in uvec2 v_grid_pos;
 in vec2 v_tex_coord;
 
 out vec4 FragColor;
@@ -21,28 +41,137 @@ float horizontal_line(vec2 tex_coord, float center, float thickness) {
     return 1.0 - smoothstep(0.0, thickness, abs(tex_coord.y - center));
 }
 
+// underline coverage for the given style:
+// 0 = single, 1 = double, 2 = curly, 3 = dotted, 4 = dashed.
+// patterns repeat once per cell so that they connect across adjacent cells.
+float underline(vec2 tex_coord, uint style) {
+    float center = u_underline_pos;
+    float thickness = u_underline_thickness;
+
+    if (style == 1u) { // double
+        return max(
+            horizontal_line(tex_coord, center - 1.5 * thickness, thickness),
+            horizontal_line(tex_coord, center + 1.5 * thickness, thickness)
+        );
+    } else if (style == 2u) { // curly
+        float wave = center + thickness * sin(tex_coord.x * 6.2831853);
+        return horizontal_line(tex_coord, wave, thickness);
+    } else if (style == 3u) { // dotted
+        float dots = 1.0 - step(0.5, fract(tex_coord.x * 4.0));
+        return horizontal_line(tex_coord, center, thickness) * dots;
+    } else if (style == 4u) { // dashed
+        float dashes = 1.0 - step(0.75, fract(tex_coord.x * 2.0));
+        return horizontal_line(tex_coord, center, thickness) * dashes;
+    }
+
+    return horizontal_line(tex_coord, center, thickness);
+}
+
+// maps the cell-local coordinate to the glyph coordinate; double-height rows
+// sample the top or bottom half of a glyph stretched across two rows
+vec2 glyph_coord(vec2 tex_coord) {
+    uint line_attribute = (v_cell_flags >> 8u) & 0x3u;
+    if (line_attribute == 2u) { // double-height top
+        return vec2(tex_coord.x, tex_coord.y * 0.5);
+    } else if (line_attribute == 3u) { // double-height bottom
+        return vec2(tex_coord.x, 0.5 + tex_coord.y * 0.5);
+    }
+
+    return tex_coord;
+}
+
+// 0.0 if the glyph and its decorations are concealed or in the off phase
+// of blinking, 1.0 otherwise
+float text_visibility() {
+    bool hidden = (v_cell_flags & 0x40u) != 0u;
+    bool blink = (v_cell_flags & 0x20u) != 0u && u_text_blink_ms > 0u;
+    bool blink_off = blink && ((u_time_ms / max(u_text_blink_ms, 1u)) & 1u) == 1u;
+    return float(!(hidden || blink_off));
+}
+
+// whether the cell lies within the selection; linear selections run from
+// start to end in reading order, block selections span the rectangle between them
+bool is_selected() {
+    uvec2 pos = v_grid_pos;
+    if (u_selection_mode == 2u) {
+        return all(greaterThanEqual(pos, u_selection_start)) && all(lessThanEqual(pos, u_selection_end));
+    } else if (u_selection_mode == 1u) {
+        bool after_start = pos.y > u_selection_start.y
+            || (pos.y == u_selection_start.y && pos.x >= u_selection_start.x);
+        bool before_end = pos.y < u_selection_end.y
+            || (pos.y == u_selection_end.y && pos.x <= u_selection_end.x);
+        return after_start && before_end;
+    }
+
+    return false;
+}
+
+// cursor coverage of the current fragment; 0.0 outside the cursor cell,
+// while hidden, or during the off phase of a blink cycle.
+// `px` is the size of one pixel in cell-local coordinates.
+float cursor_coverage(vec2 tex_coord, vec2 px) {
+    if (u_cursor_shape == 0u || v_grid_pos != u_cursor_pos) {
+        return 0.0;
+    }
+
+    // unsigned subtraction keeps the phase stable across clock wraparound
+    uint elapsed = u_time_ms - u_cursor_epoch_ms;
+    if (u_cursor_blink_ms > 0u && ((elapsed / u_cursor_blink_ms) & 1u) == 1u) {
+        return 0.0;
+    }
+
+    if (u_cursor_shape == 2u) { // bar
+        return 1.0 - step(max(px.x, 0.125), tex_coord.x);
+    } else if (u_cursor_shape == 3u) { // underline
+        return step(1.0 - max(px.y, 0.1), tex_coord.y);
+    } else if (u_cursor_shape == 4u) { // hollow block
+        vec2 border = max(px, vec2(0.06));
+        vec2 inside = step(border, tex_coord) * step(tex_coord, 1.0 - border);
+        return 1.0 - inside.x * inside.y;
+    }
+
+    return 1.0; // block
+}
+
 void main() {
     uint glyph_index = v_glyph_index;
 
+    // derivatives must be computed in uniform control flow
+    vec2 px = fwidth(v_tex_coord);
+    float cursor = cursor_coverage(v_tex_coord, px);
+    float block_cursor = cursor * float(u_cursor_shape == 1u);
+
     // texture position from sequential index (32 glyphs per layer)
     uint layer = (glyph_index & 0x1FFFu) >> 5u;
     uint pos_in_layer = glyph_index & 0x1Fu;
 
     // apply strikethrough or underline if the glyph has either bit set
-    float line_alpha = max(
-        horizontal_line(v_tex_coord, u_underline_pos, u_underline_thickness) * float((glyph_index >> 13u) & 0x1u),
-        horizontal_line(v_tex_coord, u_strikethrough_pos, u_strikethrough_thickness) * float((glyph_index >> 14u) & 0x1u)
-    );
+    vec2 glyph_tex_coord = glyph_coord(v_tex_coord);
+    float visibility = text_visibility();
+    float underline_alpha = underline(glyph_tex_coord, v_cell_flags & 0x7u)
+        * float((glyph_index >> 13u) & 0x1u) * visibility;
+    float strikethrough_alpha = horizontal_line(glyph_tex_coord, u_strikethrough_pos, u_strikethrough_thickness)
+        * float((glyph_index >> 14u) & 0x1u) * visibility;
 
-    vec2 inner_tex_coord = v_tex_coord * (1.0 - 2.0 * u_padding_frac) + u_padding_frac;
+    vec2 inner_tex_coord = glyph_tex_coord * (1.0 - 2.0 * u_padding_frac) + u_padding_frac;
     vec3 tex_coord = vec3(
         inner_tex_coord.x + 0.001,
         (float(pos_in_layer) + inner_tex_coord.y + 0.001) * 0.03125, // 0.03125 == 1/32
         float(layer)
     );
 
-    // the base foreground color is used for normal glyphs and underlines/strikethroughs
-    vec3 base_fg = v_fg_color;
+    // selected cells are highlighted without modifying the cell data
+    float selected = float(is_selected());
+    float selection_alpha = selected * u_selection_alpha;
+    bool inverted = u_selection_colors == 2u;
+    vec3 selection_bg = inverted ? v_fg_color : u_selection_bg;
+    vec3 selection_fg = inverted ? v_bg_color : u_selection_fg;
+    vec3 cell_bg = mix(v_bg_color, selection_bg, inverted ? selected : selection_alpha);
+    vec3 cell_fg = mix(v_fg_color, selection_fg, selected * float(u_selection_colors != 0u));
+
+    // the base foreground color is used for normal glyphs and underlines/strikethroughs;
+    // a block cursor draws the glyph using the cell's background color
+    vec3 base_fg = mix(cell_fg, cell_bg, block_cursor);
 
     vec4 glyph = texture(u_sampler, tex_coord);
 
@@ -55,12 +184,29 @@ void main() {
 
     // if we're drawing a line, blend it with the base foreground color.
     // this allows us to do strikethroughs and underlines on emojis with
-    // the same color as the base foreground.
-    fg = mix(fg, base_fg, line_alpha);
+    // the same color as the base foreground. underlines may instead use
+    // their own color.
+    vec3 underline_color = mix(base_fg, v_underline_color, float((v_cell_flags >> 3u) & 0x1u));
+    fg = mix(fg, base_fg, strikethrough_alpha);
+    fg = mix(fg, underline_color, underline_alpha);
+
+    float a = max(glyph.a * visibility, max(underline_alpha, strikethrough_alpha));
+    vec3 bg = mix(cell_bg, u_cursor_color, block_cursor);
 
-    float a = max(glyph.a, line_alpha);
-    vec3 bg = v_bg_color;
+    // the highlight stays visible over translucent backgrounds
+    float bg_alpha = max(u_bg_alpha, selection_alpha);
+    float cell_alpha = mix(bg_alpha, 1.0, max(a, block_cursor));
 
-    float cell_alpha = mix(u_bg_alpha, 1.0, a);
-    FragColor = vec4(mix(bg, fg, a), cell_alpha);
+    // non-block cursor shapes are drawn on top of the cell
+    float cursor_overlay = cursor - block_cursor;
+    if (u_pass == 1u) {
+        // images below the text are drawn between the background and text passes
+        FragColor = vec4(bg, mix(bg_alpha, 1.0, block_cursor));
+    } else if (u_pass == 2u) {
+        float text_alpha = max(a, cursor_overlay);
+        FragColor = vec4(mix(fg, u_cursor_color, cursor_overlay), text_alpha);
+    } else {
+        vec3 color = mix(mix(bg, fg, a), u_cursor_color, cursor_overlay);
+        FragColor = vec4(color, max(cell_alpha, cursor_overlay));
+    }
 }
@@ -4,20 +4,34 @@ layout(location = 1) in vec2 a_tex_coord;
 
 // instance attributes
 layout(location = 2) in uvec2 a_instance_pos;
-layout(location = 3) in uvec2 a_packed_data;
+layout(location = 3) in uvec3 a_packed_data;
+layout(location = 4) in uint a_line_attribute; // advances once per row
 
 // uniforms
 layout(std140) uniform VertUbo {
     mat4 u_projection;
-    vec2 u_cell_size; // unpadded cell size in pixels
+    vec2 u_cell_size;     // unpadded cell size in pixels
+    vec2 u_scroll_offset; // sub-cell scroll offset in cells
+    float u_minimum_contrast; // minimum WCAG contrast ratio; 1.0 = disabled
+    uint u_grid_cols;         // number of columns of the terminal grid
+};
+
+layout(std140) uniform PaletteUbo {
+    vec4 u_palette[258]; // 256 indexed colors, default fg, default bg
 };
 
 // pass glyph index and pre-extracted colors to fragment shader
 flat out uint v_glyph_index;
 flat out vec3 v_fg_color;
 flat out vec3 v_bg_color;
+flat out vec3 v_underline_color;
+flat out uint v_cell_flags;
+flat out uvec2 v_grid_pos;
 out vec2 v_tex_coord;
 
+// foreground blend factor towards the background for dim cells
+const float DIM_FACTOR = 0.5;
+
 // extract byte at position (0=low, 3=high)
 float extract_byte(uint value, uint byte_pos) {
     uint mask = 0xFFu << (byte_pos * 8u);
@@ -25,26 +39,129 @@ float extract_byte(uint value, uint byte_pos) {
     return float(masked >> (byte_pos * 8u)) / 255.0;
 }
 
+// resolves a 9-bit palette slot stored in the low bits of a color
+vec3 palette_color(uint slot) {
+    return u_palette[slot & 0x1FFu].rgb;
+}
+
+// relative luminance of an sRGB color, as defined by WCAG
+float luminance(vec3 color) {
+    vec3 linear = mix(
+        color / 12.92,
+        pow((color + 0.055) / 1.055, vec3(2.4)),
+        step(0.04045, color)
+    );
+    return dot(linear, vec3(0.2126, 0.7152, 0.0722));
+}
+
+float contrast_ratio(float l1, float l2) {
+    return (max(l1, l2) + 0.05) / (min(l1, l2) + 0.05);
+}
+
+// moves the foreground towards white or black, whichever can contrast more
+// with the background, until it reaches the minimum contrast ratio
+vec3 ensure_contrast(vec3 fg, vec3 bg) {
+    float bg_luminance = luminance(bg);
+    if (contrast_ratio(luminance(fg), bg_luminance) >= u_minimum_contrast) {
+        return fg;
+    }
+
+    bool towards_white = contrast_ratio(1.0, bg_luminance) >= contrast_ratio(0.0, bg_luminance);
+    vec3 target = towards_white ? vec3(1.0) : vec3(0.0);
+
+    // binary search for the smallest sufficient blend factor
+    float lo = 0.0;
+    float hi = 1.0;
+    for (int i = 0; i < 8; i++) {
+        float mid = 0.5 * (lo + hi);
+        if (contrast_ratio(luminance(mix(fg, target, mid)), bg_luminance) >= u_minimum_contrast) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    return mix(fg, target, hi);
+}
+
 void main() {
     v_tex_coord = a_tex_coord;
+    // the position buffer starts with a margin row above the grid; it wraps
+    // around here and never matches a grid position
+    v_grid_pos = a_instance_pos - uvec2(0u, 1u);
     v_glyph_index = a_packed_data.x & 0xFFFFu;
 
-    // extract colors in vertex shader to avoid ANGLE fragment shader bugs
-    v_fg_color = vec3(
-        extract_byte(a_packed_data.x, 2u),
-        extract_byte(a_packed_data.x, 3u),
-        extract_byte(a_packed_data.y, 0u)
-    );
-    v_bg_color = vec3(
-        extract_byte(a_packed_data.y, 1u),
-        extract_byte(a_packed_data.y, 2u),
-        extract_byte(a_packed_data.y, 3u)
+    // the underline code is the underline style plus five times the color layout:
+    // 0 = underline in fg color, palette flags in byte 8
+    // 1 = custom underline color, rgb colors only
+    // 2 = custom underline color, palette flags in byte 7
+    uint flags = a_packed_data.z >> 24u;
+    uint underline_code = flags & 0xFu;
+    uint color_layout = underline_code / 5u;
+    uint palette_flags = color_layout == 0u
+        ? a_packed_data.z & 0x3u
+        : (color_layout == 2u ? (a_packed_data.y >> 24u) & 0x3u : 0u);
+    // layout 2 keeps a lone palette fg in the bg bytes, as byte 7 holds the flags
+    bool swapped = color_layout == 2u && palette_flags == 1u;
+
+    // extract colors in vertex shader to avoid ANGLE fragment shader bugs;
+    // palette colors store their slot in the red and green bytes
+    vec3 color_a = ((palette_flags & 0x1u) != 0u && !swapped)
+        ? palette_color(a_packed_data.x >> 16u)
+        : vec3(
+            extract_byte(a_packed_data.x, 2u),
+            extract_byte(a_packed_data.x, 3u),
+            extract_byte(a_packed_data.y, 0u)
+        );
+    vec3 color_b = ((palette_flags & 0x2u) != 0u || swapped)
+        ? palette_color(a_packed_data.y >> 8u)
+        : vec3(
+            extract_byte(a_packed_data.y, 1u),
+            extract_byte(a_packed_data.y, 2u),
+            extract_byte(a_packed_data.y, 3u)
+        );
+    v_fg_color = swapped ? color_b : color_a;
+    v_bg_color = swapped ? color_a : color_b;
+    v_underline_color = vec3(
+        extract_byte(a_packed_data.z, 0u),
+        extract_byte(a_packed_data.z, 1u),
+        extract_byte(a_packed_data.z, 2u)
     );
 
+    // 0 = single, 1 = double-width, 2 = double-height top, 3 = double-height bottom
+    uint line_attribute = a_line_attribute & 0x3u;
+    // underline style in bits 0-2, custom underline color in bit 3, attributes in bits 4-7
+    v_cell_flags = (underline_code % 5u)
+        | (uint(color_layout != 0u) << 3u)
+        | (flags & 0xF0u)
+        | (line_attribute << 8u);
+
+    // reverse video and dim attributes only affect the colors
+    if ((v_cell_flags & 0x80u) != 0u) {
+        vec3 fg = v_fg_color;
+        v_fg_color = v_bg_color;
+        v_bg_color = fg;
+    }
+    if ((v_cell_flags & 0x10u) != 0u) {
+        v_fg_color = mix(v_fg_color, v_bg_color, DIM_FACTOR);
+    }
+
+    // only affects glyphs drawn in the foreground color; emoji keep their texture colors
+    if (u_minimum_contrast > 1.0) {
+        v_fg_color = ensure_contrast(v_fg_color, v_bg_color);
+    }
+
+    // double-width and double-height rows scale the cells to twice their width;
+    // cells that no longer fit the row are collapsed into degenerate quads
+    float width_scale = line_attribute == 0u ? 1.0 : 2.0;
+    bool overflows = line_attribute != 0u && 2u * a_instance_pos.x + 2u > u_grid_cols;
+    vec2 pos = overflows ? vec2(0.0) : a_pos * vec2(width_scale, 1.0);
+
+    vec2 grid_pos = vec2(a_instance_pos) * vec2(width_scale, 1.0) - vec2(0.0, 1.0) + u_scroll_offset;
     vec2 offset = vec2(
-        floor(float(a_instance_pos.x) * u_cell_size.x + 0.5), // pixel-snapped
-        floor(float(a_instance_pos.y) * u_cell_size.y + 0.5)  // pixel-snapped
+        floor(grid_pos.x * u_cell_size.x + 0.5), // pixel-snapped
+        floor(grid_pos.y * u_cell_size.y + 0.5)  // pixel-snapped
     );
 
-    gl_Position = u_projection * vec4(a_pos + offset, 0.0, 1.0);
+    gl_Position = u_projection * vec4(pos + offset, 0.0, 1.0);
 }
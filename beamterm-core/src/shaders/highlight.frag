@@ -0,0 +1,7 @@
+flat in vec4 v_color; // tint color with non-premultiplied alpha
+
+out vec4 FragColor;
+
+void main() {
+    FragColor = v_color;
+}
@@ -0,0 +1,22 @@
+// instance attributes
+layout(location = 0) in vec3 a_cells; // column, row and length of the run, in cells
+layout(location = 1) in vec4 a_color; // tint color with non-premultiplied alpha
+
+// uniforms
+uniform mat4 u_projection;
+uniform vec2 u_cell_size;     // cell size in pixels
+uniform vec2 u_scroll_offset; // sub-cell scroll offset in cells
+
+flat out vec4 v_color;
+
+void main() {
+    // triangle strip corners: top-left, top-right, bottom-left, bottom-right
+    vec2 corner = vec2(float(gl_VertexID & 1), float(gl_VertexID >> 1));
+
+    // pixel-snapped, like the cells
+    vec2 origin = floor((a_cells.xy + u_scroll_offset) * u_cell_size + 0.5);
+    vec2 size = vec2(a_cells.z, 1.0) * u_cell_size;
+    v_color = a_color;
+
+    gl_Position = u_projection * vec4(origin + corner * size, 0.0, 1.0);
+}
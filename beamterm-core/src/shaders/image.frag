@@ -0,0 +1,10 @@
+// uniforms
+uniform mediump sampler2D u_image;
+
+in vec2 v_tex_coord;
+
+out vec4 FragColor;
+
+void main() {
+    FragColor = texture(u_image, v_tex_coord);
+}
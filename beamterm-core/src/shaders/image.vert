@@ -0,0 +1,14 @@
+// uniforms
+uniform mat4 u_projection;
+uniform vec4 u_rect;        // x, y, width, height in pixels
+uniform vec4 u_tex_rect;    // visible part of the image: origin and extent, cropped at the grid edges
+
+out vec2 v_tex_coord;
+
+void main() {
+    // triangle strip corners: top-left, top-right, bottom-left, bottom-right
+    vec2 corner = vec2(float(gl_VertexID & 1), float(gl_VertexID >> 1));
+    v_tex_coord = u_tex_rect.xy + corner * u_tex_rect.zw;
+
+    gl_Position = u_projection * vec4(u_rect.xy + corner * u_rect.zw, 0.0, 1.0);
+}
@@ -0,0 +1,318 @@
+use beamterm_data::{
+    CellSnapshot, CursorSnapshot, Glyph, GridSnapshot, RowSnapshot, SelectionSnapshot,
+};
+use beamterm_unicode::{is_double_width, is_emoji};
+use compact_str::CompactString;
+
+use crate::{
+    CellAttributes, CellColor, CellData, CellDynamic, Cursor, CursorPosition, CursorShape,
+    GlyphLookup, GridModel, LineAttribute, SelectionMode, UnderlineStyle, select,
+};
+
+/// Captures the live screen of `grid` in a [`GridSnapshot`], together with
+/// its selection.
+///
+/// The snapshot has no cursor, since the cursor belongs to the
+/// [`TerminalGrid`](crate::TerminalGrid); see
+/// [`TerminalGrid::snapshot`](crate::TerminalGrid::snapshot). Hyperlinks and
+/// the scrollback history are not captured.
+#[must_use]
+pub fn snapshot_grid<A: GlyphLookup>(grid: &GridModel<A>) -> GridSnapshot {
+    let size = grid.terminal_size();
+    let cols = size.cols as usize;
+
+    let mut cells = Vec::with_capacity(grid.cell_count());
+    let mut wide_glyph: Option<u16> = None;
+    for idx in 0..grid.cell_count() {
+        let cell = grid.live_cell(idx);
+        let glyph_id = cell.glyph_id();
+
+        // the right half of a double-width glyph is restored along with its left half
+        let is_right_half = idx % cols != 0
+            && wide_glyph
+                .take()
+                .is_some_and(|left| glyph_id == left.wrapping_add(1));
+        let symbol = if is_right_half {
+            CompactString::const_new(" ")
+        } else {
+            let symbol = grid
+                .live_symbol(idx)
+                .filter(|symbol| !symbol.is_empty())
+                .unwrap_or_else(|| CompactString::const_new(" "));
+            if is_double_width(&symbol) || is_emoji(&symbol) {
+                wide_glyph = Some(glyph_id);
+            }
+            symbol
+        };
+
+        cells.push(cell_snapshot(symbol, &cell));
+    }
+
+    let rows = (0..size.rows)
+        .map(|row| RowSnapshot {
+            line_attribute: grid.line_attribute(row).shader_line_attribute(),
+            wrapped: grid.is_line_wrapped(row),
+        })
+        .collect();
+
+    let selection = grid
+        .selection_tracker()
+        .get_query()
+        .and_then(|query| {
+            Some(SelectionSnapshot {
+                mode: selection_mode_ordinal(query.mode),
+                start: query.start?,
+                end: query.end?,
+                trim_trailing_whitespace: query.trim_trailing_whitespace,
+            })
+        });
+
+    GridSnapshot { size, cells, rows, cursor: None, selection }
+}
+
+/// Restores the cells, row attributes and selection of a [`GridSnapshot`]
+/// to the live screen of `grid`.
+///
+/// Glyphs are resolved by their symbols, so the snapshot may have been taken
+/// with a different font atlas. If the grid size differs from the snapshot,
+/// the snapshot is anchored at the top-left corner: cells outside the grid
+/// are dropped and cells not covered by the snapshot are cleared.
+pub fn restore_grid<A: GlyphLookup>(grid: &mut GridModel<A>, snapshot: &GridSnapshot) {
+    let size = grid.terminal_size();
+    let blank = CellData::new_with_style_bits(" ", 0, 0, 0)
+        .with_fg(CellColor::DefaultFg)
+        .with_bg(CellColor::DefaultBg);
+
+    let snapshot_cols = snapshot.size.cols as usize;
+    let cells = (0..grid.cell_count()).map(|idx| {
+        let (col, row) = (idx % size.cols as usize, idx / size.cols as usize);
+        let cell = (col < snapshot_cols)
+            .then(|| snapshot.cells.get(row * snapshot_cols + col))
+            .flatten()
+            .map_or(blank, cell_data);
+        (idx, cell)
+    });
+    // infallible; see `update_cells_by_index`
+    let _ = grid.update_cells_by_index(cells);
+
+    for row in 0..size.rows {
+        let attributes = snapshot
+            .rows
+            .get(row as usize)
+            .copied()
+            .unwrap_or_default();
+        let line_attribute = LineAttribute::from_shader_line_attribute(attributes.line_attribute);
+        grid.set_line_attribute(row, line_attribute);
+        grid.set_line_wrapped(row, attributes.wrapped);
+    }
+
+    let selection = grid.selection_tracker();
+    match snapshot.selection {
+        Some(snapshot) => {
+            let query = select(selection_mode_from_ordinal(snapshot.mode))
+                .start(snapshot.start)
+                .end(snapshot.end)
+                .trim_trailing_whitespace(snapshot.trim_trailing_whitespace);
+            selection.set_query(query);
+            selection.set_content_hash(grid.hash_cells(query));
+        },
+        None => selection.clear(),
+    }
+}
+
+pub(crate) fn cursor_snapshot(cursor: &Cursor) -> CursorSnapshot {
+    CursorSnapshot {
+        col: cursor.position.col,
+        row: cursor.position.row,
+        shape: match cursor.shape {
+            CursorShape::Block => 0,
+            CursorShape::Bar => 1,
+            CursorShape::Underline => 2,
+            CursorShape::HollowBlock => 3,
+        },
+        color: cursor.color,
+        blink_interval_ms: cursor.blink_interval_ms,
+        visible: cursor.visible,
+    }
+}
+
+pub(crate) fn cursor_from_snapshot(cursor: &CursorSnapshot) -> Cursor {
+    Cursor {
+        position: CursorPosition { col: cursor.col, row: cursor.row },
+        shape: match cursor.shape {
+            1 => CursorShape::Bar,
+            2 => CursorShape::Underline,
+            3 => CursorShape::HollowBlock,
+            _ => CursorShape::Block,
+        },
+        color: cursor.color,
+        blink_interval_ms: cursor.blink_interval_ms,
+        visible: cursor.visible,
+    }
+}
+
+fn cell_snapshot(symbol: CompactString, cell: &CellDynamic) -> CellSnapshot {
+    // the bold and italic bits are part of the id of emoji glyphs
+    let mut style_bits = cell.get_style();
+    if cell.glyph_id() & Glyph::EMOJI_FLAG != 0 {
+        style_bits &= !(Glyph::BOLD_FLAG | Glyph::ITALIC_FLAG);
+    }
+
+    CellSnapshot {
+        symbol,
+        style_bits,
+        fg: color_snapshot(cell.get_fg()),
+        bg: color_snapshot(cell.get_bg()),
+        underline_style: cell.get_underline_style().shader_style(),
+        underline_color: cell.get_underline_color(),
+        attributes: cell.get_attributes().bits(),
+    }
+}
+
+pub(crate) fn cell_data(cell: &CellSnapshot) -> CellData<'_> {
+    let mut data = CellData::new_with_style_bits(&cell.symbol, cell.style_bits, 0, 0)
+        .with_fg(color_from_snapshot(cell.fg))
+        .with_bg(color_from_snapshot(cell.bg))
+        .with_attributes(CellAttributes::from_bits_truncate(cell.attributes));
+
+    // the underline style only matters for underlined cells; setting it
+    // would underline the cell
+    if cell.style_bits & Glyph::UNDERLINE_FLAG != 0 {
+        data = data.with_underline(UnderlineStyle::from_shader_style(cell.underline_style));
+    }
+    if let Some(color) = cell.underline_color {
+        data = data.with_underline_color(color);
+    }
+
+    data
+}
+
+fn color_snapshot(color: CellColor) -> u32 {
+    match color {
+        CellColor::Rgb(rgb) => rgb & 0xFFFFFF,
+        color => CellSnapshot::PALETTE_COLOR | color.palette_index().unwrap_or_default() as u32,
+    }
+}
+
+fn color_from_snapshot(color: u32) -> CellColor {
+    if color & CellSnapshot::PALETTE_COLOR != 0 {
+        CellColor::from_palette_index((color & 0x1FF) as u16)
+    } else {
+        CellColor::Rgb(color & 0xFFFFFF)
+    }
+}
+
+fn selection_mode_ordinal(mode: SelectionMode) -> u8 {
+    match mode {
+        SelectionMode::Block => 0,
+        SelectionMode::Linear => 1,
+        SelectionMode::Word => 2,
+        SelectionMode::Line => 3,
+    }
+}
+
+fn selection_mode_from_ordinal(mode: u8) -> SelectionMode {
+    match mode {
+        1 => SelectionMode::Linear,
+        2 => SelectionMode::Word,
+        3 => SelectionMode::Line,
+        _ => SelectionMode::Block,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use beamterm_data::{FontAtlasData, TerminalSize};
+
+    use super::*;
+    use crate::{StaticGlyphLookup, gl::CellQuery};
+
+    fn model(cols: u16, rows: u16) -> GridModel<StaticGlyphLookup> {
+        let lookup = StaticGlyphLookup::new(&FontAtlasData::default());
+        GridModel::new(lookup, TerminalSize::new(cols, rows))
+    }
+
+    fn all(cols: u16, rows: u16) -> CellQuery {
+        select(SelectionMode::Linear)
+            .start((0, 0))
+            .end((cols - 1, rows - 1))
+    }
+
+    #[test]
+    fn restoring_a_snapshot_reproduces_the_grid() {
+        let mut original = model(4, 2);
+        let cells = [
+            CellData::new_with_style_bits("a", Glyph::BOLD_FLAG, 0x123456, 0)
+                .with_bg(CellColor::Indexed(4)),
+            CellData::new_with_style_bits("🚀", 0, 0, 0).with_fg(CellColor::DefaultFg),
+            CellData::new_with_style_bits(" ", 0, 0, 0),
+            CellData::new_with_style_bits("b", Glyph::UNDERLINE_FLAG, 0, 0)
+                .with_underline(UnderlineStyle::Curly)
+                .with_underline_color(0xFF0000)
+                .with_attributes(CellAttributes::DIM),
+            CellData::new_with_style_bits("c", 0, 0xFFFFFF, 0x000000),
+            CellData::new_with_style_bits("d", 0, 0xFFFFFF, 0x000000),
+            CellData::new_with_style_bits("e", 0, 0xFFFFFF, 0x000000),
+            CellData::new_with_style_bits("f", 0, 0xFFFFFF, 0x000000),
+        ];
+        original.update_cells(cells.into_iter()).unwrap();
+        original.set_line_attribute(1, LineAttribute::DoubleWidth);
+        original.set_line_wrapped(0, true);
+        original.selection_tracker().set_query(
+            select(SelectionMode::Word)
+                .start((3, 0))
+                .end((1, 1)),
+        );
+
+        let snapshot = snapshot_grid(&original);
+        assert_eq!(snapshot.cells[1].symbol, "🚀");
+        assert_eq!(snapshot.cells[2].symbol, " ");
+
+        let bytes = snapshot.to_binary().unwrap();
+        let mut restored = model(4, 2);
+        restore_grid(&mut restored, &GridSnapshot::from_binary(&bytes).unwrap());
+
+        assert_eq!(snapshot_grid(&restored), snapshot);
+        assert_eq!(restored.get_text(all(4, 2)), original.get_text(all(4, 2)));
+        assert_eq!(restored.line_attribute(1), LineAttribute::DoubleWidth);
+        assert!(restored.is_line_wrapped(0));
+
+        let selection = restored.selection_tracker().get_query().unwrap();
+        assert_eq!(selection.mode, SelectionMode::Word);
+        assert_eq!(
+            (selection.start, selection.end),
+            (Some((3, 0)), Some((1, 1)))
+        );
+    }
+
+    #[test]
+    fn snapshots_are_anchored_at_the_top_left_of_smaller_and_larger_grids() {
+        let mut original = model(3, 2);
+        let cells = "abcdef"
+            .chars()
+            .map(|ch| ch.to_string())
+            .collect::<Vec<_>>();
+        original
+            .update_cells(
+                cells
+                    .iter()
+                    .map(|s| CellData::new_with_style_bits(s, 0, 0xFFFFFF, 0)),
+            )
+            .unwrap();
+        let snapshot = snapshot_grid(&original);
+
+        let mut smaller = model(2, 1);
+        restore_grid(&mut smaller, &snapshot);
+        assert_eq!(smaller.get_text(all(2, 1)), "ab");
+
+        let mut larger = model(4, 3);
+        larger
+            .update_cells(std::iter::repeat_n(
+                CellData::new_with_style_bits("x", 0, 0, 0),
+                12,
+            ))
+            .unwrap();
+        restore_grid(&mut larger, &snapshot);
+        assert_eq!(larger.get_text(all(4, 3)), "abc \ndef \n    ");
+    }
+}
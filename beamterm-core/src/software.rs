@@ -0,0 +1,654 @@
+use beamterm_data::{CellSize, FontAtlasData, Glyph, TerminalSize};
+
+use crate::{
+    gl::{CellAttributes, CellDynamic, LineAttribute, UnderlineStyle},
+    palette::Palette,
+};
+
+/// An 8-bit RGBA image produced by the [`SoftwareRenderer`].
+///
+/// Pixels are stored row-major, top to bottom, with non-premultiplied alpha.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Returns the image width in pixels.
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the image height in pixels.
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the raw RGBA pixel data.
+    #[must_use]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Consumes the image, returning the raw RGBA pixel data.
+    #[must_use]
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Returns the RGBA value of the pixel at `(x, y)`, or `None` if out of bounds.
+    #[must_use]
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let idx = (y as usize * self.width as usize + x as usize) * 4;
+        let mut rgba = [0; 4];
+        rgba.copy_from_slice(&self.pixels[idx..idx + 4]);
+        Some(rgba)
+    }
+}
+
+/// A CPU rasterizer producing the same output as the GL cell shader.
+///
+/// Renders packed [`CellDynamic`] data against a static [`FontAtlasData`]
+/// without a GL context, which makes it suitable for golden-image tests and
+/// snapshots on GPU-less machines. Glyph alpha, emoji texture colors,
+/// underline styles and colors, strikethrough, cell and line attributes,
+/// minimum contrast and background opacity are composited exactly like the
+/// cell shaders. The cursor and inline images are not drawn,
+/// and blinking text is rendered in its visible phase.
+///
+/// The glyph ids in the cells must have been resolved against the same atlas
+/// data, e.g. by a [`TerminalGrid`](crate::TerminalGrid) backed by a
+/// [`StaticFontAtlas`](crate::StaticFontAtlas).
+#[derive(Debug)]
+pub struct SoftwareRenderer {
+    atlas: FontAtlasData,
+    bg_alpha: f32,
+    minimum_contrast: f32,
+    palette: Palette,
+}
+
+impl SoftwareRenderer {
+    /// Creates a software renderer sampling glyphs from `atlas`.
+    #[must_use]
+    pub fn new(atlas: FontAtlasData) -> Self {
+        Self {
+            atlas,
+            bg_alpha: 1.0,
+            minimum_contrast: 1.0,
+            palette: Palette::default(),
+        }
+    }
+
+    /// Sets the palette used to resolve palette colors of the cells.
+    #[must_use]
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Sets the background cell opacity, clamped to `0.0..=1.0`.
+    #[must_use]
+    pub fn with_bg_alpha(mut self, alpha: f32) -> Self {
+        self.bg_alpha = alpha.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the minimum WCAG contrast ratio of text against its background,
+    /// clamped to `1.0..=21.0`; see
+    /// [`TerminalGrid::set_minimum_contrast`](crate::TerminalGrid::set_minimum_contrast).
+    #[must_use]
+    pub fn with_minimum_contrast(mut self, ratio: f32) -> Self {
+        self.minimum_contrast = ratio.clamp(1.0, 21.0);
+        self
+    }
+
+    /// Returns the size of a rendered cell in pixels, excluding atlas padding.
+    #[must_use]
+    pub fn cell_size(&self) -> CellSize {
+        let padded = self.atlas.cell_size();
+        CellSize::new(
+            padded.width - 2 * FontAtlasData::PADDING,
+            padded.height - 2 * FontAtlasData::PADDING,
+        )
+    }
+
+    /// Renders `cells`, laid out row-major in a grid of `size`, into an image
+    /// of `size.cols * cell_width` by `size.rows * cell_height` pixels.
+    ///
+    /// Cells missing from the end of `cells` are left fully transparent.
+    #[must_use]
+    pub fn render(&self, cells: &[CellDynamic], size: TerminalSize) -> RgbaImage {
+        self.render_with_line_attributes(cells, size, &[])
+    }
+
+    /// Renders `cells` like [`render`](Self::render), drawing each row with
+    /// its DEC line attribute, e.g. from
+    /// [`GridModel::viewport_line_attributes`](crate::GridModel::viewport_line_attributes).
+    ///
+    /// Rows missing from the end of `line_attributes` are single-width.
+    #[must_use]
+    pub fn render_with_line_attributes(
+        &self,
+        cells: &[CellDynamic],
+        size: TerminalSize,
+        line_attributes: &[LineAttribute],
+    ) -> RgbaImage {
+        let cell_size = self.cell_size();
+        let (cw, ch) = (
+            cell_size.width.max(1) as u32,
+            cell_size.height.max(1) as u32,
+        );
+        let cols = size.cols as usize;
+
+        let mut image = RgbaImage::new(size.cols as u32 * cw, size.rows as u32 * ch);
+        if cols == 0 {
+            return image;
+        }
+
+        let params =
+            FragmentParams::new(&self.atlas, cell_size, self.bg_alpha, self.minimum_contrast);
+        for (idx, cell) in cells
+            .iter()
+            .take(cols * size.rows as usize)
+            .enumerate()
+        {
+            let col = (idx % cols) as u32;
+            let line_attribute = line_attributes
+                .get(idx / cols)
+                .copied()
+                .unwrap_or_default();
+
+            // double-width rows draw their cells twice as wide, dropping the
+            // cells that no longer fit the row
+            let width_scale = if line_attribute.is_double_width() { 2 } else { 1 };
+            if width_scale == 2 && 2 * col + 2 > size.cols as u32 {
+                continue;
+            }
+
+            let (x0, y0) = (col * width_scale * cw, (idx / cols) as u32 * ch);
+            let quad_width = width_scale * cw;
+
+            for py in 0..ch {
+                for px in 0..quad_width {
+                    // fragment centers, as rasterized by the GPU
+                    let tex_coord =
+                        [(px as f32 + 0.5) / quad_width as f32, (py as f32 + 0.5) / ch as f32];
+
+                    let rgba =
+                        params.shade(&self.atlas, &self.palette, *cell, line_attribute, tex_coord);
+                    let offset = (((y0 + py) * image.width + x0 + px) * 4) as usize;
+                    image.pixels[offset..offset + 4].copy_from_slice(&rgba);
+                }
+            }
+        }
+
+        image
+    }
+}
+
+/// The uniform inputs of the cell shaders, precomputed for a single render.
+struct FragmentParams {
+    padding_frac: [f32; 2],
+    underline: (f32, f32),
+    strikethrough: (f32, f32),
+    bg_alpha: f32,
+    minimum_contrast: f32,
+}
+
+impl FragmentParams {
+    /// Emoji flag of static atlases.
+    const EMOJI_BIT: u16 = 12;
+    /// Foreground blend factor towards the background for dim cells.
+    const DIM_FACTOR: f32 = 0.5;
+
+    fn new(
+        atlas: &FontAtlasData,
+        cell_size: CellSize,
+        bg_alpha: f32,
+        minimum_contrast: f32,
+    ) -> Self {
+        let underline = atlas.underline();
+        let strikethrough = atlas.strikethrough();
+
+        Self {
+            padding_frac: [
+                FontAtlasData::PADDING as f32 / cell_size.width as f32,
+                FontAtlasData::PADDING as f32 / cell_size.height as f32,
+            ],
+            underline: (underline.position(), underline.thickness()),
+            strikethrough: (strikethrough.position(), strikethrough.thickness()),
+            bg_alpha,
+            minimum_contrast,
+        }
+    }
+
+    /// Shades one fragment of `cell` at the cell-local `tex_coord`.
+    fn shade(
+        &self,
+        atlas: &FontAtlasData,
+        palette: &Palette,
+        cell: CellDynamic,
+        line_attribute: LineAttribute,
+        tex_coord: [f32; 2],
+    ) -> [u8; 4] {
+        let glyph_index = cell.glyph_id();
+        let attributes = cell.get_attributes();
+
+        let mut fg_color = rgb_to_vec3(palette.resolve(cell.get_fg()));
+        let mut bg_color = rgb_to_vec3(palette.resolve(cell.get_bg()));
+        if attributes.contains(CellAttributes::REVERSE) {
+            std::mem::swap(&mut fg_color, &mut bg_color);
+        }
+        if attributes.contains(CellAttributes::DIM) {
+            fg_color = std::array::from_fn(|i| mix(fg_color[i], bg_color[i], Self::DIM_FACTOR));
+        }
+        if self.minimum_contrast > 1.0 {
+            fg_color = ensure_contrast(fg_color, bg_color, self.minimum_contrast);
+        }
+        let visibility = if attributes.contains(CellAttributes::HIDDEN) { 0.0 } else { 1.0 };
+
+        // double-height rows show the top or bottom half of a glyph stretched across two rows
+        let tex_coord = match line_attribute {
+            LineAttribute::DoubleHeightTop => [tex_coord[0], tex_coord[1] * 0.5],
+            LineAttribute::DoubleHeightBottom => [tex_coord[0], 0.5 + tex_coord[1] * 0.5],
+            LineAttribute::Single | LineAttribute::DoubleWidth => tex_coord,
+        };
+
+        let layer = (glyph_index & 0x1FFF) >> 5;
+        let pos_in_layer = glyph_index & 0x1F;
+
+        let flag = |mask: u16| if glyph_index & mask != 0 { 1.0 } else { 0.0 };
+        let underline_alpha = self.underline(tex_coord, cell.get_underline_style())
+            * flag(Glyph::UNDERLINE_FLAG)
+            * visibility;
+        let strikethrough_alpha = horizontal_line(tex_coord[1], self.strikethrough)
+            * flag(Glyph::STRIKETHROUGH_FLAG)
+            * visibility;
+        let underline_color = cell
+            .get_underline_color()
+            .map_or(fg_color, rgb_to_vec3);
+
+        let inner_x = tex_coord[0] * (1.0 - 2.0 * self.padding_frac[0]) + self.padding_frac[0];
+        let inner_y = tex_coord[1] * (1.0 - 2.0 * self.padding_frac[1]) + self.padding_frac[1];
+        let glyph = sample_nearest(
+            atlas,
+            inner_x + 0.001,
+            (pos_in_layer as f32 + inner_y + 0.001) * 0.03125,
+            layer as i32,
+        );
+
+        let emoji_factor = f32::from((glyph_index >> Self::EMOJI_BIT) & 1);
+
+        let mut fg = [0.0; 3];
+        let mut color = [0.0; 3];
+        let a = (glyph[3] * visibility).max(underline_alpha.max(strikethrough_alpha));
+        for i in 0..3 {
+            fg[i] = mix(fg_color[i], glyph[i], emoji_factor);
+            fg[i] = mix(fg[i], fg_color[i], strikethrough_alpha);
+            fg[i] = mix(fg[i], underline_color[i], underline_alpha);
+            color[i] = mix(bg_color[i], fg[i], a);
+        }
+        let cell_alpha = mix(self.bg_alpha, 1.0, a);
+
+        [to_unorm8(color[0]), to_unorm8(color[1]), to_unorm8(color[2]), to_unorm8(cell_alpha)]
+    }
+
+    /// Underline coverage of the fragment at `tex_coord` for the given style.
+    fn underline(&self, [x, y]: [f32; 2], style: UnderlineStyle) -> f32 {
+        let (center, thickness) = self.underline;
+
+        match style {
+            UnderlineStyle::Double => f32::max(
+                horizontal_line(y, (center - 1.5 * thickness, thickness)),
+                horizontal_line(y, (center + 1.5 * thickness, thickness)),
+            ),
+            UnderlineStyle::Curly => {
+                let wave = center + thickness * (x * std::f32::consts::TAU).sin();
+                horizontal_line(y, (wave, thickness))
+            },
+            UnderlineStyle::Dotted => {
+                let dots = 1.0 - step(0.5, (x * 4.0).fract());
+                horizontal_line(y, self.underline) * dots
+            },
+            UnderlineStyle::Dashed => {
+                let dashes = 1.0 - step(0.75, (x * 2.0).fract());
+                horizontal_line(y, self.underline) * dashes
+            },
+            UnderlineStyle::Single => horizontal_line(y, self.underline),
+        }
+    }
+}
+
+/// Samples the atlas texture array with nearest filtering and edge clamping.
+fn sample_nearest(atlas: &FontAtlasData, u: f32, v: f32, layer: i32) -> [f32; 4] {
+    let (width, height, layers) = atlas.texture_dimensions();
+    let x = ((u * width as f32).floor() as i32).clamp(0, width - 1);
+    let y = ((v * height as f32).floor() as i32).clamp(0, height - 1);
+    let layer = layer.clamp(0, layers - 1);
+
+    let idx = ((layer * width * height + y * width + x) * 4) as usize;
+    match atlas.texture_data().get(idx..idx + 4) {
+        Some(&[r, g, b, a]) => [r, g, b, a].map(|c| f32::from(c) / 255.0),
+        _ => [0.0; 4],
+    }
+}
+
+/// Relative luminance of an sRGB color, as defined by WCAG.
+fn luminance(color: [f32; 3]) -> f32 {
+    let [r, g, b] =
+        color.map(
+            |c| {
+                if c < 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+            },
+        );
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+fn contrast_ratio(l1: f32, l2: f32) -> f32 {
+    (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+}
+
+/// Moves `fg` towards white or black, whichever can contrast more with `bg`,
+/// until it reaches the contrast `ratio`; mirrors `ensure_contrast` in `cell.vert`.
+fn ensure_contrast(fg: [f32; 3], bg: [f32; 3], ratio: f32) -> [f32; 3] {
+    let bg_luminance = luminance(bg);
+    if contrast_ratio(luminance(fg), bg_luminance) >= ratio {
+        return fg;
+    }
+
+    let towards_white = contrast_ratio(1.0, bg_luminance) >= contrast_ratio(0.0, bg_luminance);
+    let target = if towards_white { 1.0 } else { 0.0 };
+    let blend = |t: f32| fg.map(|c| mix(c, target, t));
+
+    // binary search for the smallest sufficient blend factor
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..8 {
+        let mid = 0.5 * (lo + hi);
+        if contrast_ratio(luminance(blend(mid)), bg_luminance) >= ratio {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    blend(hi)
+}
+
+fn horizontal_line(y: f32, (center, thickness): (f32, f32)) -> f32 {
+    1.0 - smoothstep(0.0, thickness, (y - center).abs())
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn step(edge: f32, x: f32) -> f32 {
+    if x < edge { 0.0 } else { 1.0 }
+}
+
+fn mix(x: f32, y: f32, a: f32) -> f32 {
+    x * (1.0 - a) + y * a
+}
+
+fn rgb_to_vec3(rgb: u32) -> [f32; 3] {
+    [
+        ((rgb >> 16) & 0xFF) as f32 / 255.0,
+        ((rgb >> 8) & 0xFF) as f32 / 255.0,
+        (rgb & 0xFF) as f32 / 255.0,
+    ]
+}
+
+fn to_unorm8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CellColor;
+
+    const FG: u32 = 0xFF_FF_FF;
+    const BG: u32 = 0x10_20_30;
+
+    fn render_one(renderer: &SoftwareRenderer, cell: CellDynamic) -> RgbaImage {
+        renderer.render(&[cell], TerminalSize::new(1, 1))
+    }
+
+    fn count_pixels(image: &RgbaImage, rgba: [u8; 4]) -> usize {
+        image
+            .pixels()
+            .chunks_exact(4)
+            .filter(|px| *px == rgba)
+            .count()
+    }
+
+    #[test]
+    fn image_covers_grid_in_unpadded_cells() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+        let cs = renderer.cell_size();
+
+        let image = renderer.render(&[], TerminalSize::new(3, 2));
+        assert_eq!(image.width(), 3 * cs.width as u32);
+        assert_eq!(image.height(), 2 * cs.height as u32);
+        assert_eq!(image.pixel(0, 0), Some([0, 0, 0, 0]));
+        assert_eq!(image.pixel(image.width(), 0), None);
+    }
+
+    #[test]
+    fn space_renders_background_only() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+        let image = render_one(&renderer, CellDynamic::new(' ' as u16, FG, BG));
+
+        let total = (image.width() * image.height()) as usize;
+        assert_eq!(count_pixels(&image, [0x10, 0x20, 0x30, 0xFF]), total);
+    }
+
+    #[test]
+    fn glyph_pixels_use_foreground_color() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+        let image = render_one(&renderer, CellDynamic::new('#' as u16, FG, BG));
+
+        assert!(count_pixels(&image, [0xFF, 0xFF, 0xFF, 0xFF]) > 0);
+        assert!(count_pixels(&image, [0x10, 0x20, 0x30, 0xFF]) > 0);
+    }
+
+    #[test]
+    fn underline_is_drawn_at_atlas_position() {
+        let atlas = FontAtlasData::default();
+        let underline = atlas.underline();
+        let renderer = SoftwareRenderer::new(atlas);
+
+        let cell = CellDynamic::new(' ' as u16 | Glyph::UNDERLINE_FLAG, FG, BG);
+        let image = render_one(&renderer, cell);
+
+        let y = (underline.position() * image.height() as f32) as u32;
+        let y = y.min(image.height() - 1);
+        assert_eq!(image.pixel(0, y), Some([0xFF, 0xFF, 0xFF, 0xFF]));
+        assert_eq!(image.pixel(0, 0), Some([0x10, 0x20, 0x30, 0xFF]));
+    }
+
+    #[test]
+    fn underline_uses_its_own_color() {
+        let atlas = FontAtlasData::default();
+        let underline = atlas.underline();
+        let renderer = SoftwareRenderer::new(atlas);
+
+        let mut cell = CellDynamic::new(' ' as u16 | Glyph::UNDERLINE_FLAG, FG, BG);
+        cell.underline_color(Some(0xFF_00_00));
+        let image = render_one(&renderer, cell);
+
+        let y = (underline.position() * image.height() as f32) as u32;
+        let y = y.min(image.height() - 1);
+        assert_eq!(image.pixel(0, y), Some([0xFF, 0x00, 0x00, 0xFF]));
+    }
+
+    #[test]
+    fn underline_styles_differ_from_single() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+
+        let render_style = |style: UnderlineStyle| {
+            let mut cell = CellDynamic::new(' ' as u16 | Glyph::UNDERLINE_FLAG, FG, BG);
+            cell.underline_style(style);
+            render_one(&renderer, cell)
+        };
+
+        let blank = render_one(&renderer, CellDynamic::new(' ' as u16, FG, BG));
+        let single = render_style(UnderlineStyle::Single);
+        for style in [
+            UnderlineStyle::Double,
+            UnderlineStyle::Curly,
+            UnderlineStyle::Dotted,
+            UnderlineStyle::Dashed,
+        ] {
+            let image = render_style(style);
+            assert_ne!(image, single, "{style:?}");
+            assert_ne!(image, blank, "{style:?}");
+        }
+    }
+
+    #[test]
+    fn reverse_swaps_colors() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+        let mut cell = CellDynamic::new(' ' as u16, FG, BG);
+        cell.attributes(CellAttributes::REVERSE);
+        let image = render_one(&renderer, cell);
+
+        assert_eq!(image.pixel(0, 0), Some([0xFF, 0xFF, 0xFF, 0xFF]));
+    }
+
+    #[test]
+    fn dim_blends_foreground_towards_background() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+        let mut cell = CellDynamic::new('#' as u16, FG, 0x000000);
+        cell.attributes(CellAttributes::DIM);
+        let image = render_one(&renderer, cell);
+
+        assert_eq!(count_pixels(&image, [0xFF, 0xFF, 0xFF, 0xFF]), 0);
+        assert!(count_pixels(&image, [0x80, 0x80, 0x80, 0xFF]) > 0);
+    }
+
+    #[test]
+    fn hidden_cells_draw_background_only() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+        let mut cell = CellDynamic::new('#' as u16 | Glyph::UNDERLINE_FLAG, FG, BG);
+        cell.attributes(CellAttributes::HIDDEN);
+        let image = render_one(&renderer, cell);
+
+        let total = (image.width() * image.height()) as usize;
+        assert_eq!(count_pixels(&image, [0x10, 0x20, 0x30, 0xFF]), total);
+    }
+
+    #[test]
+    fn palette_colors_are_resolved() {
+        let mut palette = Palette::default();
+        palette.set_color(4, 0x10_20_30);
+        let renderer = SoftwareRenderer::new(FontAtlasData::default()).with_palette(palette);
+
+        let mut cell = CellDynamic::new(' ' as u16, FG, 0);
+        cell.bg(CellColor::Indexed(4));
+        let image = render_one(&renderer, cell);
+        assert_eq!(image.pixel(0, 0), Some([0x10, 0x20, 0x30, 0xFF]));
+
+        cell.bg(CellColor::DefaultBg);
+        let image = render_one(&renderer, cell);
+        assert_eq!(image.pixel(0, 0), Some([0x00, 0x00, 0x00, 0xFF]));
+    }
+
+    #[test]
+    fn minimum_contrast_brightens_dark_text() {
+        const DARK_GREY: u32 = 0x20_20_20;
+        let renderer = SoftwareRenderer::new(FontAtlasData::default()).with_minimum_contrast(4.5);
+        let image = render_one(&renderer, CellDynamic::new('#' as u16, DARK_GREY, 0x000000));
+
+        let brightest = image
+            .pixels()
+            .chunks_exact(4)
+            .map(|px| [px[0], px[1], px[2]].map(|c| f32::from(c) / 255.0))
+            .max_by(|a, b| luminance(*a).total_cmp(&luminance(*b)))
+            .unwrap();
+        assert!(contrast_ratio(luminance(brightest), 0.0) >= 4.4);
+    }
+
+    #[test]
+    fn minimum_contrast_keeps_sufficient_colors() {
+        let fg = rgb_to_vec3(0xFFFFFF);
+        let bg = rgb_to_vec3(0x000000);
+        assert_eq!(ensure_contrast(fg, bg, 7.0), fg);
+
+        let adjusted = ensure_contrast(rgb_to_vec3(0xE0E0E0), rgb_to_vec3(0xF0F0F0), 3.0);
+        assert!(contrast_ratio(luminance(adjusted), luminance(rgb_to_vec3(0xF0F0F0))) >= 3.0);
+    }
+
+    #[test]
+    fn double_width_rows_stretch_cells() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+        let cw = renderer.cell_size().width as u32;
+        let white = [0xFF, 0xFF, 0xFF, 0xFF];
+
+        let mut cells =
+            [CellDynamic::new(' ' as u16, FG, BG), CellDynamic::new('#' as u16, FG, BG)];
+        let render = |cells: &[CellDynamic]| {
+            let line_attributes = [LineAttribute::DoubleWidth];
+            renderer.render_with_line_attributes(cells, TerminalSize::new(2, 1), &line_attributes)
+        };
+
+        // the second cell no longer fits the row
+        let image = render(&cells);
+        assert_eq!(count_pixels(&image, white), 0);
+
+        // the first cell covers both columns
+        cells.swap(0, 1);
+        let image = render(&cells);
+        let right_half = (cw..image.width())
+            .flat_map(|x| (0..image.height()).map(move |y| (x, y)))
+            .filter(|&(x, y)| image.pixel(x, y) == Some(white))
+            .count();
+        assert!(right_half > 0);
+    }
+
+    #[test]
+    fn double_height_rows_show_glyph_halves() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default());
+        let bg = [0x10, 0x20, 0x30, 0xFF];
+
+        let render = |line_attribute| {
+            let cell = CellDynamic::new(' ' as u16 | Glyph::UNDERLINE_FLAG, FG, BG);
+            // double-height cells are double-width, so a single cell fills two columns
+            renderer.render_with_line_attributes(&[cell, cell], TerminalSize::new(2, 1), &[
+                line_attribute,
+            ])
+        };
+
+        // the underline is near the bottom of the glyph, so only the lower row shows it
+        let top = render(LineAttribute::DoubleHeightTop);
+        let bottom = render(LineAttribute::DoubleHeightBottom);
+        let total = (top.width() * top.height()) as usize;
+        assert_eq!(count_pixels(&top, bg), total);
+        assert!(count_pixels(&bottom, bg) < total);
+    }
+
+    #[test]
+    fn bg_alpha_applies_to_background_pixels() {
+        let renderer = SoftwareRenderer::new(FontAtlasData::default()).with_bg_alpha(0.5);
+        let image = render_one(&renderer, CellDynamic::new(' ' as u16, FG, BG));
+
+        assert_eq!(image.pixel(0, 0), Some([0x10, 0x20, 0x30, 0x80]));
+    }
+}
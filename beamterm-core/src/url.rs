@@ -1,6 +1,9 @@
 use compact_str::CompactString;
 
-use crate::{SelectionMode, TerminalGrid, gl::CellQuery, position::CursorPosition, select};
+use crate::{
+    GlyphLookup, GridModel, SelectionMode, TerminalGrid, gl::CellQuery, position::CursorPosition,
+    select,
+};
 
 /// Result of URL detection containing the query and extracted URL text.
 pub struct UrlMatch {
@@ -11,7 +14,7 @@ pub struct UrlMatch {
 }
 
 /// Characters that are valid within a URL (RFC 3986 unreserved + reserved).
-fn is_url_char(ch: char) -> bool {
+pub(crate) fn is_url_char(ch: char) -> bool {
     ch.is_ascii_alphanumeric()
         || matches!(
             ch,
@@ -41,7 +44,7 @@ fn is_url_char(ch: char) -> bool {
 }
 
 /// Characters that should be trimmed from the end of a URL.
-fn is_trailing_punctuation(ch: char) -> bool {
+pub(crate) fn is_trailing_punctuation(ch: char) -> bool {
     matches!(ch, '.' | ',' | ';' | ':' | '!' | '?')
 }
 
@@ -53,6 +56,18 @@ fn is_trailing_punctuation(ch: char) -> bool {
 /// Returns `None` if no URL is found at the cursor position.
 #[must_use]
 pub fn find_url_at_cursor(cursor: CursorPosition, grid: &TerminalGrid) -> Option<UrlMatch> {
+    find_url_at_cursor_in_model(cursor, grid.model())
+}
+
+/// Detects an HTTP/HTTPS URL at or around the given cursor position of a
+/// [`GridModel`].
+///
+/// Like [`find_url_at_cursor`], for grids without a GL context.
+#[must_use]
+pub fn find_url_at_cursor_in_model<A: GlyphLookup>(
+    cursor: CursorPosition,
+    grid: &GridModel<A>,
+) -> Option<UrlMatch> {
     let cols = grid.terminal_size().cols;
 
     // Find scheme start by scanning left
@@ -90,9 +105,9 @@ pub fn find_url_at_cursor(cursor: CursorPosition, grid: &TerminalGrid) -> Option
 }
 
 /// Scans left from the cursor to find the start of a URL scheme.
-fn find_scheme_start(
+fn find_scheme_start<A: GlyphLookup>(
     cursor: CursorPosition,
-    grid: &TerminalGrid,
+    grid: &GridModel<A>,
     cols: u16,
 ) -> Option<CursorPosition> {
     let mut pos = cursor;
@@ -112,7 +127,12 @@ fn find_scheme_start(
 }
 
 /// Checks if a sequence of characters matches starting at the given position.
-fn matches_sequence(grid: &TerminalGrid, start: CursorPosition, seq: &str, cols: u16) -> bool {
+fn matches_sequence<A: GlyphLookup>(
+    grid: &GridModel<A>,
+    start: CursorPosition,
+    seq: &str,
+    cols: u16,
+) -> bool {
     let mut pos = start;
     let char_count = seq.chars().count();
 
@@ -134,7 +154,11 @@ fn matches_sequence(grid: &TerminalGrid, start: CursorPosition, seq: &str, cols:
 /// Scans right from the starting position to find the extent of a URL.
 ///
 /// Returns the end position and the parenthesis balance (positive means more '(' than ')').
-fn scan_url_extent(start: CursorPosition, grid: &TerminalGrid, cols: u16) -> (CursorPosition, i32) {
+fn scan_url_extent<A: GlyphLookup>(
+    start: CursorPosition,
+    grid: &GridModel<A>,
+    cols: u16,
+) -> (CursorPosition, i32) {
     let mut pos = start;
     let mut paren_balance: i32 = 0;
     let mut last_valid = start;
@@ -162,11 +186,11 @@ fn scan_url_extent(start: CursorPosition, grid: &TerminalGrid, cols: u16) -> (Cu
 }
 
 /// Trims trailing punctuation and unbalanced closing parentheses from the URL end.
-fn trim_url_end(
+fn trim_url_end<A: GlyphLookup>(
     start: CursorPosition,
     mut end: CursorPosition,
     mut paren_balance: i32,
-    grid: &TerminalGrid,
+    grid: &GridModel<A>,
 ) -> CursorPosition {
     // Work backwards, trimming trailing punctuation and unbalanced ')'
     while end.col > start.col {
@@ -0,0 +1,412 @@
+use std::{
+    borrow::Cow,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{CellSnapshot, Deserializer, Serializable, SerializationError, TerminalSize};
+
+/// Runs of at least this many identical cells are encoded as a fill.
+const MIN_FILL_LEN: usize = 4;
+/// Unchanged cells between two changed cells are rewritten rather than
+/// starting a new span, as long as there are at most this many of them.
+const MAX_SPAN_GAP: usize = 4;
+
+/// A complete frame of cells, e.g. rendered by a TUI on a server, for
+/// computing a [`FrameDiff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// Dimensions of the frame.
+    pub size: TerminalSize,
+    /// Cells of the frame in row-major order, `cols * rows` entries.
+    pub cells: Vec<CellSnapshot>,
+}
+
+impl Frame {
+    /// Creates a frame of `size` with every cell set to `fill`.
+    #[must_use]
+    pub fn new(size: TerminalSize, fill: CellSnapshot) -> Self {
+        let cells = vec![fill; size.cols as usize * size.rows as usize];
+        Self { size, cells }
+    }
+
+    /// Returns a mutable reference to the cell at (`col`, `row`).
+    pub fn cell_mut(&mut self, col: u16, row: u16) -> Option<&mut CellSnapshot> {
+        if col >= self.size.cols {
+            return None;
+        }
+
+        let idx = row as usize * self.size.cols as usize + col as usize;
+        self.cells.get_mut(idx)
+    }
+
+    fn row(&self, row: usize) -> &[CellSnapshot] {
+        let cols = self.size.cols as usize;
+        &self.cells[row * cols..(row + 1) * cols]
+    }
+}
+
+/// An update operation of a [`FrameDiff`]. Cell indices are row-major.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameOp {
+    /// Resizes the grid. The operations that follow redraw every cell.
+    Resize(TerminalSize),
+    /// Scrolls the rows `top..bottom` by `lines`; positive values move the
+    /// content up. The vacated rows are filled with `fill`.
+    Scroll {
+        /// First row of the scrolled region.
+        top: u16,
+        /// Row after the last row of the scrolled region.
+        bottom: u16,
+        /// Number of rows to scroll by.
+        lines: i32,
+        /// Cell written to the vacated rows.
+        fill: CellSnapshot,
+    },
+    /// Writes consecutive cells, starting at `index`.
+    Cells {
+        /// Index of the first cell.
+        index: u32,
+        /// The cells to write.
+        cells: Vec<CellSnapshot>,
+    },
+    /// Writes `count` copies of `cell`, starting at `index`.
+    Fill {
+        /// Index of the first cell.
+        index: u32,
+        /// Number of cells to write.
+        count: u32,
+        /// The cell to write.
+        cell: CellSnapshot,
+    },
+}
+
+/// Update operations turning one [`Frame`] into the next, for driving a
+/// grid remotely with a compact binary protocol.
+///
+/// The server diffs consecutive frames with [`FrameDiff::between`] and sends
+/// them with [`FrameDiff::to_binary`]; the client decodes them with
+/// [`FrameDiff::from_binary`] and applies them to its grid, e.g. with
+/// `beamterm_core::apply_frame_diff`.
+///
+/// # Example
+/// ```
+/// use beamterm_data::{CellSnapshot, Frame, FrameDiff, TerminalSize};
+///
+/// let blank = CellSnapshot {
+///     symbol: " ".into(),
+///     style_bits: 0,
+///     fg: 0xFFFFFF,
+///     bg: 0x000000,
+///     underline_style: 0,
+///     underline_color: None,
+///     attributes: 0,
+/// };
+/// let previous = Frame::new(TerminalSize::new(80, 24), blank.clone());
+/// let mut next = previous.clone();
+/// next.cell_mut(0, 0).unwrap().symbol = "$".into();
+///
+/// let diff = FrameDiff::between(&previous, &next);
+/// let bytes = diff.to_binary().unwrap();
+/// assert_eq!(FrameDiff::from_binary(&bytes).unwrap(), diff);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameDiff {
+    /// The operations, in the order they are applied.
+    pub ops: Vec<FrameOp>,
+}
+
+impl FrameDiff {
+    /// Computes the operations turning `previous` into `next`.
+    ///
+    /// Changed cells are sent as spans, with runs of identical cells
+    /// run-length encoded as fills. If the content of `next` is the content of
+    /// `previous` moved up or down, a scroll is sent first, so only the
+    /// vacated rows are redrawn. A size change resizes the grid and redraws
+    /// all of `next`.
+    #[must_use]
+    pub fn between(previous: &Frame, next: &Frame) -> Self {
+        let mut ops = Vec::new();
+        let cell_count = next.size.cols as usize * next.size.rows as usize;
+        let next_cells = &next.cells[..cell_count.min(next.cells.len())];
+
+        if previous.size != next.size || previous.cells.len() != next.cells.len() {
+            ops.push(FrameOp::Resize(next.size));
+            push_cells(&mut ops, 0, next_cells);
+            return Self { ops };
+        }
+
+        let mut base = Cow::Borrowed(previous.cells.as_slice());
+        if let Some(lines) = detect_scroll(previous, next) {
+            let fill = if lines > 0 {
+                next.row(next.size.rows as usize - lines as usize)[0].clone()
+            } else {
+                next.cells[0].clone()
+            };
+
+            base = Cow::Owned(scrolled_cells(previous, lines, &fill));
+            ops.push(FrameOp::Scroll { top: 0, bottom: next.size.rows, lines, fill });
+        }
+
+        let is_changed = |idx: usize| base[idx] != next_cells[idx];
+        let mut idx = 0;
+        while idx < next_cells.len() {
+            if !is_changed(idx) {
+                idx += 1;
+                continue;
+            }
+
+            let start = idx;
+            let mut end = idx + 1;
+            loop {
+                // the cell after a replaced non-ASCII symbol may be the right
+                // half of a double-width glyph, which must be redrawn too
+                let glyph_end = if end < next_cells.len() && !base[end - 1].symbol.is_ascii() {
+                    end + 1
+                } else {
+                    end
+                };
+
+                let gap_end = next_cells.len().min(end + MAX_SPAN_GAP + 1);
+                match (end..gap_end).find(|&i| is_changed(i)) {
+                    Some(changed) => end = glyph_end.max(changed + 1),
+                    None => {
+                        end = glyph_end;
+                        break;
+                    },
+                }
+            }
+
+            push_cells(&mut ops, start, &next_cells[start..end]);
+            idx = end;
+        }
+
+        Self { ops }
+    }
+
+    /// Returns `true` if the diff has no operations, i.e. the frames were equal.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Deserializes a frame diff from binary format.
+    ///
+    /// # Errors
+    /// Returns [`SerializationError`] if the binary data is malformed, or was
+    /// written by an incompatible version of the protocol.
+    pub fn from_binary(serialized: &[u8]) -> Result<Self, SerializationError> {
+        let mut deserializer = Deserializer::new(serialized);
+        FrameDiff::deserialize(&mut deserializer)
+    }
+
+    /// Serializes the frame diff to binary format.
+    ///
+    /// # Errors
+    /// Returns [`SerializationError`] if a cell symbol exceeds 255 bytes.
+    pub fn to_binary(&self) -> Result<Vec<u8>, SerializationError> {
+        self.serialize()
+    }
+}
+
+/// Appends operations writing `cells` from `index` on, encoding runs of
+/// identical cells as fills.
+fn push_cells(ops: &mut Vec<FrameOp>, index: usize, cells: &[CellSnapshot]) {
+    let mut span_start = 0;
+    let mut run_start = 0;
+    for run in cells.chunk_by(|a, b| a == b) {
+        if run.len() >= MIN_FILL_LEN {
+            if span_start < run_start {
+                ops.push(FrameOp::Cells {
+                    index: (index + span_start) as u32,
+                    cells: cells[span_start..run_start].to_vec(),
+                });
+            }
+            ops.push(FrameOp::Fill {
+                index: (index + run_start) as u32,
+                count: run.len() as u32,
+                cell: run[0].clone(),
+            });
+            span_start = run_start + run.len();
+        }
+        run_start += run.len();
+    }
+
+    if span_start < cells.len() {
+        ops.push(FrameOp::Cells {
+            index: (index + span_start) as u32,
+            cells: cells[span_start..].to_vec(),
+        });
+    }
+}
+
+/// Returns the number of lines the content of `next` is scrolled by
+/// relative to `previous`, if scrolling keeps more rows than not scrolling.
+fn detect_scroll(previous: &Frame, next: &Frame) -> Option<i32> {
+    let rows = next.size.rows as usize;
+    if rows < 2 || next.size.cols == 0 || next.cells.len() < rows * next.size.cols as usize {
+        return None;
+    }
+
+    let row_hash = |frame: &Frame, row: usize| {
+        let mut hasher = DefaultHasher::new();
+        frame.row(row).hash(&mut hasher);
+        hasher.finish()
+    };
+    let previous_rows: Vec<u64> = (0..rows)
+        .map(|row| row_hash(previous, row))
+        .collect();
+    let next_rows: Vec<u64> = (0..rows).map(|row| row_hash(next, row)).collect();
+
+    // number of rows matching when the previous rows are moved up by `lines`
+    let matches = |lines: i32| {
+        (0..rows)
+            .filter(|&row| {
+                let source = row as i32 + lines;
+                (0..rows as i32).contains(&source)
+                    && next_rows[row] == previous_rows[source as usize]
+            })
+            .count()
+    };
+
+    let unscrolled = matches(0);
+    (1..rows as i32)
+        .flat_map(|lines| [lines, -lines])
+        .map(|lines| (matches(lines), lines))
+        .filter(|&(count, _)| count > unscrolled)
+        .max_by_key(|&(count, lines)| (count, -lines.abs()))
+        .map(|(_, lines)| lines)
+}
+
+/// Returns the cells of `frame` scrolled by `lines`, as the client scrolls them.
+fn scrolled_cells(frame: &Frame, lines: i32, fill: &CellSnapshot) -> Vec<CellSnapshot> {
+    let rows = frame.size.rows as i32;
+    (0..rows)
+        .flat_map(|row| {
+            let source = row + lines;
+            let cols = frame.size.cols as usize;
+            if (0..rows).contains(&source) {
+                frame.row(source as usize).to_vec()
+            } else {
+                vec![fill.clone(); cols]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use compact_str::CompactString;
+
+    use super::*;
+
+    fn cell(symbol: &str) -> CellSnapshot {
+        CellSnapshot {
+            symbol: CompactString::from(symbol),
+            style_bits: 0,
+            fg: 0xFFFFFF,
+            bg: CellSnapshot::PALETTE_COLOR | 257,
+            underline_style: 0,
+            underline_color: None,
+            attributes: 0,
+        }
+    }
+
+    fn frame(cols: u16, rows: u16, text: &str) -> Frame {
+        let mut frame = Frame::new(TerminalSize::new(cols, rows), cell(" "));
+        for (idx, ch) in text.chars().enumerate() {
+            frame.cells[idx] = cell(&ch.to_string());
+        }
+        frame
+    }
+
+    /// Applies the operations to a frame, like a client grid would.
+    fn apply(frame: &mut Frame, diff: &FrameDiff) {
+        for op in &diff.ops {
+            match op {
+                FrameOp::Resize(size) => *frame = Frame::new(*size, cell(" ")),
+                FrameOp::Scroll { lines, fill, .. } => {
+                    frame.cells = scrolled_cells(frame, *lines, fill);
+                },
+                FrameOp::Cells { index, cells } => {
+                    let index = *index as usize;
+                    frame.cells[index..index + cells.len()].clone_from_slice(cells);
+                },
+                FrameOp::Fill { index, count, cell } => {
+                    let index = *index as usize;
+                    frame.cells[index..index + *count as usize].fill(cell.clone());
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn changed_cells_are_sent_as_spans_and_fills() {
+        let previous = frame(10, 2, "hello");
+        let next = frame(10, 2, "jelly     ==========");
+
+        let diff = FrameDiff::between(&previous, &next);
+        assert_eq!(diff.ops, vec![
+            FrameOp::Cells {
+                index: 0,
+                cells: vec![cell("j"), cell("e"), cell("l"), cell("l"), cell("y")]
+            },
+            FrameOp::Fill { index: 10, count: 10, cell: cell("=") },
+        ]);
+
+        let mut applied = previous.clone();
+        apply(&mut applied, &diff);
+        assert_eq!(applied, next);
+
+        assert!(FrameDiff::between(&next, &next).is_empty());
+    }
+
+    #[test]
+    fn scrolled_content_is_moved_instead_of_redrawn() {
+        let previous = frame(3, 4, "aaabbbcccddd");
+        let next = frame(3, 4, "bbbcccdddeee");
+
+        let diff = FrameDiff::between(&previous, &next);
+        assert_eq!(diff.ops, vec![FrameOp::Scroll {
+            top: 0,
+            bottom: 4,
+            lines: 1,
+            fill: cell("e")
+        },]);
+
+        let previous = frame(3, 4, "bbbcccdddeee");
+        let next = frame(3, 4, "xyzbbbcccddd");
+        let diff = FrameDiff::between(&previous, &next);
+        assert!(matches!(diff.ops[0], FrameOp::Scroll { lines: -1, .. }));
+
+        let mut applied = previous.clone();
+        apply(&mut applied, &diff);
+        assert_eq!(applied, next);
+    }
+
+    #[test]
+    fn resizing_redraws_the_frame() {
+        let previous = frame(2, 2, "ab");
+        let next = frame(3, 1, "xyz");
+
+        let diff = FrameDiff::between(&previous, &next);
+        assert_eq!(diff.ops[0], FrameOp::Resize(TerminalSize::new(3, 1)));
+
+        let mut applied = previous.clone();
+        apply(&mut applied, &diff);
+        assert_eq!(applied, next);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let previous = frame(8, 3, "hello");
+        let next = frame(8, 3, "world   ........xyz");
+        let diff = FrameDiff::between(&frame(8, 4, ""), &previous);
+        let scroll = FrameDiff::between(&previous, &next);
+
+        for diff in [diff, scroll, FrameDiff::default()] {
+            let bytes = diff.to_binary().unwrap();
+            assert_eq!(FrameDiff::from_binary(&bytes).unwrap(), diff);
+            assert!(FrameDiff::from_binary(&bytes[..bytes.len() - 1]).is_err());
+        }
+    }
+}
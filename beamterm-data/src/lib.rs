@@ -2,13 +2,17 @@
 
 mod atlas;
 mod cell_size;
+mod frame;
 mod glyph;
 pub(crate) mod serialization;
+mod snapshot;
 mod terminal_size;
 
 pub use atlas::{DebugSpacePattern, FontAtlasData, LineDecoration};
 pub use cell_size::CellSize;
+pub use frame::{Frame, FrameDiff, FrameOp};
 pub use glyph::{FontStyle, Glyph, GlyphEffect};
 pub use serialization::SerializationError;
 use serialization::*;
+pub use snapshot::{CellSnapshot, CursorSnapshot, GridSnapshot, RowSnapshot, SelectionSnapshot};
 pub use terminal_size::TerminalSize;
@@ -1,10 +1,29 @@
 use compact_str::{CompactString, format_compact};
 
-use crate::{CellSize, FontAtlasData, FontStyle, Glyph, LineDecoration};
+use crate::{
+    CellSize, CellSnapshot, CursorSnapshot, FontAtlasData, FontStyle, FrameDiff, FrameOp, Glyph,
+    GridSnapshot, LineDecoration, RowSnapshot, SelectionSnapshot, TerminalSize,
+};
 
 const ATLAS_HEADER: [u8; 4] = [0xBA, 0xB1, 0xF0, 0xA7];
 const ATLAS_VERSION: u8 = 0x03; // dictates the format of the serialized data
 
+const SNAPSHOT_HEADER: [u8; 4] = [0xBA, 0xB1, 0x5A, 0xA9];
+const SNAPSHOT_VERSION: u8 = 0x01; // dictates the format of the serialized data
+
+const FRAME_DIFF_HEADER: [u8; 4] = [0xBA, 0xB1, 0xD1, 0xFF];
+const FRAME_DIFF_VERSION: u8 = 0x01; // dictates the format of the serialized data
+
+/// Serialized size of a [`CellSnapshot`] with an empty symbol.
+const MIN_CELL_SNAPSHOT_LEN: usize = 18;
+/// Serialized size of a [`CellSnapshot`] with the longest possible symbol.
+const MAX_CELL_SNAPSHOT_LEN: usize = MIN_CELL_SNAPSHOT_LEN + u8::MAX as usize;
+
+const FRAME_OP_RESIZE: u8 = 0;
+const FRAME_OP_SCROLL: u8 = 1;
+const FRAME_OP_CELLS: u8 = 2;
+const FRAME_OP_FILL: u8 = 3;
+
 /// Error returned when font atlas serialization or deserialization fails.
 #[derive(Debug, thiserror::Error)]
 #[error("{message}")]
@@ -153,6 +172,11 @@ impl<'a> Deserializer<'a> {
         Ok(CompactString::from_utf8_lossy(bytes))
     }
 
+    /// Returns the number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
     fn verify_offset_in_bounds(&self, length: usize) -> Result<(), SerializationError> {
         if (self.position + length) > self.data.len() {
             return Err(SerializationError { message: CompactString::from("Out of bounds read") });
@@ -302,6 +326,296 @@ impl Serializable for FontAtlasData {
     }
 }
 
+impl Serializable for CellSnapshot {
+    fn serialize(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut ser = Serializer::new();
+        ser.write_string(&self.symbol)?;
+        ser.write_u16(self.style_bits);
+        ser.write_u32(self.fg);
+        ser.write_u32(self.bg);
+        ser.write_u8(self.underline_style);
+        ser.write_u8(self.underline_color.is_some() as u8);
+        ser.write_u32(self.underline_color.unwrap_or(0));
+        ser.write_u8(self.attributes);
+        Ok(ser.data)
+    }
+
+    fn deserialize(deser: &mut Deserializer) -> Result<Self, SerializationError> {
+        let symbol = deser.read_string()?;
+        let style_bits = deser.read_u16()?;
+        let fg = deser.read_u32()?;
+        let bg = deser.read_u32()?;
+        let underline_style = deser.read_u8()?;
+        let has_underline_color = deser.read_u8()? != 0;
+        let underline_color = deser.read_u32()?;
+        let attributes = deser.read_u8()?;
+
+        Ok(CellSnapshot {
+            symbol,
+            style_bits,
+            fg,
+            bg,
+            underline_style,
+            underline_color: has_underline_color.then_some(underline_color),
+            attributes,
+        })
+    }
+}
+
+impl Serializable for GridSnapshot {
+    fn serialize(&self) -> Result<Vec<u8>, SerializationError> {
+        let (cols, rows) = (self.size.cols as usize, self.size.rows as usize);
+        if self.cells.len() != cols * rows || self.rows.len() != rows {
+            return Err(SerializationError::new(format_compact!(
+                "Snapshot of {cols}x{rows} grid has {} cells and {} rows",
+                self.cells.len(),
+                self.rows.len()
+            )));
+        }
+
+        let mut ser = Serializer::new();
+        SNAPSHOT_HEADER
+            .iter()
+            .for_each(|&byte| ser.write_u8(byte));
+        ser.write_u8(SNAPSHOT_VERSION);
+
+        ser.write_u16(self.size.cols);
+        ser.write_u16(self.size.rows);
+
+        ser.write_u8(self.cursor.is_some() as u8);
+        if let Some(cursor) = &self.cursor {
+            ser.write_u16(cursor.col);
+            ser.write_u16(cursor.row);
+            ser.write_u8(cursor.shape);
+            ser.write_u32(cursor.color);
+            ser.write_u32(cursor.blink_interval_ms);
+            ser.write_u8(cursor.visible as u8);
+        }
+
+        ser.write_u8(self.selection.is_some() as u8);
+        if let Some(selection) = &self.selection {
+            ser.write_u8(selection.mode);
+            ser.write_u16(selection.start.0);
+            ser.write_u16(selection.start.1);
+            ser.write_u16(selection.end.0);
+            ser.write_u16(selection.end.1);
+            ser.write_u8(selection.trim_trailing_whitespace as u8);
+        }
+
+        // rows and cells are highly repetitive, so they are compressed together
+        let mut grid = Serializer::new();
+        for row in &self.rows {
+            grid.write_u8(row.line_attribute);
+            grid.write_u8(row.wrapped as u8);
+        }
+        for cell in &self.cells {
+            grid.data.extend(cell.serialize()?);
+        }
+
+        let packed_grid_data = miniz_oxide::deflate::compress_to_vec(&grid.data, 6);
+        ser.write_u8_slice(&packed_grid_data);
+
+        Ok(ser.data)
+    }
+
+    fn deserialize(deser: &mut Deserializer) -> Result<Self, SerializationError> {
+        let header = [deser.read_u8()?, deser.read_u8()?, deser.read_u8()?, deser.read_u8()?];
+        if header != SNAPSHOT_HEADER {
+            return Err(SerializationError {
+                message: CompactString::const_new(
+                    "Invalid grid snapshot header (wrong file format?)",
+                ),
+            });
+        }
+
+        let version = deser.read_u8()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SerializationError {
+                message: format_compact!(
+                    "Grid snapshot version mismatch: expected v{}, found v{}",
+                    SNAPSHOT_VERSION,
+                    version
+                ),
+            });
+        }
+
+        let size = TerminalSize::new(deser.read_u16()?, deser.read_u16()?);
+
+        let cursor = if deser.read_u8()? != 0 {
+            Some(CursorSnapshot {
+                col: deser.read_u16()?,
+                row: deser.read_u16()?,
+                shape: deser.read_u8()?,
+                color: deser.read_u32()?,
+                blink_interval_ms: deser.read_u32()?,
+                visible: deser.read_u8()? != 0,
+            })
+        } else {
+            None
+        };
+
+        let selection = if deser.read_u8()? != 0 {
+            Some(SelectionSnapshot {
+                mode: deser.read_u8()?,
+                start: (deser.read_u16()?, deser.read_u16()?),
+                end: (deser.read_u16()?, deser.read_u16()?),
+                trim_trailing_whitespace: deser.read_u8()? != 0,
+            })
+        } else {
+            None
+        };
+
+        // the grid data can't inflate to more than the largest possible rows
+        // and cells of a grid of this size
+        let cell_count = size.cols as usize * size.rows as usize;
+        let max_grid_len = cell_count
+            .saturating_mul(MAX_CELL_SNAPSHOT_LEN)
+            .saturating_add(size.rows as usize * 2);
+
+        let packed_grid_data = deser.read_u8_slice()?;
+        let grid_data =
+            miniz_oxide::inflate::decompress_to_vec_with_limit(&packed_grid_data, max_grid_len)
+                .map_err(|_| SerializationError {
+                    message: CompactString::const_new("Failed to decompress grid snapshot cells"),
+                })?;
+
+        // don't trust the grid size for allocating
+        let mut grid = Deserializer::new(&grid_data);
+        let mut rows = Vec::with_capacity((size.rows as usize).min(grid.remaining() / 2));
+        for _ in 0..size.rows {
+            rows.push(RowSnapshot {
+                line_attribute: grid.read_u8()?,
+                wrapped: grid.read_u8()? != 0,
+            });
+        }
+
+        let mut cells =
+            Vec::with_capacity(cell_count.min(grid.remaining() / MIN_CELL_SNAPSHOT_LEN));
+        for _ in 0..cell_count {
+            cells.push(CellSnapshot::deserialize(&mut grid)?);
+        }
+
+        Ok(GridSnapshot { size, cells, rows, cursor, selection })
+    }
+}
+
+impl Serializable for FrameOp {
+    fn serialize(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut ser = Serializer::new();
+        match self {
+            FrameOp::Resize(size) => {
+                ser.write_u8(FRAME_OP_RESIZE);
+                ser.write_u16(size.cols);
+                ser.write_u16(size.rows);
+            },
+            FrameOp::Scroll { top, bottom, lines, fill } => {
+                ser.write_u8(FRAME_OP_SCROLL);
+                ser.write_u16(*top);
+                ser.write_u16(*bottom);
+                ser.write_i32(*lines);
+                ser.data.extend(fill.serialize()?);
+            },
+            FrameOp::Cells { index, cells } => {
+                ser.write_u8(FRAME_OP_CELLS);
+                ser.write_u32(*index);
+                ser.write_u32(cells.len() as u32);
+                for cell in cells {
+                    ser.data.extend(cell.serialize()?);
+                }
+            },
+            FrameOp::Fill { index, count, cell } => {
+                ser.write_u8(FRAME_OP_FILL);
+                ser.write_u32(*index);
+                ser.write_u32(*count);
+                ser.data.extend(cell.serialize()?);
+            },
+        }
+        Ok(ser.data)
+    }
+
+    fn deserialize(deser: &mut Deserializer) -> Result<Self, SerializationError> {
+        let op = match deser.read_u8()? {
+            FRAME_OP_RESIZE => {
+                FrameOp::Resize(TerminalSize::new(deser.read_u16()?, deser.read_u16()?))
+            },
+            FRAME_OP_SCROLL => FrameOp::Scroll {
+                top: deser.read_u16()?,
+                bottom: deser.read_u16()?,
+                lines: deser.read_i32()?,
+                fill: CellSnapshot::deserialize(deser)?,
+            },
+            FRAME_OP_CELLS => {
+                let index = deser.read_u32()?;
+                let count = deser.read_u32()? as usize;
+                // don't trust the count for allocating
+                let mut cells =
+                    Vec::with_capacity(count.min(deser.remaining() / MIN_CELL_SNAPSHOT_LEN));
+                for _ in 0..count {
+                    cells.push(CellSnapshot::deserialize(deser)?);
+                }
+                FrameOp::Cells { index, cells }
+            },
+            FRAME_OP_FILL => FrameOp::Fill {
+                index: deser.read_u32()?,
+                count: deser.read_u32()?,
+                cell: CellSnapshot::deserialize(deser)?,
+            },
+            op => {
+                return Err(SerializationError::new(format_compact!(
+                    "Unknown frame operation: {op}"
+                )));
+            },
+        };
+
+        Ok(op)
+    }
+}
+
+impl Serializable for FrameDiff {
+    fn serialize(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut ser = Serializer::new();
+        FRAME_DIFF_HEADER
+            .iter()
+            .for_each(|&byte| ser.write_u8(byte));
+        ser.write_u8(FRAME_DIFF_VERSION);
+
+        ser.write_u32(self.ops.len() as u32);
+        for op in &self.ops {
+            ser.data.extend(op.serialize()?);
+        }
+
+        Ok(ser.data)
+    }
+
+    fn deserialize(deser: &mut Deserializer) -> Result<Self, SerializationError> {
+        let header = [deser.read_u8()?, deser.read_u8()?, deser.read_u8()?, deser.read_u8()?];
+        if header != FRAME_DIFF_HEADER {
+            return Err(SerializationError {
+                message: CompactString::const_new("Invalid frame diff header (wrong protocol?)"),
+            });
+        }
+
+        let version = deser.read_u8()?;
+        if version != FRAME_DIFF_VERSION {
+            return Err(SerializationError {
+                message: format_compact!(
+                    "Frame diff version mismatch: expected v{}, found v{}",
+                    FRAME_DIFF_VERSION,
+                    version
+                ),
+            });
+        }
+
+        let op_count = deser.read_u32()? as usize;
+        let mut ops = Vec::with_capacity(op_count.min(deser.remaining()));
+        for _ in 0..op_count {
+            ops.push(FrameOp::deserialize(deser)?);
+        }
+
+        Ok(FrameDiff { ops })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,6 +786,90 @@ mod tests {
         assert_eq!(serialized.read_string().unwrap(), "Hello");
     }
 
+    #[test]
+    fn test_grid_snapshot_round_trip() {
+        let cell = |symbol: &str, fg: u32| CellSnapshot {
+            symbol: CompactString::from(symbol),
+            style_bits: Glyph::BOLD_FLAG,
+            fg,
+            bg: CellSnapshot::PALETTE_COLOR | 257,
+            underline_style: 2,
+            underline_color: (fg == 0).then_some(0xFF0000),
+            attributes: 0b1000,
+        };
+
+        let original = GridSnapshot {
+            size: TerminalSize::new(2, 2),
+            cells: vec![cell("A", 0x123456), cell("🚀", 0), cell(" ", 7), cell("€", 0xFFFFFF)],
+            rows: vec![RowSnapshot { line_attribute: 1, wrapped: true }, RowSnapshot::default()],
+            cursor: Some(CursorSnapshot {
+                col: 1,
+                row: 1,
+                shape: 2,
+                color: 0xABCDEF,
+                blink_interval_ms: 530,
+                visible: true,
+            }),
+            selection: Some(SelectionSnapshot {
+                mode: 1,
+                start: (0, 0),
+                end: (1, 1),
+                trim_trailing_whitespace: true,
+            }),
+        };
+
+        let serialized = original.to_binary().unwrap();
+        assert_eq!(GridSnapshot::from_binary(&serialized).unwrap(), original);
+
+        let empty = GridSnapshot { cursor: None, selection: None, ..original.clone() };
+        let serialized = empty.to_binary().unwrap();
+        assert_eq!(GridSnapshot::from_binary(&serialized).unwrap(), empty);
+
+        // other formats and truncated data are rejected
+        assert!(GridSnapshot::from_binary(&serialized[1..]).is_err());
+        assert!(GridSnapshot::from_binary(&serialized[..serialized.len() - 1]).is_err());
+
+        let mismatched = GridSnapshot { rows: Vec::new(), ..original };
+        assert!(mismatched.to_binary().is_err());
+    }
+
+    #[test]
+    fn test_grid_snapshot_with_malformed_size() {
+        let cell = CellSnapshot {
+            symbol: CompactString::const_new("x"),
+            style_bits: 0,
+            fg: 0,
+            bg: 0,
+            underline_style: 0,
+            underline_color: None,
+            attributes: 0,
+        };
+        let snapshot = GridSnapshot {
+            size: TerminalSize::new(2, 1),
+            cells: vec![cell.clone(), cell.clone()],
+            rows: vec![RowSnapshot::default()],
+            cursor: None,
+            selection: None,
+        };
+        let serialized = snapshot.to_binary().unwrap();
+
+        // a huge grid size must not be trusted for allocating the cells
+        let mut huge = serialized.clone();
+        huge[5..9].copy_from_slice(&[0xFF; 4]);
+        assert!(GridSnapshot::from_binary(&huge).is_err());
+
+        // grid data inflating to more than the grid size allows is rejected
+        let oversized = GridSnapshot {
+            size: TerminalSize::new(1000, 2),
+            cells: vec![cell; 2000],
+            rows: vec![RowSnapshot::default(); 2],
+            ..snapshot
+        };
+        let mut serialized = oversized.to_binary().unwrap();
+        serialized[5..9].copy_from_slice(&[2, 0, 1, 0]);
+        assert!(GridSnapshot::from_binary(&serialized).is_err());
+    }
+
     #[test]
     fn test_font_atlas_config_round_trip() {
         // Create test glyphs
@@ -0,0 +1,109 @@
+use compact_str::CompactString;
+
+use crate::{Deserializer, Serializable, SerializationError, TerminalSize};
+
+/// Snapshot of the visible state of a terminal grid, for persisting and
+/// restoring sessions without replaying their output.
+///
+/// Cells store their symbols rather than glyph IDs, so a snapshot can be
+/// restored against a different font atlas. The encoding of the enumerated
+/// fields is defined by `beamterm-core`, which creates and restores
+/// snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridSnapshot {
+    /// Dimensions of the grid.
+    pub size: TerminalSize,
+    /// Cells of the grid in row-major order, `cols * rows` entries.
+    pub cells: Vec<CellSnapshot>,
+    /// Rows of the grid, `rows` entries.
+    pub rows: Vec<RowSnapshot>,
+    /// The cursor, if the grid has one.
+    pub cursor: Option<CursorSnapshot>,
+    /// The active selection, if any.
+    pub selection: Option<SelectionSnapshot>,
+}
+
+/// Content and style of a cell in a [`GridSnapshot`] or [`Frame`](crate::Frame).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CellSnapshot {
+    /// The symbol of the cell; a single space for the right half of a
+    /// double-width glyph.
+    pub symbol: CompactString,
+    /// Font style and effect bits, as in [`Glyph`](crate::Glyph) IDs.
+    pub style_bits: u16,
+    /// Foreground color as 0xRRGGBB, or a palette slot tagged with
+    /// [`CellSnapshot::PALETTE_COLOR`].
+    pub fg: u32,
+    /// Background color, encoded like `fg`.
+    pub bg: u32,
+    /// Shape of the underline.
+    pub underline_style: u8,
+    /// Underline color as 0xRRGGBB, if it differs from the foreground.
+    pub underline_color: Option<u32>,
+    /// Rendering attribute bits, e.g. dim or reverse video.
+    pub attributes: u8,
+}
+
+impl CellSnapshot {
+    /// Tags a color as a palette slot rather than an RGB value.
+    pub const PALETTE_COLOR: u32 = 0x0100_0000;
+}
+
+/// Attributes of a row in a [`GridSnapshot`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RowSnapshot {
+    /// DEC line attribute, e.g. double-width.
+    pub line_attribute: u8,
+    /// Whether the text of the row continues on the next row.
+    pub wrapped: bool,
+}
+
+/// The cursor of a [`GridSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorSnapshot {
+    /// Column of the cursor.
+    pub col: u16,
+    /// Row of the cursor.
+    pub row: u16,
+    /// Shape of the cursor.
+    pub shape: u8,
+    /// Cursor color as 0xRRGGBB.
+    pub color: u32,
+    /// Duration of each blink phase in milliseconds; `0` disables blinking.
+    pub blink_interval_ms: u32,
+    /// Whether the cursor is drawn.
+    pub visible: bool,
+}
+
+/// The selection of a [`GridSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectionSnapshot {
+    /// Selection mode, e.g. block or linear.
+    pub mode: u8,
+    /// Start of the selection as (col, row).
+    pub start: (u16, u16),
+    /// End of the selection as (col, row).
+    pub end: (u16, u16),
+    /// Whether trailing whitespace is trimmed from selected lines.
+    pub trim_trailing_whitespace: bool,
+}
+
+impl GridSnapshot {
+    /// Deserializes a grid snapshot from binary format.
+    ///
+    /// # Errors
+    /// Returns [`SerializationError`] if the binary data is malformed, or was
+    /// written by an incompatible version of the format.
+    pub fn from_binary(serialized: &[u8]) -> Result<Self, SerializationError> {
+        let mut deserializer = Deserializer::new(serialized);
+        GridSnapshot::deserialize(&mut deserializer)
+    }
+
+    /// Serializes the grid snapshot to binary format.
+    ///
+    /// # Errors
+    /// Returns [`SerializationError`] if a cell symbol exceeds 255 bytes.
+    pub fn to_binary(&self) -> Result<Vec<u8>, SerializationError> {
+        self.serialize()
+    }
+}
@@ -5,8 +5,10 @@ mod renderer;
 
 // Re-export platform-agnostic types from beamterm-core
 pub use beamterm_core::gl::{
-    Atlas, CellData, CellIterator, CellQuery, Drawable, FontAtlas, GlyphSlot, GlyphTracker,
-    RenderContext, SelectionMode, SelectionTracker, StaticFontAtlas, TerminalGrid, select,
+    Atlas, CellAttributes, CellData, CellIterator, CellQuery, Cursor, CursorShape,
+    DEFAULT_WORD_SEPARATORS, Drawable, FontAtlas, GlyphSlot, GlyphTracker, Highlight, HighlightId,
+    HyperlinkId, ImageId, ImagePlacement, LineAttribute, PlacementId, RenderContext, SelectionMode,
+    SelectionStyle, SelectionTracker, StaticFontAtlas, TerminalGrid, UnderlineStyle, select,
 };
 // Web-specific exports
 pub(crate) use context_loss::ContextLossHandler;
@@ -1,5 +1,5 @@
-use js_sys::wasm_bindgen::JsCast;
-use wasm_bindgen_futures::spawn_local;
+use js_sys::wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{JsFuture, spawn_local};
 use web_sys::{Document, HtmlCanvasElement, console};
 
 use crate::error::Error;
@@ -58,6 +58,15 @@ pub(crate) fn device_pixel_ratio() -> f32 {
     web_sys::window().map_or(1.0, |w| w.device_pixel_ratio() as f32)
 }
 
+/// Returns the time since page load, as reported by `performance.now()`.
+pub(crate) fn performance_now() -> std::time::Duration {
+    let millis = web_sys::window()
+        .and_then(|w| w.performance())
+        .map_or(0.0, |p| p.now());
+
+    std::time::Duration::from_secs_f64(millis.max(0.0) / 1000.0)
+}
+
 /// Copies text to the system clipboard using the browser's async clipboard API.
 ///
 /// Spawns an async task to handle the clipboard write operation.
@@ -69,12 +78,55 @@ pub(crate) fn copy_to_clipboard(text: impl Into<String>) {
     let text = text.into();
     spawn_local(async move {
         if let Some(window) = web_sys::window() {
-            let clipboard = window.navigator().clipboard();
-            if let Err(err) =
-                wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&text)).await
-            {
-                console::error_1(&format!("Failed to copy to clipboard: {err:?}").into());
-            }
+            write_text(&window.navigator().clipboard(), &text).await;
+        }
+    });
+}
+
+/// Copies text to the system clipboard as both `text/plain` and `text/html`,
+/// so that rich text editors keep the colors and styles of the cells.
+///
+/// Falls back to plain text if the browser cannot write HTML to the clipboard.
+/// Logs failure to the console.
+pub(crate) fn copy_to_clipboard_with_html(text: impl Into<String>, html: impl Into<String>) {
+    let (text, html) = (text.into(), html.into());
+    spawn_local(async move {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let clipboard = window.navigator().clipboard();
+
+        let written = match clipboard_item(&text, &html) {
+            Ok(item) => JsFuture::from(clipboard.write(&js_sys::Array::of1(&item))).await,
+            Err(err) => Err(err),
+        };
+        if written.is_err() {
+            write_text(&clipboard, &text).await;
         }
     });
 }
+
+/// Writes plain text to the clipboard, logging failure to the console.
+async fn write_text(clipboard: &web_sys::Clipboard, text: &str) {
+    if let Err(err) = JsFuture::from(clipboard.write_text(text)).await {
+        console::error_1(&format!("Failed to copy to clipboard: {err:?}").into());
+    }
+}
+
+/// Creates a clipboard item holding `text` and its `html` representation.
+fn clipboard_item(text: &str, html: &str) -> Result<web_sys::ClipboardItem, JsValue> {
+    let blob = |data: &str, mime: &str| {
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(mime);
+        web_sys::Blob::new_with_str_sequence_and_options(
+            &js_sys::Array::of1(&data.into()),
+            &options,
+        )
+    };
+
+    let (text, html) = (blob(text, "text/plain")?, blob(html, "text/html")?);
+    let items = js_sys::Object::new();
+    js_sys::Reflect::set(&items, &"text/plain".into(), &text)?;
+    js_sys::Reflect::set(&items, &"text/html".into(), &html)?;
+    web_sys::ClipboardItem::new_with_record_from_str_to_blob_promise(&items)
+}
@@ -43,8 +43,10 @@ pub mod mouse;
 // Re-export platform-agnostic types from beamterm-core
 pub use ::beamterm_data::{DebugSpacePattern, GlyphEffect};
 pub use beamterm_core::{
-    CellSize, CursorPosition, FontAtlasData, FontStyle, GlslVersion, SerializationError,
-    TerminalSize, UrlMatch, compact_str, find_url_at_cursor, glow, is_double_width, is_emoji,
+    CellColor, CellSize, CellSnapshot, CursorPosition, FontAtlasData, FontStyle, Frame, FrameDiff,
+    FrameOp, GlslVersion, GridSnapshot, Matcher, Palette, PatternMatch, SearchMatch, SearchOptions,
+    SearchQuery, SerializationError, SvgOptions, TerminalSize, UrlMatch, compact_str,
+    find_all_matches, find_url_at_cursor, glow, is_double_width, is_emoji,
 };
 pub use js_sys;
 pub use terminal::*;
@@ -89,6 +89,8 @@ pub struct TerminalMouseEvent {
     pub row: u16,
     /// Mouse button pressed (0 = left, 1 = middle, 2 = right).
     button: i16,
+    /// Number of consecutive clicks, e.g. 2 for the second press of a double-click.
+    click_count: u32,
     /// All modifier that were pressed during the event.
     modifier_keys: ModifierKeys,
 }
@@ -100,6 +102,13 @@ impl TerminalMouseEvent {
         self.button
     }
 
+    /// Returns the number of consecutive clicks for mouse down, mouse up and
+    /// click events: 1 for a single click, 2 for a double-click, and so on.
+    #[must_use]
+    pub fn click_count(&self) -> u32 {
+        self.click_count
+    }
+
     /// Returns whether the Ctrl key was pressed during the event.
     #[must_use]
     pub fn ctrl_key(&self) -> bool {
@@ -187,6 +196,8 @@ pub struct MouseSelectOptions {
     selection_mode: SelectionMode,
     require_modifier_keys: ModifierKeys,
     trim_trailing_whitespace: bool,
+    word_separators: Option<&'static str>,
+    copy_html: bool,
 }
 
 impl MouseSelectOptions {
@@ -196,15 +207,20 @@ impl MouseSelectOptions {
     /// - Selection mode: `Block`
     /// - Required modifier keys: none
     /// - Trim trailing whitespace: `false`
+    /// - Word separators: [`DEFAULT_WORD_SEPARATORS`](crate::gl::DEFAULT_WORD_SEPARATORS)
+    /// - Copy as HTML: `false`
     #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Sets the selection mode (Linear or Block).
+    /// Sets the selection mode for click and drag selections.
     ///
     /// - `Linear`: Selects text following the natural reading order
     /// - `Block`: Selects a rectangular region of cells
+    /// - `Word`, `Line`: Selects whole words or rows
+    ///
+    /// Double-clicks always select words, and triple-clicks rows.
     #[must_use]
     pub fn selection_mode(mut self, mode: SelectionMode) -> Self {
         self.selection_mode = mode;
@@ -244,6 +260,26 @@ impl MouseSelectOptions {
         self.trim_trailing_whitespace = trim;
         self
     }
+
+    /// Sets the characters separating words when double-clicking.
+    ///
+    /// Whitespace always separates words. See
+    /// [`CellQuery::word_separators`](crate::gl::CellQuery::word_separators).
+    #[must_use]
+    pub fn word_separators(mut self, separators: &'static str) -> Self {
+        self.word_separators = Some(separators);
+        self
+    }
+
+    /// Sets whether selections are also copied as HTML, keeping the colors
+    /// and text styles of the cells when pasted into rich text editors.
+    ///
+    /// The plain text is always copied along with the HTML.
+    #[must_use]
+    pub fn copy_html(mut self, copy_html: bool) -> Self {
+        self.copy_html = copy_html;
+        self
+    }
 }
 
 impl TerminalMouseHandler {
@@ -311,8 +347,11 @@ impl TerminalMouseHandler {
             let ts = g.terminal_size();
             let (cell_width, cell_height) = g.css_cell_size();
 
-            let col = (event.offset_x() as f32 / cell_width).floor() as u16;
             let row = (event.offset_y() as f32 / cell_height).floor() as u16;
+            // cells of double-width rows span two screen columns
+            let width_scale =
+                if g.viewport_line_attribute(row).is_double_width() { 2.0 } else { 1.0 };
+            let col = (event.offset_x() as f32 / (cell_width * width_scale)).floor() as u16;
 
             if col < ts.cols && row < ts.rows { Some((col, row)) } else { None }
         };
@@ -359,6 +398,7 @@ impl TerminalMouseHandler {
 ///
 /// # Features
 /// - Click and drag to select text
+/// - Double-click to select words, triple-click to select rows
 /// - Automatic clipboard copy on mouse release
 /// - Configurable selection modes (Linear/Block)
 /// - Optional trailing whitespace trimming
@@ -395,7 +435,8 @@ impl DefaultSelectionHandler {
     /// * `active_selection` - Selection tracker for visual feedback
     ///
     /// # Algorithm
-    /// 1. MouseDown: Begin new selection or replace existing
+    /// 1. MouseDown: Begin new selection or replace existing; double and
+    ///    triple clicks begin word and line selections
     /// 2. MouseMove: Update selection end point if selecting
     /// 3. MouseUp: Complete selection and copy to clipboard
     ///
@@ -405,6 +446,8 @@ impl DefaultSelectionHandler {
         let query_mode = self.options.selection_mode;
         let trim_trailing = self.options.trim_trailing_whitespace;
         let require_modifier_keys = self.options.require_modifier_keys;
+        let word_separators = self.options.word_separators;
+        let copy_html = self.options.copy_html;
 
         Box::new(move |event: TerminalMouseEvent, grid: &TerminalGrid| {
             let mut state = selection_state.borrow_mut();
@@ -422,8 +465,21 @@ impl DefaultSelectionHandler {
                     // while a previous selection was ongoing. if so, we do
                     // nothing and await the MouseUp event.
 
+                    // double and triple clicks select the word or row under
+                    // the cursor, and dragging extends the selection by words
+                    // or rows
+                    let query_mode = match event.click_count {
+                        2 => SelectionMode::Word,
+                        n if n >= 3 => SelectionMode::Line,
+                        _ => query_mode,
+                    };
+
                     // mouse down always begins a new *potential* selection
-                    if state.is_complete() {
+                    if event.click_count >= 2 {
+                        // the preceding click may have canceled the selection
+                        // or completed a single-cell one
+                        state.begin_multi_click_selection(event.col, event.row);
+                    } else if state.is_complete() {
                         // the existing (completed) selection is replaced with
                         // a new selection which will be canceled if the mouse
                         // up event is fired on the same cell.
@@ -433,9 +489,15 @@ impl DefaultSelectionHandler {
                         state.begin_selection(event.col, event.row);
                     }
 
-                    let query = select(query_mode)
+                    let mut query = select(query_mode)
                         .start((event.col, event.row))
                         .trim_trailing_whitespace(trim_trailing);
+                    if let Some(separators) = word_separators {
+                        query = query.word_separators(separators);
+                    }
+                    if event.click_count >= 2 {
+                        query = query.end((event.col, event.row));
+                    }
 
                     active_selection.set_query(query);
                 },
@@ -455,8 +517,13 @@ impl DefaultSelectionHandler {
                         let query = active_selection.query();
                         active_selection.set_content_hash(grid.hash_cells(query));
 
-                        let selected_text = grid.get_text(active_selection.query());
-                        crate::js::copy_to_clipboard(selected_text);
+                        let selected_text = grid.get_text(query);
+                        if copy_html {
+                            let html = grid.get_html(query);
+                            crate::js::copy_to_clipboard_with_html(selected_text, html);
+                        } else {
+                            crate::js::copy_to_clipboard(selected_text);
+                        }
                     } else {
                         state.clear();
                         active_selection.clear();
@@ -520,6 +587,12 @@ impl SelectionState {
         *self = SelectionState::Selecting { start: (col, row), current: None };
     }
 
+    /// Begins a word or line selection on a double or triple click,
+    /// replacing any previous state.
+    fn begin_multi_click_selection(&mut self, col: u16, row: u16) {
+        *self = SelectionState::Selecting { start: (col, row), current: Some((col, row)) };
+    }
+
     /// Updates selection end point during drag.
     ///
     /// Transitions MaybeSelecting to Selecting if needed.
@@ -637,6 +710,7 @@ fn create_mouse_event_closure(
             col,
             row,
             button: event.button(),
+            click_count: event.detail().max(0) as u32,
             modifier_keys: modifiers,
         };
         let grid_ref = grid.borrow();
@@ -1,14 +1,18 @@
 use std::{cell::RefCell, rc::Rc};
 
 use beamterm_core::GlslVersion;
-use beamterm_data::{DebugSpacePattern, FontAtlasData};
+use beamterm_data::{DebugSpacePattern, FontAtlasData, FrameDiff, GridSnapshot};
 use compact_str::{CompactString, CompactStringExt, ToCompactString, format_compact};
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    CellData, CursorPosition, Error, FontAtlas, Renderer, StaticFontAtlas, TerminalGrid, UrlMatch,
-    gl::{CellQuery, ContextLossHandler, DynamicFontAtlas, dynamic_atlas::CanvasGlyphRasterizer},
-    js::device_pixel_ratio,
+    CellData, CursorPosition, Error, FontAtlas, Matcher, PatternMatch, Renderer, SearchMatch,
+    SearchQuery, StaticFontAtlas, SvgOptions, TerminalGrid, UrlMatch,
+    gl::{
+        CellQuery, ContextLossHandler, DynamicFontAtlas, HyperlinkId,
+        dynamic_atlas::CanvasGlyphRasterizer,
+    },
+    js::{device_pixel_ratio, performance_now},
     mouse::{
         DefaultSelectionHandler, MouseEventCallback, MouseSelectOptions, TerminalMouseEvent,
         TerminalMouseHandler,
@@ -299,6 +303,57 @@ impl Terminal {
         self.grid.borrow().get_text(selection)
     }
 
+    /// Returns the specified cell selection as text with ANSI SGR escape
+    /// sequences, preserving colors and text styles.
+    pub fn get_ansi(&self, selection: CellQuery) -> String {
+        self.grid.borrow().get_ansi(selection)
+    }
+
+    /// Returns the specified cell selection as an HTML fragment with inline
+    /// styles, e.g. for copying it to the clipboard as rich text.
+    pub fn get_html(&self, selection: CellQuery) -> String {
+        self.grid.borrow().get_html(selection)
+    }
+
+    /// Returns SVG export options matching the terminal's cell size and font
+    /// atlas, for use with [`get_svg`](Self::get_svg).
+    pub fn svg_options(&self) -> SvgOptions {
+        self.grid.borrow().svg_options()
+    }
+
+    /// Returns the viewport as an SVG image, e.g. for documentation and bug
+    /// reports.
+    pub fn get_svg(&self, options: &SvgOptions) -> String {
+        self.grid.borrow().get_svg(options)
+    }
+
+    /// Captures the cells, selection and cursor of the terminal, e.g. for
+    /// persisting a session with [`GridSnapshot::to_binary`].
+    pub fn snapshot(&self) -> GridSnapshot {
+        self.grid.borrow().snapshot()
+    }
+
+    /// Restores a snapshot taken with [`snapshot`](Self::snapshot), possibly
+    /// in another session with a different font atlas or terminal size.
+    pub fn restore(&mut self, snapshot: &GridSnapshot) {
+        self.grid
+            .borrow_mut()
+            .restore(self.renderer.gl(), snapshot);
+    }
+
+    /// Applies a [`FrameDiff`] received from a server rendering a TUI, e.g.
+    /// decoded with [`FrameDiff::from_binary`].
+    ///
+    /// # Errors
+    /// Returns an error if flushing the pending cell updates before a scroll fails.
+    pub fn apply_frame_diff(&mut self, diff: &FrameDiff) -> Result<(), Error> {
+        self.grid
+            .borrow_mut()
+            .apply_frame_diff(self.renderer.gl(), diff)?;
+
+        Ok(())
+    }
+
     /// Detects an HTTP/HTTPS URL at or around the given cell position.
     ///
     /// Scans left from the cursor to find a URL scheme (`http://` or `https://`),
@@ -314,6 +369,40 @@ impl Terminal {
         beamterm_core::find_url_at_cursor(cursor, &grid)
     }
 
+    /// Registers an explicit hyperlink target, e.g. from an OSC 8 escape
+    /// sequence, for linking cells with [`CellData::with_hyperlink`].
+    ///
+    /// Links registered with the same `id` and `uri` share a single target.
+    pub fn register_hyperlink(&self, uri: &str, id: Option<&str>) -> HyperlinkId {
+        self.grid.borrow_mut().register_hyperlink(uri, id)
+    }
+
+    /// Returns the explicit hyperlink at the given cell position, with its
+    /// target URI and the query spanning all its cells.
+    ///
+    /// Unlike [`find_url_at`](Self::find_url_at), the link label may differ
+    /// from its target and may wrap across rows.
+    pub fn find_hyperlink_at(&self, cursor: CursorPosition) -> Option<UrlMatch> {
+        self.grid.borrow().hyperlink_at(cursor)
+    }
+
+    /// Returns every match of the `matchers` on screen, e.g. for underlining
+    /// all links up front or for keyboard hint navigation.
+    ///
+    /// Where matches overlap, the matcher listed first wins.
+    pub fn find_all_matches(&self, matchers: &[Matcher]) -> Vec<PatternMatch> {
+        self.grid.borrow().find_all_matches(matchers)
+    }
+
+    /// Returns every match of a text search on screen, in reading order, e.g.
+    /// for a find bar highlighting all hits.
+    ///
+    /// Rows marked as wrapped with [`TerminalGrid::set_line_wrapped`] are
+    /// searched as one line.
+    pub fn search(&self, query: &SearchQuery) -> Vec<SearchMatch> {
+        self.grid.borrow().search(query)
+    }
+
     /// Renders the current terminal state to the canvas.
     ///
     /// This method performs the complete render pipeline: frame setup, grid rendering,
@@ -346,9 +435,10 @@ impl Terminal {
             self.handle_pixel_ratio_change(raw_dpr)?;
         }
 
-        self.grid
-            .borrow_mut()
-            .flush_cells(self.renderer.gl())?;
+        let mut grid = self.grid.borrow_mut();
+        grid.set_time(self.renderer.gl(), performance_now());
+        grid.flush_cells(self.renderer.gl())?;
+        drop(grid);
 
         self.renderer.begin_frame();
         self.renderer.render(&*self.grid.borrow())?;
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use beamterm_data::{FontAtlasData, Glyph};
+use beamterm_data::{FontAtlasData, FrameDiff, Glyph, GridSnapshot};
 use compact_str::CompactString;
 use serde_wasm_bindgen::from_value;
 use unicode_segmentation::UnicodeSegmentation;
@@ -80,6 +80,10 @@ pub enum SelectionMode {
     Block,
     /// Linear text flow selection
     Linear,
+    /// Selection of whole words
+    Word,
+    /// Selection of whole rows
+    Line,
 }
 
 /// Type of mouse event
@@ -696,6 +700,30 @@ impl BeamtermRenderer {
         self.terminal.get_text(query.inner).to_string()
     }
 
+    /// Get the text of a cell query with ANSI escape sequences for its colors
+    /// and text styles
+    #[wasm_bindgen(js_name = "getAnsi")]
+    pub fn get_ansi(&self, query: &CellQuery) -> String {
+        self.terminal.get_ansi(query.inner)
+    }
+
+    /// Get the cells of a cell query as an HTML fragment with inline styles
+    #[wasm_bindgen(js_name = "getHtml")]
+    pub fn get_html(&self, query: &CellQuery) -> String {
+        self.terminal.get_html(query.inner)
+    }
+
+    /// Get the visible cells as an SVG image, optionally with a CSS
+    /// `font-family` list other than `monospace`
+    #[wasm_bindgen(js_name = "getSvg")]
+    pub fn get_svg(&self, font_family: Option<String>) -> String {
+        let mut options = self.terminal.svg_options();
+        if let Some(font_family) = font_family {
+            options = options.with_font_family(&font_family);
+        }
+        self.terminal.get_svg(&options)
+    }
+
     /// Detects an HTTP/HTTPS URL at or around the given cell position.
     ///
     /// Scans left from the position to find a URL scheme (`http://` or `https://`),
@@ -735,6 +763,18 @@ impl BeamtermRenderer {
         crate::js::copy_to_clipboard(text);
     }
 
+    /// Copy text to the system clipboard as both `text/plain` and `text/html`
+    ///
+    /// # Example
+    /// ```javascript
+    /// const query = new CellQuery(SelectionMode.Linear).start(0, 2).end(40, 5);
+    /// renderer.copyRichToClipboard(renderer.getText(query), renderer.getHtml(query));
+    /// ```
+    #[wasm_bindgen(js_name = "copyRichToClipboard")]
+    pub fn copy_rich_to_clipboard(&self, text: &str, html: String) {
+        crate::js::copy_to_clipboard_with_html(text, html);
+    }
+
     /// Clear any active selection
     #[wasm_bindgen(js_name = "clearSelection")]
     pub fn clear_selection(&self) {
@@ -781,6 +821,47 @@ impl BeamtermRenderer {
         Ok(self.terminal.resize(width, height)?)
     }
 
+    /// Capture the cells, selection and cursor in a binary snapshot
+    ///
+    /// # Example
+    /// ```javascript
+    /// sessionStorage.setItem('screen', btoa(String.fromCharCode(...renderer.snapshot())));
+    /// ```
+    #[wasm_bindgen]
+    pub fn snapshot(&self) -> Result<Vec<u8>, JsValue> {
+        self.terminal
+            .snapshot()
+            .to_binary()
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Restore a binary snapshot taken with `snapshot()`, possibly with a
+    /// different font atlas or terminal size
+    #[wasm_bindgen]
+    pub fn restore(&mut self, snapshot: &[u8]) -> Result<(), JsValue> {
+        let snapshot = GridSnapshot::from_binary(snapshot)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse snapshot: {e}")))?;
+        self.terminal.restore(&snapshot);
+        Ok(())
+    }
+
+    /// Apply a binary frame diff, as encoded by `FrameDiff::to_binary` on
+    /// the server
+    ///
+    /// # Example
+    /// ```javascript
+    /// socket.onmessage = (event) => {
+    ///     renderer.applyFrameDiff(new Uint8Array(event.data));
+    ///     renderer.render();
+    /// };
+    /// ```
+    #[wasm_bindgen(js_name = "applyFrameDiff")]
+    pub fn apply_frame_diff(&mut self, diff: &[u8]) -> Result<(), JsValue> {
+        let diff = FrameDiff::from_binary(diff)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse frame diff: {e}")))?;
+        Ok(self.terminal.apply_frame_diff(&diff)?)
+    }
+
     /// Replace the current font atlas with a new static atlas.
     ///
     /// This method enables runtime font switching by loading a new `.atlas` file.
@@ -855,6 +936,8 @@ impl From<SelectionMode> for RustSelectionMode {
         match mode {
             SelectionMode::Block => RustSelectionMode::Block,
             SelectionMode::Linear => RustSelectionMode::Linear,
+            SelectionMode::Word => RustSelectionMode::Word,
+            SelectionMode::Line => RustSelectionMode::Line,
         }
     }
 }
@@ -864,6 +947,8 @@ impl From<RustSelectionMode> for SelectionMode {
         match mode {
             RustSelectionMode::Block => SelectionMode::Block,
             RustSelectionMode::Linear => SelectionMode::Linear,
+            RustSelectionMode::Word => SelectionMode::Word,
+            RustSelectionMode::Line => SelectionMode::Line,
             _ => unreachable!(),
         }
     }
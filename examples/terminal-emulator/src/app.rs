@@ -40,8 +40,6 @@ pub struct AppState {
     gl_state: GlState,
     grid: TerminalGrid,
     pub parser: vt100::Parser<TermCallbacks>,
-    prev_cursor: (u16, u16),
-    prev_show_cursor: bool,
     last_render: Instant,
     redraw_pending: bool,
     pty_master: Box<dyn portable_pty::MasterPty + Send>,
@@ -162,8 +160,6 @@ impl ApplicationHandler for App {
             gl_state,
             grid,
             parser,
-            prev_cursor: (0, 0),
-            prev_show_cursor: true,
             last_render: Instant::now(),
             redraw_pending: true,
             pty_master: pair.master,
@@ -292,12 +288,7 @@ impl ApplicationHandler for App {
             WindowEvent::RedrawRequested => {
                 drain_pty(state);
 
-                sync_terminal(
-                    &mut state.grid,
-                    &mut state.parser,
-                    &mut state.prev_cursor,
-                    &mut state.prev_show_cursor,
-                );
+                sync_terminal(&state.win.gl, &mut state.grid, &mut state.parser);
                 state
                     .grid
                     .flush_cells(&state.win.gl)
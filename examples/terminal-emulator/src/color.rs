@@ -45,10 +45,3 @@ pub fn color_to_rgb(color: vt100::Color, default: u32) -> u32 {
         vt100::Color::Rgb(r, g, b) => ((r as u32) << 16) | ((g as u32) << 8) | b as u32,
     }
 }
-
-pub fn dim_color(c: u32) -> u32 {
-    let r = ((c >> 16) & 0xff) / 2;
-    let g = ((c >> 8) & 0xff) / 2;
-    let b = (c & 0xff) / 2;
-    (r << 16) | (g << 8) | b
-}
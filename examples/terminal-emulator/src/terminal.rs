@@ -5,11 +5,11 @@ use std::{
     sync::{Arc, Mutex, mpsc},
 };
 
-use beamterm_core::{CellData, TerminalGrid};
+use beamterm_core::{CellAttributes, CellData, Cursor, CursorPosition, TerminalGrid};
 
 use crate::{
     app::AppState,
-    color::{DEFAULT_BG, DEFAULT_FG, color_to_rgb, dim_color},
+    color::{DEFAULT_BG, DEFAULT_FG, color_to_rgb},
 };
 
 /// Extracts beamterm glyph styling bits from vt100 attribute mode byte.
@@ -24,24 +24,19 @@ const fn into_glyph_bits(mode: u8) -> u16 {
     | (m << 10) & (1 << 13) // underline
 }
 
-fn convert_cell(cell: &'_ vt100::Cell, is_cursor: bool) -> CellData<'_> {
+fn convert_cell(cell: &'_ vt100::Cell) -> CellData<'_> {
     let contents = cell.contents();
     let symbol = if contents.is_empty() { " " } else { contents };
 
-    let mut fg = color_to_rgb(cell.fgcolor(), DEFAULT_FG);
-    let mut bg = color_to_rgb(cell.bgcolor(), DEFAULT_BG);
+    let fg = color_to_rgb(cell.fgcolor(), DEFAULT_FG);
+    let bg = color_to_rgb(cell.bgcolor(), DEFAULT_BG);
 
-    if cell.inverse() {
-        std::mem::swap(&mut fg, &mut bg);
-    }
-    if cell.dim() {
-        fg = dim_color(fg);
-    }
-    if is_cursor {
-        std::mem::swap(&mut fg, &mut bg);
-    }
+    let mut attributes = CellAttributes::empty();
+    attributes.set(CellAttributes::REVERSE, cell.inverse());
+    attributes.set(CellAttributes::DIM, cell.dim());
 
     CellData::new_with_style_bits(symbol, into_glyph_bits(cell.attrs().mode), fg, bg)
+        .with_attributes(attributes)
 }
 
 // terminal sync //
@@ -78,18 +73,24 @@ pub fn drain_pty(state: &mut AppState) -> bool {
 }
 
 pub fn sync_terminal(
+    gl: &glow::Context,
     grid: &mut TerminalGrid,
     parser: &mut vt100::Parser<TermCallbacks>,
-    prev_cursor: &mut (u16, u16),
-    prev_show_cursor: &mut bool,
 ) {
     let screen = parser.screen();
-    let cursor = screen.cursor_position();
-    let show_cursor = !screen.hide_cursor();
+    let (cursor_row, cursor_col) = screen.cursor_position();
     let (rows, _cols) = screen.size();
 
+    // the cursor is drawn by the grid's shader; this is a no-op when unchanged
+    grid.set_cursor(gl, Cursor {
+        position: CursorPosition::new(cursor_col, cursor_row),
+        color: DEFAULT_FG,
+        visible: !screen.hide_cursor(),
+        ..Cursor::default()
+    });
+
     let dirty = parser.screen_mut().take_dirty();
-    if !dirty.any() && cursor == *prev_cursor && show_cursor == *prev_show_cursor {
+    if !dirty.any() {
         return;
     }
 
@@ -102,44 +103,13 @@ pub fn sync_terminal(
             .cells()
             .iter()
             .enumerate()
-            .map(move |(col, cell)| {
-                let is_cursor = show_cursor && row_idx == cursor.0 && col as u16 == cursor.1;
-                (col as u16, row_idx, cell_data(cell, is_cursor))
-            })
+            .map(move |(col, cell)| (col as u16, row_idx, cell_data(cell)))
     }))
     .expect("failed to update cells");
-
-    // cursor overlay: repaint cells where cursor was and where it is now,
-    // unless already covered by dirty rows
-    let cursor_cells = [(show_cursor, cursor), (*prev_show_cursor, *prev_cursor)];
-    grid.update_cells_by_position(
-        cursor_cells
-            .into_iter()
-            .filter_map(|(visible, (row, col))| {
-                if dirty.is_dirty(row) {
-                    return None; // already handled above
-                }
-                let cell = screen.cell(row, col)?;
-                let is_cursor = visible && row == cursor.0 && col == cursor.1;
-                Some((col, row, cell_data(cell, is_cursor)))
-            }),
-    )
-    .expect("failed to update cursor cells");
-
-    *prev_cursor = cursor;
-    *prev_show_cursor = show_cursor;
 }
 
-fn cell_data(cell: &vt100::Cell, is_cursor: bool) -> CellData<'_> {
-    if cell.is_wide_continuation() {
-        if is_cursor {
-            CellData::new_with_style_bits(" ", 0, DEFAULT_BG, DEFAULT_FG)
-        } else {
-            SPACE
-        }
-    } else {
-        convert_cell(cell, is_cursor)
-    }
+fn cell_data(cell: &vt100::Cell) -> CellData<'_> {
+    if cell.is_wide_continuation() { SPACE } else { convert_cell(cell) }
 }
 
 // terminal callbacks //